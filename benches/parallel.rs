@@ -0,0 +1,43 @@
+//! Sampling a synthetic 1920x1080 frame into a 200x60 grid on one thread against splitting
+//! the rows across the rayon thread pool, with the cheapest and the costliest filter.
+//!
+//! Run with `cargo bench --bench parallel`.
+
+use ascii_vision::ascii::{AsciiConverter, FilterKind};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
+
+/// Gradients on every channel, so no two neighbouring cells sample the same color
+fn frame() -> Vec<u8> {
+    let mut frame = Vec::with_capacity((WIDTH * HEIGHT * 3) as usize);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            frame.extend_from_slice(&[(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8]);
+        }
+    }
+    frame
+}
+
+fn parallel_sampling(c: &mut Criterion) {
+    let frame = frame();
+    let mut group = c.benchmark_group("sampling");
+    group.throughput(Throughput::Elements(1));
+    for filter in [FilterKind::Nearest, FilterKind::Gaussian] {
+        for parallel in [false, true] {
+            let mut converter = AsciiConverter::new_dense(200, 60);
+            converter.set_color_enabled(true);
+            converter.set_filter(filter);
+            converter.set_parallel(parallel);
+            let name = if parallel { "parallel" } else { "serial" };
+            group.bench_function(BenchmarkId::new(name, format!("{filter:?}")), |b| {
+                b.iter(|| converter.convert_rgb_frame_direct(&frame, WIDTH, HEIGHT))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parallel_sampling);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Print converted camera frames from an async program, redrawing in place, until Ctrl-C.
+//!
+//! Run with `cargo run --example frame_stream`.
+
+use ascii_vision::{
+    ascii::{AsciiConverter, to_ansi_string},
+    config::CameraConfig,
+    stream::FrameStream,
+};
+use futures::StreamExt;
+
+#[tokio::main]
+async fn main() -> color_eyre::Result<()> {
+    let camera = CameraConfig {
+        fps: 15.0,
+        width: 640,
+        height: 480,
+        ..Default::default()
+    };
+    let mut converter = AsciiConverter::new_dense(80, 24);
+    converter.set_color_enabled(true);
+    let mut frames = FrameStream::open(&camera, converter);
+
+    print!("\x1b[2J");
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            frame = frames.next() => match frame {
+                Some(Ok(frame)) => print!("\x1b[H{}", to_ansi_string(&frame.grid)),
+                Some(Err(e)) => eprintln!("{e}"),
+                None => break,
+            },
+        }
+    }
+    // Dropping the stream closes the camera too
+    frames.close();
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Convert a frame with a custom preprocessing step: the hook inverts every pixel before
+//! the converter downscales it, so the gradient below comes out reversed.
+//!
+//! Run with `cargo run --example invert_hook`.
+
+use ascii_vision::ascii::AsciiConverter;
+
+fn main() {
+    let (width, height) = (64, 16);
+    // Horizontal gradient, dark on the left
+    let frame: Vec<u8> = (0..width * height)
+        .flat_map(|i| [((i % width) * 255 / (width - 1)) as u8; 3])
+        .collect();
+
+    let mut converter = AsciiConverter::new_dense(32, 8);
+    println!("Original:");
+    print_grid(&mut converter, &frame, width, height);
+
+    converter.set_frame_preprocessor(Some(Box::new(|rgb: &mut [u8], _width, _height| {
+        rgb.iter_mut().for_each(|channel| *channel = 255 - *channel);
+    })));
+    println!("Inverted by the hook:");
+    print_grid(&mut converter, &frame, width, height);
+}
+
+fn print_grid(converter: &mut AsciiConverter, frame: &[u8], width: u32, height: u32) {
+    for row in converter.convert_rgb_frame_direct(frame, width, height) {
+        println!("{}", row.iter().map(|cell| cell.ch).collect::<String>());
+    }
+}
@@ -0,0 +1,61 @@
+//! Compare monochrome conversion of YUYV frames straight from their luma against decoding to
+//! RGB first, on a synthetic 1280x720 frame.
+//!
+//! Run with `cargo run --release --example yuyv_benchmark`.
+
+use std::time::{Duration, Instant};
+
+use ascii_vision::{ascii::AsciiConverter, yuyv};
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    // Diagonal luma gradient with some chroma, so the decode has real work to do
+    let mut frame = Vec::with_capacity(yuyv::frame_len(WIDTH, HEIGHT));
+    for y in 0..HEIGHT {
+        for x in (0..WIDTH).step_by(2) {
+            let luma = |x: u32| (16 + (x + y) % 220) as u8;
+            frame.extend_from_slice(&[luma(x), (x % 256) as u8, luma(x + 1), (y % 256) as u8]);
+        }
+    }
+    let mut converter = AsciiConverter::new_dense(160, 48);
+    assert!(converter.accepts_luma());
+
+    let decoded = time(|| {
+        let rgb = yuyv::to_rgb(&frame);
+        converter.convert_rgb_frame_direct(&rgb, WIDTH, HEIGHT);
+    });
+    let direct = time(|| {
+        converter.convert_yuyv_frame(&frame, WIDTH, HEIGHT);
+    });
+
+    report("Decode to RGB, then convert", decoded);
+    report("Convert from luma", direct);
+    println!(
+        "Speedup: {:.1}x",
+        decoded.as_secs_f64() / direct.as_secs_f64()
+    );
+}
+
+/// Average time per frame
+fn time(mut convert: impl FnMut()) -> Duration {
+    // Warm up caches and the converter's lookup tables first
+    for _ in 0..10 {
+        convert();
+    }
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        convert();
+    }
+    started.elapsed() / ITERATIONS
+}
+
+fn report(name: &str, per_frame: Duration) {
+    println!(
+        "{name:<28} {:>8.3} ms/frame {:>8.0} fps",
+        per_frame.as_secs_f64() * 1000.0,
+        1.0 / per_frame.as_secs_f64()
+    );
+}
@@ -1,7 +1,11 @@
+use std::{path::PathBuf, time::Instant};
+
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+use crate::{app::Mode, ascii::CharacterSet, worker::ConvertedFrame};
+
+#[derive(Debug, Clone, PartialEq, Display, Serialize, Deserialize)]
 pub enum Action {
     Tick,
     Render,
@@ -12,24 +16,174 @@ pub enum Action {
     ClearScreen,
     Error(String),
     Help,
+    ToggleLogViewer,
+    SetMode(Mode),
+    PendingChord(String), // Keys typed so far towards a multi-key binding
     // Camera actions
     StartCamera,
     StopCamera,
     ToggleCamera,
-    CameraFrame(Vec<u8>, u32, u32), // Raw frame data with dimensions
+    /// Reopen the camera from scratch, e.g. after access was granted
+    RetryCamera,
+    /// Close the camera device so other apps can use it, keeping the last frame on screen
+    ReleaseCamera,
+    CameraReleased,
+    /// Freeze the picture while the camera keeps streaming, or carry on
+    TogglePause,
+    /// Capture was paused (true) or resumed, with the stream left open
+    CapturePaused(bool),
+    /// Raw RGB frame data with its dimensions and when the source captured it
+    #[serde(skip)]
+    CameraFrame(Vec<u8>, u32, u32, Instant),
+    /// Undecoded YUYV frame data with its dimensions and when the source captured it
+    #[serde(skip)]
+    CameraYuyvFrame(Vec<u8>, u32, u32, Instant),
+    /// The camera delivered a frame, counted for the capture rate
+    FrameCaptured,
+    /// App ticks per second, measured by the FPS counter
+    FpsUpdate(f64),
+    /// A frame converted on the conversion worker, ready to show
+    #[serde(skip)]
+    AsciiFrameReady(ConvertedFrame),
+    /// Whether the camera should send YUYV frames undecoded, as the display only needs luma
+    LumaCapture(bool),
     CameraError(String),
+    /// Captures kept failing, most likely because the camera was unplugged
+    CameraDisconnected,
+    // Test pattern source
+    ToggleTestPattern,
+    TestPatternStarted,
+    TestPatternStopped,
+    /// An image or video file stands in for the camera; carries its path
+    InputFileStarted(String),
+    /// The input video was paused (true) or resumed
+    InputFilePaused(bool),
+    ToggleDemo,
+    DemoFaster,
+    DemoSlower,
+    /// Demo mode is cycling settings every this many seconds; sent again on speed changes
+    DemoStarted(u64),
+    DemoStopped,
+    // Burst capture
+    Burst,
+    BurstFrame(PathBuf, Vec<u8>, u32, u32), // File stem for the frame, raw frame data with dimensions
+    BurstProgress(u32, u32),
+    BurstFinished(u32, u32, String),
+    // Photo mode: one still at the camera's highest resolution
+    Photo,
+    PhotoStarted,
+    PhotoFrame(PathBuf, Vec<u8>, u32, u32), // File stem for the photo, raw frame data with dimensions
+    PhotoSaved(String),
+    PhotoFailed(String),
+    /// Write the frame on screen as ANSI-colored text
+    SaveFrameAnsi,
+    /// Save the frame on screen as a PNG drawn with the bundled font
+    SaveSnapshotPng,
+    /// Start or stop appending every frame as plain text to a file or named pipe; relative
+    /// paths are in the data directory
+    ToggleStreamToFile(PathBuf),
     // Camera controls
     NextCamera,
     PreviousCamera,
     SetCamera(u32),
+    OpenCameraPicker,
+    /// Close the focused overlay component
+    CloseOverlay,
     // ASCII controls
     NextCharacterSet,
+    NextCellMode,
+    /// Cycle ASCII, half blocks, sextants, braille and edges
+    NextRenderMode,
+    PreviousRenderMode,
     PreviousCharacterSet,
     ToggleColor,
+    /// Cycle truecolor, 256 colors, 16 colors and monochrome
+    NextColorMode,
+    /// Cycle the single color the picture is drawn in
+    NextTint,
+    ToggleInvert,
     IncreaseScale,
     DecreaseScale,
+    SetScale(f32),
+    SetGamma(f32),
+    IncreaseGamma,
+    DecreaseGamma,
+    IncreaseContrast,
+    DecreaseContrast,
+    IncreaseBrightness,
+    DecreaseBrightness,
+    NextToneCurve,
+    NextScaleMode,
+    /// Turn the picture a quarter clockwise, for cameras mounted sideways
+    RotateCw,
+    /// Digital zoom: convert a smaller region around the center of the picture
+    ZoomIn,
+    ZoomOut,
+    /// Move the zoomed region across the picture
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    IncreaseCellAspect,
+    DecreaseCellAspect,
+    /// One character per drawable cell with the best filter, for the current terminal size
+    TogglePeakDetail,
+    /// Converter and display settings back to their startup values
+    ResetSettings,
+    NextFilter,
+    NextIndexRounding,
+    TogglePerceptualRamp,
+    NextAutoContrast,
+    CyclePreset,
+    ToggleEdgeOverlay,
+    ToggleEdgeDetect,
+    IncreaseEdgeStrength,
+    DecreaseEdgeStrength,
+    TogglePhosphor,
+    IncreasePhosphorDecay,
+    DecreasePhosphorDecay,
+    ToggleSmoothing,
+    IncreaseSmoothing,
+    DecreaseSmoothing,
+    IncreaseGrain,
+    DecreaseGrain,
+    ToggleGrainAnimation,
+    TogglePowerSaver,
+    ToggleAutoQuality,
+    /// Automatic quality is holding this many frames per second
+    AutoQualityStarted(u32),
+    AutoQualityStopped,
+    /// Index into `quality::QUALITY_SCALES` chosen by automatic quality
+    SetQualityLevel(u32),
+    ToggleDetailLevel,
+    IncreaseDetail,
+    DecreaseDetail,
+    // Named profiles
+    SaveProfile,
+    NextProfile,
+    NextEffect,
+    // Split view
+    ToggleChannelView,
+    ToggleSplitView,
+    CycleSplitComparison,
     // Resolution controls
     IncreaseResolution,
     DecreaseResolution,
     SetResolution(u32, u32),
+    SetTargetFps(u32),
+    IncreaseFps,
+    DecreaseFps,
+    // Hardware camera controls
+    IncreaseExposure,
+    DecreaseExposure,
+    /// The camera's exposure control is now at this value
+    ExposureChanged(i64),
+    // Typing exact values
+    EnterScale,
+    EnterGamma,
+    EnterFps,
+    EnterResolution,
+    GridResized(u32, u32), // ASCII grid size in cells
+    /// The character set, color mode and scale the user picked, for saving on quit
+    SettingsChanged(CharacterSet, bool, f32),
 }
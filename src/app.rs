@@ -1,16 +1,36 @@
-use color_eyre::Result;
-use crossterm::event::KeyEvent;
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use crossterm::event::{KeyEvent, MouseEventKind};
 use ratatui::prelude::Rect;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     action::Action,
-    camera::CameraCapture,
-    components::{Component, fps::FpsCounter, home::Home},
+    camera::{
+        CameraCapture, PermissionDenied, capture_resolution_for_grid, reconnect_index, step_fps,
+        step_resolution,
+    },
+    chord::ChordBuffer,
+    cli::Cli,
+    components::{
+        Component, camera_picker::CameraPicker, fps::FpsCounter, help::Help, home::Home,
+        log_viewer::LogViewer,
+    },
     config::Config,
+    demo::DemoScheduler,
+    frame::{FrameReader, shared_frame},
+    power,
+    quality::QualityController,
+    screen::{self, CaptureSource},
+    test_pattern::TestPattern,
     tui::{Event, Tui},
+    video::VideoPlayer,
 };
 
 pub struct App {
@@ -21,39 +41,160 @@ pub struct App {
     should_quit: bool,
     should_suspend: bool,
     mode: Mode,
-    last_tick_key_events: Vec<KeyEvent>,
+    chord: ChordBuffer,
+    demo: DemoScheduler,
+    frame: FrameReader,
+    /// Latest ASCII grid size and when it last changed, for picking a capture resolution
+    grid_size: Option<(u32, u32)>,
+    grid_changed_at: Instant,
+    resolution_settled: bool,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
     camera_capture: Option<CameraCapture>,
+    /// The camera was dropped after it stopped delivering frames, so the next start looks
+    /// for it again
+    camera_disconnected: bool,
+    /// Frames aren't captured, leaving the last one on screen with the stream still open
+    capture_paused: bool,
+    burst: Option<BurstState>,
+    photo: Option<PhotoState>,
+    test_pattern: Option<TestPattern>,
+    last_pattern_frame: Instant,
+    /// Image or video shown instead of the camera, from `camera.input`
+    input_image: Option<InputImage>,
+    video: Option<VideoPlayer>,
+    /// Capture rate to go back to while power saver is on
+    power_saver_fps: Option<f64>,
+    /// The display only needs luma, so YUYV frames can skip decoding
+    luma_capture: bool,
+    quality: QualityController,
+}
+
+/// How long the grid size must stay put before the camera is asked for a new resolution
+const RESOLUTION_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Progress of an in-flight burst capture
+struct BurstState {
+    total: u32,
+    captured: u32,
+    directory: PathBuf,
+    deadline: Instant,
+}
+
+/// Still image standing in for the camera
+struct InputImage {
+    image: image::RgbImage,
+    last_frame: Instant,
+}
+
+/// How long a photo may take, including both resolution switches
+const PHOTO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A photo waiting for its high-resolution frame
+struct PhotoState {
+    /// Resolution to stream at again once the photo is taken
+    restore: (u32, u32),
+    /// Resolution of the frame to keep; earlier frames may still be queued
+    expected: (u32, u32),
+    stem: PathBuf,
+    deadline: Instant,
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode {
     #[default]
     Home,
+    /// Text entry, keybindings are suspended
+    Prompt,
 }
 
 impl App {
-    pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
+    pub fn new(args: &Cli) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let mut config = Config::new()?;
+        args.apply_overrides(&mut config);
+        // Home owns the video and is the only writer; everyone else gets a reader
+        let (frame_writer, frame) = shared_frame();
+        let chord = ChordBuffer::new(Duration::from_millis(config.input.chord_timeout_ms));
+        let mut input_image = None;
+        let mut video = None;
+        match &config.camera.input {
+            // Anything that isn't a known image format is left to ffmpeg
+            Some(path) if image::ImageFormat::from_path(path).is_err() => {
+                video = Some(VideoPlayer::open(
+                    path,
+                    config.camera.fps,
+                    action_tx.clone(),
+                )?);
+            }
+            Some(path) => {
+                let image = image::open(path)
+                    .map_err(|e| eyre!("Failed to open input image {}: {e}", path.display()))?
+                    .to_rgb8();
+                info!(
+                    "Showing {} ({}x{}) instead of a camera",
+                    path.display(),
+                    image.width(),
+                    image.height()
+                );
+                input_image = Some(InputImage {
+                    image,
+                    last_frame: Instant::now(),
+                });
+            }
+            None if config.camera.source == CaptureSource::Screen => {
+                video = Some(screen::open_screen(
+                    config.camera.screen_region()?,
+                    config.camera.fps,
+                    action_tx.clone(),
+                )?);
+            }
+            None => {}
+        }
+        let demo = DemoScheduler::new(
+            Duration::from_secs(config.demo.interval_secs),
+            Duration::from_secs(config.demo.resume_after_secs),
+        );
         Ok(Self {
-            tick_rate,
-            frame_rate,
-            components: vec![Box::new(Home::new()), Box::new(FpsCounter::default())],
+            tick_rate: args.tick_rate,
+            frame_rate: args.frame_rate,
+            components: vec![
+                Box::new(Home::new(frame_writer)),
+                Box::new(FpsCounter::default()),
+                Box::new(LogViewer::new()),
+                // Last, so it draws over everything else
+                Box::new(Help::new()),
+            ],
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config,
             mode: Mode::Home,
-            last_tick_key_events: Vec::new(),
+            chord,
+            demo,
+            frame,
+            grid_size: None,
+            grid_changed_at: Instant::now(),
+            resolution_settled: true,
             action_tx,
             action_rx,
             camera_capture: None,
+            camera_disconnected: false,
+            capture_paused: false,
+            burst: None,
+            photo: None,
+            test_pattern: None,
+            last_pattern_frame: Instant::now(),
+            input_image,
+            video,
+            power_saver_fps: None,
+            luma_capture: false,
+            quality: QualityController::default(),
         })
     }
 
     pub async fn run(&mut self) -> Result<()> {
         let mut tui = Tui::new()?
-            // .mouse(true) // uncomment this line to enable mouse support
+            .mouse(self.config.ui.mouse)
             .tick_rate(self.tick_rate)
             .frame_rate(self.frame_rate);
         tui.enter()?;
@@ -64,9 +205,31 @@ impl App {
         for component in self.components.iter_mut() {
             component.register_config_handler(self.config.clone())?;
         }
+        for component in self.components.iter_mut() {
+            component.register_frame_handler(self.frame.clone())?;
+        }
         for component in self.components.iter_mut() {
             component.init(tui.size()?)?;
         }
+        if self.config.power.auto_on_battery && power::on_battery() == Some(true) {
+            info!("Running on battery, starting in power saver mode");
+            self.action_tx.send(Action::TogglePowerSaver)?;
+        }
+        if self.config.quality.auto {
+            self.action_tx.send(Action::ToggleAutoQuality)?;
+        }
+        if let Some(path) = &self.config.camera.input {
+            self.action_tx
+                .send(Action::InputFileStarted(path.display().to_string()))?;
+        } else if self.config.camera.source == CaptureSource::Screen {
+            self.action_tx
+                .send(Action::InputFileStarted("the screen".to_string()))?;
+        } else if self.config.camera.auto_start {
+            // Queued after init, so the camera list and grid size are known; a failure shows
+            // up as a `CameraError` like one from pressing the key
+            info!("Starting the camera on launch");
+            self.action_tx.send(Action::ToggleCamera)?;
+        }
 
         let action_tx = self.action_tx.clone();
         loop {
@@ -76,7 +239,6 @@ impl App {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
                 action_tx.send(Action::ClearScreen)?;
-                // tui.mouse(true);
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -84,6 +246,7 @@ impl App {
             }
         }
         tui.exit()?;
+        self.save_settings();
         Ok(())
     }
 
@@ -92,15 +255,29 @@ impl App {
             return Ok(());
         };
         let action_tx = self.action_tx.clone();
+        // The top of the focus stack takes all keys, bypassing keybindings
+        let focused = self.components.iter().rposition(|c| c.has_focus());
         match event {
             Event::Quit => action_tx.send(Action::Quit)?,
             Event::Tick => action_tx.send(Action::Tick)?,
             Event::Render => action_tx.send(Action::Render)?,
             Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-            Event::Key(key) => self.handle_key_event(key)?,
+            Event::Key(key) if focused.is_none() => self.handle_key_event(key)?,
+            Event::Mouse(mouse)
+                if focused.is_none()
+                    && matches!(
+                        mouse.kind,
+                        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                    ) =>
+            {
+                self.demo.pause_for_input(Instant::now())
+            }
             _ => {}
         }
-        for component in self.components.iter_mut() {
+        for (i, component) in self.components.iter_mut().enumerate() {
+            if matches!(event, Event::Key(_) | Event::Mouse(_)) && focused.is_some_and(|f| f != i) {
+                continue;
+            }
             if let Some(action) = component.handle_events(Some(event.clone()))? {
                 action_tx.send(action)?;
             }
@@ -113,22 +290,16 @@ impl App {
         let Some(keymap) = self.config.keybindings.get(&self.mode) else {
             return Ok(());
         };
-        match keymap.get(&vec![key]) {
-            Some(action) => {
-                info!("Got action: {action:?}");
-                action_tx.send(action.clone())?;
-            }
-            _ => {
-                // If the key was not handled as a single key action,
-                // then consider it for multi-key combinations.
-                self.last_tick_key_events.push(key);
-
-                // Check for multi-key combinations
-                if let Some(action) = keymap.get(&self.last_tick_key_events) {
-                    info!("Got action: {action:?}");
-                    action_tx.send(action.clone())?;
-                }
-            }
+        let now = Instant::now();
+        // Manual input wins over the demo for a while
+        self.demo.pause_for_input(now);
+        let was_pending = !self.chord.is_empty();
+        if let Some(action) = self.chord.resolve(keymap, key, now) {
+            info!("Got action: {action:?}");
+            action_tx.send(action)?;
+        }
+        if was_pending || !self.chord.is_empty() {
+            action_tx.send(Action::PendingChord(self.chord.display()))?;
         }
         Ok(())
     }
@@ -141,7 +312,7 @@ impl App {
         // Separate camera frames from other actions for prioritized processing
         while let Ok(action) = self.action_rx.try_recv() {
             match action {
-                Action::CameraFrame(_, _, _) => camera_frames.push(action),
+                Action::CameraFrame(..) | Action::CameraYuyvFrame(..) => camera_frames.push(action),
                 _ => other_actions.push(action),
             }
         }
@@ -152,6 +323,8 @@ impl App {
         }
 
         // Process only the latest camera frame to prevent backup
+        self.quality
+            .record_dropped(camera_frames.len().saturating_sub(1));
         if let Some(latest_frame) = camera_frames.into_iter().last() {
             self.process_action(latest_frame, tui)?;
         }
@@ -160,38 +333,165 @@ impl App {
     }
 
     fn process_action(&mut self, action: Action, tui: &mut Tui) -> Result<()> {
-        if action != Action::Tick && action != Action::Render {
+        if !matches!(
+            action,
+            Action::Tick
+                | Action::Render
+                | Action::FrameCaptured
+                | Action::FpsUpdate(_)
+                | Action::AsciiFrameReady(_)
+        ) {
             debug!("{action:?}");
         }
         match action {
             Action::Tick => {
-                self.last_tick_key_events.drain(..);
+                if self.chord.expire(Instant::now()) {
+                    self.action_tx.send(Action::PendingChord(String::new()))?;
+                }
                 // Capture camera frame on tick if camera is active
                 // Only capture if no frame is currently being processed
+                // A burst or photo still gets its frames while the picture is paused
+                let capturing =
+                    !self.capture_paused || self.burst.is_some() || self.photo.is_some();
                 if let Some(ref mut camera) = self.camera_capture
                     && camera.is_active()
+                    && capturing
                 {
                     // Try to capture frame, but don't block if it fails
                     let _ = camera.capture_frame();
                 }
+                self.emit_test_pattern_frame()?;
+                self.emit_input_image_frame()?;
+                if let Some(action) = self.demo.tick(Instant::now()) {
+                    self.action_tx.send(action)?;
+                }
+                self.update_capture_resolution()?;
+                self.update_quality()?;
+                if let Some(ref burst) = self.burst {
+                    let camera_active = self
+                        .camera_capture
+                        .as_ref()
+                        .is_some_and(CameraCapture::is_active);
+                    // Stop early if the camera went away or couldn't keep up,
+                    // keeping whatever was captured so far
+                    if !camera_active || Instant::now() >= burst.deadline {
+                        self.finish_burst()?;
+                    }
+                }
+                if self
+                    .photo
+                    .as_ref()
+                    .is_some_and(|p| Instant::now() >= p.deadline)
+                {
+                    self.finish_photo()?;
+                    self.action_tx.send(Action::PhotoFailed(
+                        "Photo timed out waiting for the camera".to_string(),
+                    ))?;
+                }
             }
             Action::Quit => self.should_quit = true,
             Action::Suspend => self.should_suspend = true,
             Action::Resume => self.should_suspend = false,
+            Action::SetMode(mode) => {
+                // A chord half-typed in one mode means nothing in the next
+                self.mode = mode;
+                self.chord.clear();
+            }
             Action::ClearScreen => tui.terminal.clear()?,
             Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
             Action::Render => self.render(tui)?,
             Action::ToggleCamera => {
-                self.handle_camera_toggle()?;
+                // A video plays and pauses in place of the camera
+                if let Some(ref video) = self.video {
+                    let paused = video.toggle_pause();
+                    info!("Video {}", if paused { "paused" } else { "playing" });
+                    self.action_tx.send(Action::InputFilePaused(paused))?;
+                } else {
+                    self.handle_camera_toggle()?;
+                }
             }
+            Action::TogglePause => self.handle_pause_toggle()?,
+            Action::RetryCamera => self.retry_camera()?,
+            Action::ReleaseCamera => self.release_camera()?,
+            Action::CameraDisconnected => self.handle_camera_disconnected(),
             Action::StartCamera => {
                 // This action is sent to update the UI after camera starts
-                // Don't trigger any camera logic here
+                // Don't trigger any camera logic here, just let the resolution adapt on a later tick
+                self.resolution_settled = false;
+                self.capture_paused = false;
+            }
+            Action::SettingsChanged(character_set, color, scale) => {
+                self.config.ascii.character_set = character_set;
+                self.config.ascii.color = color;
+                self.config.ascii.scale = scale;
+            }
+            Action::GridResized(width, height) => {
+                self.grid_size = Some((width, height));
+                self.grid_changed_at = Instant::now();
+                self.resolution_settled = false;
             }
             Action::StopCamera => {
                 // This action is sent to update the UI after camera stops
                 // Don't trigger any camera logic here
             }
+            Action::ToggleTestPattern => self.handle_test_pattern_toggle()?,
+            Action::ToggleDemo => self.handle_demo_toggle()?,
+            Action::DemoFaster => {
+                self.demo.faster(Instant::now());
+                self.report_demo_state()?;
+            }
+            Action::DemoSlower => {
+                self.demo.slower(Instant::now());
+                self.report_demo_state()?;
+            }
+            Action::SetCamera(index) => self.select_camera(index)?,
+            Action::OpenCameraPicker => self.open_overlay(Box::new(CameraPicker::new()), tui)?,
+            Action::CloseOverlay => {
+                if let Some(top) = self.components.iter().rposition(|c| c.has_focus()) {
+                    self.components.remove(top);
+                }
+            }
+            Action::SetResolution(width, height) => self.set_capture_resolution(width, height)?,
+            Action::IncreaseResolution => self.step_capture_resolution(true)?,
+            Action::DecreaseResolution => self.step_capture_resolution(false)?,
+            Action::SetTargetFps(fps) => self.set_target_fps(fps as f64)?,
+            Action::IncreaseFps => self.step_capture_fps(true)?,
+            Action::DecreaseFps => self.step_capture_fps(false)?,
+            Action::IncreaseExposure => self.adjust_exposure(1)?,
+            Action::DecreaseExposure => self.adjust_exposure(-1)?,
+            Action::TogglePowerSaver => self.toggle_power_saver()?,
+            Action::ToggleAutoQuality => {
+                if self.quality.toggle(Instant::now(), self.frame.sequence()) {
+                    let target = self.quality_target_fps().round() as u32;
+                    info!("Auto quality on, holding {} fps", target);
+                    self.action_tx.send(Action::AutoQualityStarted(target))?;
+                } else {
+                    info!("Auto quality off");
+                    self.action_tx.send(Action::AutoQualityStopped)?;
+                }
+            }
+            // Reset ends power saver mode and auto quality in Home, so the capture rate and
+            // the controller go back too
+            Action::ResetSettings => {
+                if let Some(fps) = self.power_saver_fps.take() {
+                    self.set_target_fps(fps)?;
+                }
+                if self.quality.is_running() {
+                    self.quality.toggle(Instant::now(), self.frame.sequence());
+                }
+            }
+            Action::LumaCapture(enabled) => {
+                self.luma_capture = enabled;
+                self.sync_luma_capture();
+            }
+            Action::Burst => self.start_burst()?,
+            Action::CameraFrame(ref data, width, height, _) if self.burst.is_some() => {
+                self.record_burst_frame(data, width, height)?;
+            }
+            Action::Photo => self.start_photo()?,
+            Action::CameraFrame(ref data, width, height, _) if self.photo.is_some() => {
+                self.record_photo_frame(data, width, height)?;
+            }
             _ => {}
         }
         for component in self.components.iter_mut() {
@@ -221,11 +521,380 @@ impl App {
         Ok(())
     }
 
+    /// Keep the character set, color, scale and camera for the next launch; a config
+    /// directory that can't be written only costs that, so it's logged rather than fatal
+    fn save_settings(&self) {
+        if !self.config.ui.remember_settings {
+            return;
+        }
+        match self.config.save_session() {
+            Ok(()) => debug!("Saved settings for the next launch"),
+            Err(e) => warn!("Failed to save settings for the next launch: {e}"),
+        }
+    }
+
+    /// Match the capture resolution to the grid once its size has settled
+    fn update_capture_resolution(&mut self) -> Result<()> {
+        if self.resolution_settled
+            || self.photo.is_some()
+            || !self.config.camera.auto_resolution
+            || self.grid_changed_at.elapsed() < RESOLUTION_DEBOUNCE
+        {
+            return Ok(());
+        }
+        let Some((grid_width, grid_height)) = self.grid_size else {
+            return Ok(());
+        };
+        let Some(camera) = self.camera_capture.as_mut().filter(|c| c.is_active()) else {
+            return Ok(());
+        };
+        // Settle even on failure so a camera that refuses doesn't get asked every tick
+        self.resolution_settled = true;
+
+        let Some(current) = camera.get_resolution() else {
+            return Ok(());
+        };
+        let supported = camera.supported_resolutions();
+        let target = capture_resolution_for_grid(grid_width, grid_height, current, &supported);
+        if target == current {
+            return Ok(());
+        }
+        debug!(
+            "Grid {}x{} wants capture at {}x{} (currently {}x{})",
+            grid_width, grid_height, target.0, target.1, current.0, current.1
+        );
+        if let Err(e) = camera.set_resolution(target.0, target.1) {
+            warn!("Failed to change capture resolution: {e}");
+        }
+        Ok(())
+    }
+
+    /// Add a component on top of the others, giving it focus if it asks for it
+    fn open_overlay(&mut self, mut component: Box<dyn Component>, tui: &mut Tui) -> Result<()> {
+        component.register_action_handler(self.action_tx.clone())?;
+        component.register_config_handler(self.config.clone())?;
+        component.register_frame_handler(self.frame.clone())?;
+        component.init(tui.size()?)?;
+        self.components.push(component);
+        Ok(())
+    }
+
+    /// Use the camera with this system index, reopening it if one is running
+    fn select_camera(&mut self, index: u32) -> Result<()> {
+        // An index with no device behind it would only fail deep in the backend, so check
+        // it against a fresh list first and leave the current camera alone
+        match CameraCapture::list_camera_names(
+            self.config.camera.backend,
+            &self.config.camera.camera_filter(),
+        ) {
+            Ok(cameras) if !cameras.iter().any(|(id, _)| *id == index) => {
+                let detected = cameras
+                    .iter()
+                    .map(|(id, name)| format!("{id}: {name}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let message = if cameras.is_empty() {
+                    format!("Camera {index} not found, no cameras detected")
+                } else {
+                    format!("Camera {index} not found (detected {detected})")
+                };
+                warn!("{message}");
+                self.action_tx.send(Action::Error(message))?;
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to list cameras, trying camera {index} anyway: {e}"),
+        }
+        self.config.camera.default_camera_index = index;
+        if self.camera_capture.as_ref().is_some_and(|c| c.is_active()) {
+            self.retry_camera()?;
+        } else if let Some(mut camera) = self.camera_capture.take() {
+            // A stopped camera would otherwise be restarted on the old device
+            camera.cleanup();
+        }
+        Ok(())
+    }
+
+    /// Capture at an exact resolution, which also stops it adapting to the grid size
+    fn set_capture_resolution(&mut self, width: u32, height: u32) -> Result<()> {
+        self.config.camera.width = width;
+        self.config.camera.height = height;
+        self.config.camera.auto_resolution = false;
+        let Some(camera) = self.camera_capture.as_mut() else {
+            // Used the next time the camera opens
+            return Ok(());
+        };
+        match camera.set_resolution(width, height) {
+            Ok(actual) if actual != (width, height) => {
+                let message = format!(
+                    "Camera chose {}x{} instead of {}x{}",
+                    actual.0, actual.1, width, height
+                );
+                warn!("{message}");
+                self.action_tx.send(Action::Error(message))?;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Failed to change capture resolution: {e}");
+                self.action_tx.send(Action::CameraError(format!(
+                    "Failed to set resolution {width}x{height}: {e}"
+                )))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask for the next capture resolution up or down, through `SetResolution` so it's
+    /// reported and kept like a typed one
+    fn step_capture_resolution(&mut self, up: bool) -> Result<()> {
+        let configured = (self.config.camera.width, self.config.camera.height);
+        let (current, supported) = match self.camera_capture.as_mut() {
+            Some(camera) => (
+                camera.get_resolution().unwrap_or(configured),
+                camera.supported_resolutions(),
+            ),
+            None => (configured, Vec::new()),
+        };
+        let action = match step_resolution(current, &supported, up) {
+            Some((width, height)) => Action::SetResolution(width, height),
+            None => Action::Error(format!(
+                "Already at the {} capture resolution, {}x{}",
+                if up { "highest" } else { "lowest" },
+                current.0,
+                current.1
+            )),
+        };
+        self.action_tx.send(action)?;
+        Ok(())
+    }
+
+    /// Change the capture rate; an open camera is reopened since the rate is negotiated then
+    fn set_target_fps(&mut self, fps: f64) -> Result<()> {
+        if fps == self.config.camera.fps {
+            return Ok(());
+        }
+        self.config.camera.fps = fps;
+        if let Some(ref mut camera) = self.camera_capture {
+            camera.set_target_fps(fps);
+        }
+        if self.camera_capture.as_ref().is_some_and(|c| c.is_active()) {
+            self.retry_camera()?;
+        }
+        Ok(())
+    }
+
+    /// Step the camera's hardware exposure, noting in the status bar when it can't be changed
+    fn adjust_exposure(&mut self, steps: i64) -> Result<()> {
+        let Some(ref mut camera) = self.camera_capture else {
+            self.action_tx.send(Action::Error(
+                "Start the camera to adjust exposure".to_string(),
+            ))?;
+            return Ok(());
+        };
+        let action = match camera.adjust_exposure(steps) {
+            Some(value) => Action::ExposureChanged(value),
+            None => Action::Error("This camera's exposure can't be adjusted".to_string()),
+        };
+        self.action_tx.send(action)?;
+        Ok(())
+    }
+
+    /// Move the capture rate one step along [`FPS_LADDER`](crate::camera::FPS_LADDER)
+    fn step_capture_fps(&mut self, up: bool) -> Result<()> {
+        let current = self.config.camera.fps;
+        let action = match step_fps(current, up) {
+            Some(fps) => Action::SetTargetFps(fps),
+            None => Action::Error(format!(
+                "Already at the {} capture rate, {current:.0} fps",
+                if up { "highest" } else { "lowest" }
+            )),
+        };
+        self.action_tx.send(action)?;
+        Ok(())
+    }
+
+    /// Capture at the power saver rate, or back at the rate from before
+    ///
+    /// Home swaps its own settings on the same action, so both halves switch together.
+    fn toggle_power_saver(&mut self) -> Result<()> {
+        match self.power_saver_fps.take() {
+            Some(fps) => self.set_target_fps(fps),
+            None => {
+                let fps = self.config.camera.fps;
+                self.power_saver_fps = Some(fps);
+                self.set_target_fps(fps.min(self.config.power.fps))
+            }
+        }
+    }
+
+    /// Frame rate auto quality aims for; the capture rate caps what can be reached
+    fn quality_target_fps(&self) -> f64 {
+        self.config.quality.target_fps.min(self.config.camera.fps)
+    }
+
+    /// Step the grid detail when auto quality sees the frame rate sag or recover. Power
+    /// saver caps the conversion rate on purpose, so the controller sits it out
+    fn update_quality(&mut self) -> Result<()> {
+        if self.power_saver_fps.is_some() {
+            return Ok(());
+        }
+        let target = self.quality_target_fps();
+        if let Some(level) = self
+            .quality
+            .tick(Instant::now(), self.frame.sequence(), target)
+        {
+            info!("Auto quality level {} at a {:.0} fps target", level, target);
+            self.action_tx.send(Action::SetQualityLevel(level as u32))?;
+        }
+        Ok(())
+    }
+
+    /// Drop the camera device entirely, unlike stopping which keeps the handle open
+    fn release_camera(&mut self) -> Result<()> {
+        let Some(mut camera) = self.camera_capture.take() else {
+            debug!("No camera to release");
+            return Ok(());
+        };
+        camera.cleanup();
+        info!("Camera released");
+        self.action_tx.send(Action::CameraReleased)?;
+        Ok(())
+    }
+
+    /// Let go of a camera that stopped delivering frames; it's looked for again on the
+    /// next start
+    fn handle_camera_disconnected(&mut self) {
+        if let Some(mut camera) = self.camera_capture.take() {
+            camera.cleanup();
+        }
+        self.camera_disconnected = true;
+        info!("Camera disconnected, waiting for the user to reconnect");
+    }
+
+    /// Enumerate the cameras again after a disconnect and pick the one to reopen, returning
+    /// false when none is attached
+    fn find_reconnected_camera(&mut self) -> Result<bool> {
+        let previous = self.config.camera.default_camera_index;
+        let cameras = match CameraCapture::list_camera_names(
+            self.config.camera.backend,
+            &self.config.camera.camera_filter(),
+        ) {
+            Ok(cameras) => cameras,
+            Err(e) => {
+                // Opening may still work where listing doesn't, so try the same camera
+                warn!("Failed to list cameras, reopening camera {previous}: {e}");
+                return Ok(true);
+            }
+        };
+        let Some(index) = reconnect_index(previous, &cameras) else {
+            self.action_tx.send(Action::Error(
+                "No camera detected, plug one in and press SPACE".to_string(),
+            ))?;
+            return Ok(false);
+        };
+        if index != previous {
+            let name = cameras
+                .iter()
+                .find(|(id, _)| *id == index)
+                .map_or("", |(_, name)| name.as_str());
+            info!("Camera {previous} is gone, reconnecting to camera {index}: {name}");
+            self.config.camera.default_camera_index = index;
+        }
+        Ok(true)
+    }
+
+    /// Whether any camera is attached; a failed enumeration counts as yes, since opening
+    /// may still work where listing doesn't
+    fn cameras_available(&self) -> bool {
+        match CameraCapture::list_camera_names(
+            self.config.camera.backend,
+            &self.config.camera.camera_filter(),
+        ) {
+            Ok(cameras) => !cameras.is_empty(),
+            Err(e) => {
+                warn!("Failed to list cameras: {e}");
+                true
+            }
+        }
+    }
+
+    /// Stop or resume taking frames from the running camera without closing its stream
+    fn handle_pause_toggle(&mut self) -> Result<()> {
+        // A video pauses the same way as with SPACE
+        if let Some(ref video) = self.video {
+            let paused = video.toggle_pause();
+            info!("Video {}", if paused { "paused" } else { "playing" });
+            self.action_tx.send(Action::InputFilePaused(paused))?;
+            return Ok(());
+        }
+        if !self
+            .camera_capture
+            .as_ref()
+            .is_some_and(CameraCapture::is_active)
+        {
+            self.action_tx
+                .send(Action::Error("Start the camera to pause it".to_string()))?;
+            return Ok(());
+        }
+        self.capture_paused = !self.capture_paused;
+        info!(
+            "Capture {}",
+            if self.capture_paused {
+                "paused"
+            } else {
+                "resumed"
+            }
+        );
+        self.action_tx
+            .send(Action::CapturePaused(self.capture_paused))?;
+        Ok(())
+    }
+
+    /// Open the camera from scratch, e.g. after the user granted access
+    fn retry_camera(&mut self) -> Result<()> {
+        info!("Retrying camera");
+        if let Some(mut camera) = self.camera_capture.take() {
+            camera.stop();
+        }
+        self.handle_camera_toggle()
+    }
+
     fn handle_camera_toggle(&mut self) -> Result<()> {
         debug!("handle_camera_toggle called");
+        if let Some(path) = &self.config.camera.input {
+            self.action_tx.send(Action::Error(format!(
+                "Showing {}, the camera stays off",
+                path.display()
+            )))?;
+            return Ok(());
+        }
+        if self.config.camera.source == CaptureSource::Screen {
+            self.action_tx.send(Action::Error(
+                "Showing the screen, the camera stays off".to_string(),
+            ))?;
+            return Ok(());
+        }
+        if self.camera_capture.is_none() && !self.camera_disconnected && !self.cameras_available() {
+            // Opening a camera that isn't there fails with a backend error that doesn't say so
+            self.action_tx
+                .send(Action::CameraError("No cameras available".to_string()))?;
+            return Ok(());
+        }
+        if self.test_pattern.take().is_some() {
+            // The camera replaces the test pattern as the frame source
+            self.action_tx.send(Action::TestPatternStopped)?;
+        }
         if self.camera_capture.is_none() {
+            if self.camera_disconnected {
+                if !self.find_reconnected_camera()? {
+                    return Ok(());
+                }
+                self.camera_disconnected = false;
+            }
             debug!("Creating new camera capture");
-            let mut camera = CameraCapture::new();
+            let mut camera = CameraCapture::new(self.config.camera.backend);
+            camera.set_yuyv(self.config.camera.yuyv);
+            camera.set_target_fps(self.config.camera.fps);
 
             debug!(
                 "Initializing camera with index: {}, resolution: {}x{}",
@@ -238,26 +907,34 @@ impl App {
                 self.config.camera.default_camera_index,
                 self.config.camera.width,
                 self.config.camera.height,
+                self.config.camera.fps.round() as u32,
+                self.config.camera.resolution_request,
                 self.action_tx.clone(),
             ) {
                 Ok(()) => match camera.start() {
                     Ok(()) => {
                         info!("Camera started successfully");
                         self.camera_capture = Some(camera);
+                        self.sync_luma_capture();
                         // Send StartCamera action to update UI
                         self.action_tx.send(Action::StartCamera)?;
                     }
                     Err(e) => {
                         error!("Failed to start camera: {e}");
                         self.action_tx
-                            .send(Action::CameraError(format!("Failed to start camera: {e}")))?;
+                            .send(Action::CameraError(camera_error_message(
+                                "Failed to start camera",
+                                &e,
+                            )))?;
                     }
                 },
                 Err(e) => {
                     error!("Failed to initialize camera: {e}");
-                    self.action_tx.send(Action::CameraError(format!(
-                        "Failed to initialize camera: {e}"
-                    )))?;
+                    self.action_tx
+                        .send(Action::CameraError(camera_error_message(
+                            "Failed to initialize camera",
+                            &e,
+                        )))?;
                 }
             }
         } else if let Some(ref mut camera) = self.camera_capture {
@@ -274,9 +951,11 @@ impl App {
                     }
                     Err(e) => {
                         error!("Failed to restart camera: {e}");
-                        self.action_tx.send(Action::CameraError(format!(
-                            "Failed to restart camera: {e}"
-                        )))?;
+                        self.action_tx
+                            .send(Action::CameraError(camera_error_message(
+                                "Failed to restart camera",
+                                &e,
+                            )))?;
                     }
                 }
             } else {
@@ -295,4 +974,314 @@ impl App {
 
         Ok(())
     }
+
+    fn handle_test_pattern_toggle(&mut self) -> Result<()> {
+        if self.test_pattern.take().is_some() {
+            info!("Test pattern stopped");
+            if let Some(ref video) = self.video {
+                video.set_held(false);
+            }
+            self.action_tx.send(Action::TestPatternStopped)?;
+            return Ok(());
+        }
+
+        // The test pattern replaces the camera or video as the frame source
+        if let Some(ref video) = self.video {
+            video.set_held(true);
+        }
+        if let Some(ref mut camera) = self.camera_capture
+            && camera.is_active()
+        {
+            camera.stop();
+            self.action_tx.send(Action::StopCamera)?;
+        }
+        let (width, height) = (self.config.camera.width, self.config.camera.height);
+        info!("Test pattern started at {}x{}", width, height);
+        self.test_pattern = Some(TestPattern::new(width, height));
+        self.action_tx.send(Action::TestPatternStarted)?;
+        Ok(())
+    }
+
+    fn handle_demo_toggle(&mut self) -> Result<()> {
+        let running = self.demo.toggle(Instant::now());
+        info!("Demo mode {}", if running { "started" } else { "stopped" });
+        // Without a source there's nothing to show off, so bring up the test pattern
+        let camera_active = self.camera_capture.as_ref().is_some_and(|c| c.is_active());
+        if running
+            && !camera_active
+            && self.test_pattern.is_none()
+            && self.video.is_none()
+            && self.input_image.is_none()
+        {
+            self.handle_test_pattern_toggle()?;
+        }
+        self.report_demo_state()
+    }
+
+    fn report_demo_state(&self) -> Result<()> {
+        let action = if self.demo.is_running() {
+            Action::DemoStarted(self.demo.interval().as_secs())
+        } else {
+            Action::DemoStopped
+        };
+        self.action_tx.send(action)?;
+        Ok(())
+    }
+
+    /// Feed a test pattern frame through the camera frame path at the configured rate
+    fn emit_test_pattern_frame(&mut self) -> Result<()> {
+        let Some(ref pattern) = self.test_pattern else {
+            return Ok(());
+        };
+        let interval = Duration::from_secs_f64(1.0 / self.config.camera.fps.max(1.0));
+        if self.last_pattern_frame.elapsed() < interval {
+            return Ok(());
+        }
+        self.last_pattern_frame = Instant::now();
+        self.action_tx.send(Action::CameraFrame(
+            pattern.frame(),
+            pattern.width(),
+            pattern.height(),
+            Instant::now(),
+        ))?;
+        Ok(())
+    }
+
+    /// Feed the input image through the camera frame path at the camera rate, so every
+    /// setting change, effect and split view applies to it like to a live feed
+    fn emit_input_image_frame(&mut self) -> Result<()> {
+        // The test pattern takes over while it runs
+        if self.test_pattern.is_some() {
+            return Ok(());
+        }
+        let Some(ref mut input) = self.input_image else {
+            return Ok(());
+        };
+        let interval = Duration::from_secs_f64(1.0 / self.config.camera.fps.max(1.0));
+        if input.last_frame.elapsed() < interval {
+            return Ok(());
+        }
+        input.last_frame = Instant::now();
+        self.action_tx.send(Action::CameraFrame(
+            input.image.as_raw().clone(),
+            input.image.width(),
+            input.image.height(),
+            Instant::now(),
+        ))?;
+        Ok(())
+    }
+
+    fn start_burst(&mut self) -> Result<()> {
+        if self.burst.is_some() {
+            debug!("Burst already in progress");
+            return Ok(());
+        }
+        let Some(ref mut camera) = self.camera_capture else {
+            return Ok(());
+        };
+        if !camera.is_active() {
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let directory = self
+            .config
+            .config
+            .data_dir
+            .join("bursts")
+            .join(format!("burst-{timestamp}"));
+        std::fs::create_dir_all(&directory)?;
+
+        // Give a slow camera twice the nominal time (plus a second of slack) before giving up
+        let total = self.config.camera.burst_count.max(1);
+        let nominal = Duration::from_secs_f64(total as f64 / self.config.camera.fps.max(1.0));
+        let deadline = Instant::now() + nominal * 2 + Duration::from_secs(1);
+
+        info!("Starting burst of {} frames into {:?}", total, directory);
+        camera.set_burst_mode(true);
+        self.burst = Some(BurstState {
+            total,
+            captured: 0,
+            directory,
+            deadline,
+        });
+        self.sync_luma_capture();
+        self.action_tx.send(Action::BurstProgress(0, total))?;
+        Ok(())
+    }
+
+    fn record_burst_frame(&mut self, data: &[u8], width: u32, height: u32) -> Result<()> {
+        let Some(ref mut burst) = self.burst else {
+            return Ok(());
+        };
+
+        let stem = burst
+            .directory
+            .join(format!("frame-{:03}", burst.captured + 1));
+        let png_path = stem.with_extension("png");
+        let frame = data.to_vec();
+        // Encode off the main loop so the next capture isn't delayed
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) =
+                image::save_buffer(&png_path, &frame, width, height, image::ColorType::Rgb8)
+            {
+                error!("Failed to save burst frame {:?}: {}", png_path, e);
+            }
+        });
+        // Let Home write the ASCII render of the same frame
+        self.action_tx
+            .send(Action::BurstFrame(stem, data.to_vec(), width, height))?;
+
+        burst.captured += 1;
+        self.action_tx
+            .send(Action::BurstProgress(burst.captured, burst.total))?;
+        if burst.captured >= burst.total {
+            self.finish_burst()?;
+        }
+        Ok(())
+    }
+
+    fn finish_burst(&mut self) -> Result<()> {
+        let Some(burst) = self.burst.take() else {
+            return Ok(());
+        };
+        if let Some(ref mut camera) = self.camera_capture {
+            camera.set_burst_mode(false);
+        }
+        self.sync_luma_capture();
+        if burst.captured < burst.total {
+            warn!(
+                "Burst timed out after {} of {} frames",
+                burst.captured, burst.total
+            );
+        }
+        info!("Burst finished: {} frames", burst.captured);
+        self.action_tx.send(Action::BurstFinished(
+            burst.captured,
+            burst.total,
+            burst.directory.display().to_string(),
+        ))?;
+        Ok(())
+    }
+
+    /// Switch the camera to its highest resolution for a single still
+    fn start_photo(&mut self) -> Result<()> {
+        if self.photo.is_some() || self.burst.is_some() {
+            debug!("Capture already in progress");
+            return Ok(());
+        }
+        let Some(camera) = self.camera_capture.as_mut().filter(|c| c.is_active()) else {
+            return Ok(());
+        };
+        let Some(current) = camera.get_resolution() else {
+            return Ok(());
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let directory = self.config.config.data_dir.join("photos");
+        std::fs::create_dir_all(&directory)?;
+
+        let expected = match camera.highest_resolution() {
+            Some(highest) if highest != current => {
+                match camera.set_resolution(highest.0, highest.1) {
+                    Ok(actual) => actual,
+                    Err(e) => {
+                        // Still take the photo, just at the streaming resolution
+                        warn!(
+                            "Failed to switch to {}x{} for a photo: {e}",
+                            highest.0, highest.1
+                        );
+                        current
+                    }
+                }
+            }
+            _ => current,
+        };
+        info!(
+            "Taking a photo at {}x{} (streaming at {}x{})",
+            expected.0, expected.1, current.0, current.1
+        );
+        self.photo = Some(PhotoState {
+            restore: current,
+            expected,
+            stem: directory.join(format!("photo-{timestamp}")),
+            deadline: Instant::now() + PHOTO_TIMEOUT,
+        });
+        self.sync_luma_capture();
+        self.action_tx.send(Action::PhotoStarted)?;
+        Ok(())
+    }
+
+    fn record_photo_frame(&mut self, data: &[u8], width: u32, height: u32) -> Result<()> {
+        let Some(photo) = self
+            .photo
+            .as_ref()
+            .filter(|photo| photo.expected == (width, height))
+        else {
+            return Ok(());
+        };
+        let stem = photo.stem.clone();
+        let png_path = stem.with_extension("png");
+        let frame = data.to_vec();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) =
+                image::save_buffer(&png_path, &frame, width, height, image::ColorType::Rgb8)
+            {
+                error!("Failed to save photo {:?}: {}", png_path, e);
+            }
+        });
+        // Let Home write the ASCII render at high detail
+        self.action_tx.send(Action::PhotoFrame(
+            stem.clone(),
+            data.to_vec(),
+            width,
+            height,
+        ))?;
+        self.finish_photo()?;
+        self.action_tx.send(Action::PhotoSaved(
+            stem.with_extension("png").display().to_string(),
+        ))?;
+        Ok(())
+    }
+
+    /// Let the camera skip decoding YUYV frames when the display only needs luma; bursts and
+    /// photos save RGB, so they always get decoded frames
+    fn sync_luma_capture(&mut self) {
+        let enabled = self.luma_capture && self.burst.is_none() && self.photo.is_none();
+        if let Some(camera) = self.camera_capture.as_mut() {
+            camera.set_luma_only(enabled);
+        }
+    }
+
+    /// Go back to the streaming resolution
+    fn finish_photo(&mut self) -> Result<()> {
+        let Some(photo) = self.photo.take() else {
+            return Ok(());
+        };
+        self.sync_luma_capture();
+        if photo.expected == photo.restore {
+            return Ok(());
+        }
+        if let Some(camera) = self.camera_capture.as_mut()
+            && let Err(e) = camera.set_resolution(photo.restore.0, photo.restore.1)
+        {
+            warn!("Failed to restore capture resolution after a photo: {e}");
+        }
+        Ok(())
+    }
+}
+
+/// Error text for the UI; permission problems carry their own actionable message
+fn camera_error_message(context: &str, error: &color_eyre::Report) -> String {
+    if error.is::<PermissionDenied>() {
+        error.to_string()
+    } else {
+        format!("{context}: {error}")
+    }
 }
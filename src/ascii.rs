@@ -1,5 +1,27 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    fmt::Write,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
 use image::{DynamicImage, ImageBuffer, Rgb, imageops::FilterType};
-use ratatui::style::Style;
+use lazy_static::lazy_static;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span, Text},
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize, Serializer, ser::SerializeStruct};
+use tracing::warn;
+
+use crate::{
+    blocks::{self, CellMode},
+    contrast, edges, glyphs, yuyv,
+    zoom::Roi,
+};
 
 /// ASCII character sets from darkest to lightest
 pub const ASCII_CHARS_DENSE: &[char] =
@@ -8,8 +30,9 @@ pub const ASCII_CHARS_SIMPLE: &[char] = &['@', '#', '*', '+', '-', '.', ' '];
 pub const ASCII_CHARS_BLOCKS: &[char] = &['█', '▉', '▊', '▋', '▌', '▍', '▎', '▏', ' '];
 pub const ASCII_CHARS_MINIMAL: &[char] = &['█', '▓', '▒', '░', ' '];
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
 pub enum CharacterSet {
+    #[default]
     Dense,
     Simple,
     Blocks,
@@ -26,6 +49,22 @@ impl CharacterSet {
         }
     }
 
+    /// Whether every character in the ramp is printable 7-bit ASCII
+    pub fn is_ascii(&self) -> bool {
+        self.chars()
+            .iter()
+            .all(|c| c.is_ascii_graphic() || *c == ' ')
+    }
+
+    /// The closest ASCII-only set, for output that must survive plain-ASCII contexts
+    pub fn ascii_fallback(&self) -> CharacterSet {
+        match self {
+            CharacterSet::Blocks => CharacterSet::Dense,
+            CharacterSet::Minimal => CharacterSet::Simple,
+            other => *other,
+        }
+    }
+
     pub fn next(&self) -> CharacterSet {
         match self {
             CharacterSet::Dense => CharacterSet::Simple,
@@ -54,307 +93,2680 @@ impl CharacterSet {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct ColoredChar {
-    pub ch: char,
-    pub style: Style,
+/// Approximate fraction of a cell inked by each ramp glyph in a typical monospace font,
+/// measured on DejaVu Sans Mono; block elements use their exact geometric coverage
+pub fn glyph_coverage(ch: char) -> f32 {
+    match ch {
+        ' ' => 0.0,
+        '.' => 0.04,
+        ',' => 0.06,
+        '-' => 0.08,
+        ':' => 0.09,
+        ';' => 0.12,
+        '+' => 0.17,
+        '*' => 0.22,
+        '?' => 0.27,
+        'S' => 0.38,
+        '%' => 0.40,
+        '#' => 0.50,
+        '@' => 0.60,
+        '░' => 0.25,
+        '▒' => 0.50,
+        '▓' => 0.75,
+        '█' => 1.0,
+        '▏' => 0.125,
+        '▎' => 0.25,
+        '▍' => 0.375,
+        '▌' => 0.5,
+        '▋' => 0.625,
+        '▊' => 0.75,
+        '▉' => 0.875,
+        _ => 0.5,
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct AsciiConverter {
-    character_set: CharacterSet,
-    width: u32,
-    height: u32,
-    color_enabled: bool,
-    scale_factor: f32,
+/// 4x4 Bayer matrix for ordered dithering between adjacent ramp entries
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Ramp spaced by glyph coverage, dithering between neighbours to approximate more levels
+#[derive(Debug, Clone, Default)]
+struct PerceptualRamp {
+    /// The ramp this was built from, densest first
+    source: Vec<char>,
+    /// Whether coverage came from calibration rather than the estimates
+    measured: bool,
+    /// Characters from least to most coverage
+    chars: Vec<char>,
+    /// For each brightness, the lower entry in `chars` and how far towards the next, 0-255
+    lut: Vec<(u8, u8)>,
 }
 
-#[allow(dead_code)] // Public API methods for potential future use
-impl AsciiConverter {
-    pub fn new(character_set: CharacterSet, width: u32, height: u32) -> Self {
+impl PerceptualRamp {
+    fn new(ramp: &[char], coverage: Option<&[(char, f32)]>) -> Self {
+        let coverage_of = |ch: char| {
+            coverage
+                .and_then(|coverage| coverage.iter().find(|(c, _)| *c == ch))
+                .map_or_else(|| glyph_coverage(ch), |(_, coverage)| *coverage)
+        };
+        let mut chars = ramp.to_vec();
+        chars.sort_by(|a, b| coverage_of(*a).total_cmp(&coverage_of(*b)));
+        let lowest = coverage_of(chars[0]);
+        let range = (coverage_of(chars[chars.len() - 1]) - lowest).max(f32::EPSILON);
+        let levels: Vec<f32> = chars
+            .iter()
+            .map(|ch| (coverage_of(*ch) - lowest) / range)
+            .collect();
+
+        let lut = (0..=255u32)
+            .map(|brightness| {
+                let target = brightness as f32 / 255.0;
+                let lower = levels
+                    .iter()
+                    .rposition(|level| *level <= target)
+                    .unwrap_or(0)
+                    .min(levels.len().saturating_sub(2));
+                let span = levels
+                    .get(lower + 1)
+                    .map_or(0.0, |upper| upper - levels[lower]);
+                let blend = if span > 0.0 {
+                    ((target - levels[lower]) / span).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                (lower as u8, (blend * 255.0).round() as u8)
+            })
+            .collect();
         Self {
-            character_set,
-            width,
-            height,
-            color_enabled: false,
-            scale_factor: 1.0,
+            source: ramp.to_vec(),
+            measured: coverage.is_some(),
+            chars,
+            lut,
         }
     }
 
-    pub fn new_dense(width: u32, height: u32) -> Self {
-        Self::new(CharacterSet::Dense, width, height)
-    }
-
-    #[allow(dead_code)]
-    pub fn new_simple(width: u32, height: u32) -> Self {
-        Self::new(CharacterSet::Simple, width, height)
+    fn built_from(&self, ramp: &[char], measured: bool) -> bool {
+        self.source == ramp && self.measured == measured
     }
 
-    #[allow(dead_code)]
-    pub fn new_blocks(width: u32, height: u32) -> Self {
-        Self::new(CharacterSet::Blocks, width, height)
+    /// Character for a brightness at a cell, dithered so neighbouring cells mix two entries
+    fn pick(&self, brightness: u32, x: u32, y: u32) -> char {
+        let (lower, blend) = self.lut[brightness.min(255) as usize];
+        let threshold = BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as u32 * 16 + 8;
+        let index = lower as usize + (blend as u32 > threshold) as usize;
+        self.chars[index.min(self.chars.len() - 1)]
     }
+}
 
-    /// Convert image to ASCII art with optimized performance
-    #[allow(dead_code)]
-    pub fn convert_image(&self, image: &DynamicImage) -> Vec<String> {
-        let (target_width, target_height) = self.get_scaled_dimensions();
+lazy_static! {
+    /// Linear light of every sRGB-encoded byte value
+    static ref SRGB_TO_LINEAR: [f32; 256] =
+        std::array::from_fn(|value| srgb_to_linear(value as f32 / 255.0));
+}
 
-        // Use Triangle filtering for better quality while still being faster than Lanczos3
-        let resized = image.resize_exact(target_width, target_height, FilterType::Triangle);
+/// Decode an sRGB value in `[0, 1]` to linear light
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
 
-        // Convert to grayscale
-        let gray = resized.to_luma8();
+/// Luma of a pixel as a byte
+///
+/// By default this weights the gamma-encoded channels directly, which is fast but renders
+/// saturated colors too dark. With `linearize` the channels are decoded to linear light,
+/// weighted with the Rec. 709 coefficients and encoded back, so brightness matches what
+/// the eye sees.
+fn pixel_luma(r: u8, g: u8, b: u8, linearize: bool) -> u8 {
+    if !linearize {
+        return ((77 * r as u32 + 150 * g as u32 + 29 * b as u32) / 256) as u8;
+    }
+    let table = &*SRGB_TO_LINEAR;
+    let luminance =
+        0.2126 * table[r as usize] + 0.7152 * table[g as usize] + 0.0722 * table[b as usize];
+    // Re-encode by finding the nearest table entry
+    let upper = table.partition_point(|&v| v < luminance).min(255);
+    if upper > 0 && luminance - table[upper - 1] < table[upper] - luminance {
+        (upper - 1) as u8
+    } else {
+        upper as u8
+    }
+}
 
-        let chars = self.character_set.chars();
-        let mut result = Vec::with_capacity(target_height as usize);
+/// Tone curve applied to normalized luma before character mapping
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToneCurve {
+    #[default]
+    Linear,
+    Log,
+    Exp,
+    Sigmoid,
+}
 
-        for y in 0..target_height {
-            let mut line = String::with_capacity(target_width as usize);
-            for x in 0..target_width {
-                let pixel = gray.get_pixel(x, y);
-                let brightness = pixel[0] as f32 / 255.0;
-                let char_index = ((1.0 - brightness) * (chars.len() - 1) as f32) as usize;
-                let char_index = char_index.min(chars.len() - 1);
-                line.push(chars[char_index]);
+impl ToneCurve {
+    /// Map a brightness in `[0, 1]` to a new brightness in `[0, 1]`
+    pub fn apply(&self, value: f32) -> f32 {
+        match self {
+            ToneCurve::Linear => value,
+            // Lifts shadows
+            ToneCurve::Log => (1.0 + 9.0 * value).log10(),
+            // Deepens shadows
+            ToneCurve::Exp => (10f32.powf(value) - 1.0) / 9.0,
+            // Boosts midtone contrast, normalized so 0 and 1 stay fixed
+            ToneCurve::Sigmoid => {
+                let sigmoid = |x: f32| 1.0 / (1.0 + (-10.0 * (x - 0.5)).exp());
+                let (low, high) = (sigmoid(0.0), sigmoid(1.0));
+                (sigmoid(value) - low) / (high - low)
             }
-            result.push(line);
         }
-
-        result
     }
 
-    /// Convert image to colored ASCII art with optimized performance
-    pub fn convert_image_colored(&self, image: &DynamicImage) -> Vec<Vec<ColoredChar>> {
-        let (target_width, target_height) = self.get_scaled_dimensions();
-
-        // Use Triangle filtering for better quality while still being faster than Lanczos3
-        let resized = image.resize_exact(target_width, target_height, FilterType::Triangle);
-        let rgb_image = resized.to_rgb8();
-
-        let chars = self.character_set.chars();
-        let mut result = Vec::with_capacity(target_height as usize);
-
-        for y in 0..target_height {
-            let mut line = Vec::with_capacity(target_width as usize);
-            for x in 0..target_width {
-                let pixel = rgb_image.get_pixel(x, y);
-                let (r, g, b) = (pixel[0], pixel[1], pixel[2]);
-
-                // Corrected brightness calculation using proper luminance formula
-                let brightness = (77 * r as u32 + 150 * g as u32 + 29 * b as u32) / 256;
-                let char_index = ((255 - brightness) * (chars.len() - 1) as u32 / 255) as usize;
-                let char_index = char_index.min(chars.len() - 1);
-
-                let style = if self.color_enabled {
-                    Style::default().fg(ratatui::style::Color::Rgb(r, g, b))
-                } else {
-                    Style::default()
-                };
-
-                line.push(ColoredChar {
-                    ch: chars[char_index],
-                    style,
-                });
-            }
-            result.push(line);
+    pub fn next(&self) -> ToneCurve {
+        match self {
+            ToneCurve::Linear => ToneCurve::Log,
+            ToneCurve::Log => ToneCurve::Exp,
+            ToneCurve::Exp => ToneCurve::Sigmoid,
+            ToneCurve::Sigmoid => ToneCurve::Linear,
         }
-
-        result
     }
 
-    /// Convert raw RGB frame to ASCII art with optimized performance
-    pub fn convert_rgb_frame(
-        &self,
-        frame: &[u8],
-        frame_width: u32,
-        frame_height: u32,
-    ) -> Vec<String> {
-        if frame.len() != (frame_width * frame_height * 3) as usize {
-            return vec!["Invalid frame data".to_string()];
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToneCurve::Linear => "Linear",
+            ToneCurve::Log => "Log",
+            ToneCurve::Exp => "Exp",
+            ToneCurve::Sigmoid => "Sigmoid",
         }
+    }
+}
 
-        // Create image buffer from raw RGB data
-        let img_buffer = match ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(
-            frame_width,
-            frame_height,
-            frame.to_vec(),
-        ) {
-            Some(buffer) => buffer,
-            None => return vec!["Failed to create image buffer".to_string()],
-        };
+/// How the source frame is mapped onto the ASCII grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScaleMode {
+    /// Fill the whole grid, distorting the source aspect ratio
+    #[default]
+    Stretch,
+    /// Preserve the source aspect ratio, padding the rest with letterbox bars
+    Fit,
+}
 
-        let dynamic_image = DynamicImage::ImageRgb8(img_buffer);
-        self.convert_image(&dynamic_image)
+impl ScaleMode {
+    pub fn next(&self) -> ScaleMode {
+        match self {
+            ScaleMode::Stretch => ScaleMode::Fit,
+            ScaleMode::Fit => ScaleMode::Stretch,
+        }
     }
 
-    /// Convert raw RGB frame to colored ASCII art with optimized performance
-    pub fn convert_rgb_frame_colored(
-        &self,
-        frame: &[u8],
-        frame_width: u32,
-        frame_height: u32,
-    ) -> Vec<Vec<ColoredChar>> {
-        if frame.len() != (frame_width * frame_height * 3) as usize {
-            return vec![vec![ColoredChar {
-                ch: 'E',
-                style: Style::default(),
-            }]];
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScaleMode::Stretch => "Stretch",
+            ScaleMode::Fit => "Fit",
         }
+    }
+}
 
-        // Create image buffer from raw RGB data
-        let img_buffer = match ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(
-            frame_width,
-            frame_height,
-            frame.to_vec(),
-        ) {
-            Some(buffer) => buffer,
-            None => {
-                return vec![vec![ColoredChar {
-                    ch: 'E',
-                    style: Style::default(),
-                }]];
-            }
-        };
+/// Clockwise rotation of the source, for cameras mounted sideways or upside down
+///
+/// The frame is rotated before anything else looks at its shape, so aspect-correct fitting,
+/// the detail level and `cell_aspect` all apply to the upright picture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
 
-        let dynamic_image = DynamicImage::ImageRgb8(img_buffer);
-        self.convert_image_colored(&dynamic_image)
+impl Rotation {
+    pub fn next(&self) -> Rotation {
+        match self {
+            Rotation::None => Rotation::Cw90,
+            Rotation::Cw90 => Rotation::Cw180,
+            Rotation::Cw180 => Rotation::Cw270,
+            Rotation::Cw270 => Rotation::None,
+        }
     }
 
-    /// Fast RGB frame to ASCII conversion with improved quality sampling
-    pub fn convert_rgb_frame_direct(
-        &self,
-        frame: &[u8],
-        frame_width: u32,
-        frame_height: u32,
-    ) -> Vec<Vec<ColoredChar>> {
-        if frame.len() != (frame_width * frame_height * 3) as usize {
-            return vec![vec![ColoredChar {
-                ch: 'E',
-                style: Style::default(),
-            }]];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rotation::None => "0°",
+            Rotation::Cw90 => "90°",
+            Rotation::Cw180 => "180°",
+            Rotation::Cw270 => "270°",
         }
+    }
 
-        let (target_width, target_height) = self.get_scaled_dimensions();
-        let chars = self.character_set.chars();
-        let mut result = Vec::with_capacity(target_height as usize);
-
-        // Calculate scaling factors
-        let x_scale = frame_width as f32 / target_width as f32;
-        let y_scale = frame_height as f32 / target_height as f32;
-
-        for y in 0..target_height {
-            let mut line = Vec::with_capacity(target_width as usize);
-            for x in 0..target_width {
-                // Use single pixel sampling for better performance, not 2x2 sampling
-                let src_x = (x as f32 * x_scale) as u32;
-                let src_y = (y as f32 * y_scale) as u32;
-
-                // Bounds checking
-                if src_x < frame_width && src_y < frame_height {
-                    let pixel_idx = ((src_y * frame_width + src_x) * 3) as usize;
-
-                    if pixel_idx + 2 < frame.len() {
-                        let r = frame[pixel_idx];
-                        let g = frame[pixel_idx + 1];
-                        let b = frame[pixel_idx + 2];
-
-                        // Corrected brightness calculation using proper luminance formula
-                        let brightness = (77 * r as u32 + 150 * g as u32 + 29 * b as u32) / 256;
-                        let char_index = ((255 - brightness) * (chars.len() - 1) as u32 / 255) as usize;
-                        let char_index = char_index.min(chars.len() - 1);
-
-                        let style = if self.color_enabled {
-                            Style::default().fg(ratatui::style::Color::Rgb(r, g, b))
-                        } else {
-                            Style::default()
-                        };
-
-                        line.push(ColoredChar {
-                            ch: chars[char_index],
-                            style,
-                        });
-                    } else {
-                        line.push(ColoredChar {
-                            ch: ' ',
-                            style: Style::default(),
-                        });
-                    }
-                } else {
-                    line.push(ColoredChar {
-                        ch: ' ',
-                        style: Style::default(),
-                    });
-                }
-            }
-            result.push(line);
+    /// Size of a `width` x `height` source once rotated
+    pub fn rotated_size(&self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Rotation::Cw90 | Rotation::Cw270 => (height, width),
+            Rotation::None | Rotation::Cw180 => (width, height),
         }
-
-        result
     }
 
-    // Getters and setters
-    pub fn character_set(&self) -> CharacterSet {
-        self.character_set
+    /// Pixel of a `width` x `height` source shown at `(x, y)` of the rotated picture
+    pub fn source_pixel(&self, x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            Rotation::None => (x, y),
+            Rotation::Cw90 => (y, height - 1 - x),
+            Rotation::Cw180 => (width - 1 - x, height - 1 - y),
+            Rotation::Cw270 => (width - 1 - y, x),
+        }
     }
+}
 
-    pub fn set_character_set(&mut self, character_set: CharacterSet) {
-        self.character_set = character_set;
-    }
+/// How the picture is drawn, cycled with one key: characters by brightness, block or
+/// braille glyphs, or characters only along edges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenderMode {
+    #[default]
+    Ascii,
+    HalfBlock,
+    Sextant,
+    Braille,
+    /// Edge strength as characters, flat areas blank
+    Edges,
+}
 
-    pub fn width(&self) -> u32 {
-        self.width
+impl RenderMode {
+    pub fn next(&self) -> RenderMode {
+        match self {
+            RenderMode::Ascii => RenderMode::HalfBlock,
+            RenderMode::HalfBlock => RenderMode::Sextant,
+            RenderMode::Sextant => RenderMode::Braille,
+            RenderMode::Braille => RenderMode::Edges,
+            RenderMode::Edges => RenderMode::Ascii,
+        }
     }
 
-    pub fn height(&self) -> u32 {
-        self.height
+    pub fn previous(&self) -> RenderMode {
+        match self {
+            RenderMode::Ascii => RenderMode::Edges,
+            RenderMode::HalfBlock => RenderMode::Ascii,
+            RenderMode::Sextant => RenderMode::HalfBlock,
+            RenderMode::Braille => RenderMode::Sextant,
+            RenderMode::Edges => RenderMode::Braille,
+        }
     }
 
-    pub fn resize(&mut self, width: u32, height: u32) {
-        self.width = width;
-        self.height = height;
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderMode::Ascii => "ASCII",
+            RenderMode::HalfBlock => "Half blocks",
+            RenderMode::Sextant => "Sextants",
+            RenderMode::Braille => "Braille",
+            RenderMode::Edges => "Edges",
+        }
     }
 
-    pub fn color_enabled(&self) -> bool {
-        self.color_enabled
+    /// The cell mode this render mode draws with
+    pub fn cell_mode(&self) -> CellMode {
+        match self {
+            RenderMode::Ascii | RenderMode::Edges => CellMode::Ramp,
+            RenderMode::HalfBlock => CellMode::HalfBlock,
+            RenderMode::Sextant => CellMode::Sextant,
+            RenderMode::Braille => CellMode::Braille,
+        }
     }
+}
 
-    pub fn set_color_enabled(&mut self, enabled: bool) {
-        self.color_enabled = enabled;
-    }
+/// How many colors the output uses, for terminals without 24-bit color
+///
+/// Colors are worked out in RGB either way; [`AsciiConverter::quantize_colors`] maps the
+/// finished grid to the nearest palette entry just before it is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorMode {
+    #[default]
+    Truecolor,
+    /// The 6x6x6 color cube and gray ramp of the xterm 256-color palette
+    Ansi256,
+    /// The 16 standard colors, drawn in whatever shades the terminal theme gives them
+    Ansi16,
+    /// Monochrome
+    None,
+}
 
-    pub fn scale_factor(&self) -> f32 {
-        self.scale_factor
+impl ColorMode {
+    pub fn next(&self) -> ColorMode {
+        match self {
+            ColorMode::Truecolor => ColorMode::Ansi256,
+            ColorMode::Ansi256 => ColorMode::Ansi16,
+            ColorMode::Ansi16 => ColorMode::None,
+            ColorMode::None => ColorMode::Truecolor,
+        }
     }
 
-    pub fn set_scale_factor(&mut self, factor: f32) {
-        self.scale_factor = factor.clamp(0.1, 2.0);
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMode::Truecolor => "Truecolor",
+            ColorMode::Ansi256 => "256 colors",
+            ColorMode::Ansi16 => "16 colors",
+            ColorMode::None => "OFF",
+        }
     }
 
-    pub fn increase_scale(&mut self) {
-        self.scale_factor = (self.scale_factor + 0.1).min(2.0);
+    /// Nearest color this mode can show; RGB passes through in truecolor and when off
+    pub fn quantize(&self, color: Color) -> Color {
+        let Color::Rgb(r, g, b) = color else {
+            return color;
+        };
+        match self {
+            ColorMode::Ansi256 => Color::Indexed(nearest_ansi256(r, g, b)),
+            ColorMode::Ansi16 => nearest_ansi16(r, g, b),
+            ColorMode::Truecolor | ColorMode::None => color,
+        }
     }
+}
 
-    pub fn decrease_scale(&mut self) {
-        self.scale_factor = (self.scale_factor - 0.1).max(0.1);
-    }
+/// The 16 standard colors with xterm's default shades, in palette order
+const ANSI16_PALETTE: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
 
-    fn get_scaled_dimensions(&self) -> (u32, u32) {
-        let width = (self.width as f32 * self.scale_factor) as u32;
-        let height = (self.height as f32 * self.scale_factor) as u32;
-        (width.max(1), height.max(1))
-    }
+/// Channel levels of the 256-color cube
+const ANSI256_CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
 
-    pub fn toggle_color(&mut self) {
-        self.color_enabled = !self.color_enabled;
-    }
+fn color_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
 
-    pub fn next_character_set(&mut self) {
-        self.character_set = self.character_set.next();
-    }
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16_PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| color_distance([r, g, b], *rgb))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
 
-    pub fn previous_character_set(&mut self) {
-        self.character_set = self.character_set.previous();
+/// Index of the nearest cube color or gray in the 256-color palette, leaving out the 16
+/// standard colors since themes change them
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| {
+        (0..ANSI256_CUBE_LEVELS.len())
+            .min_by_key(|&i| ANSI256_CUBE_LEVELS[i].abs_diff(c))
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = [
+        ANSI256_CUBE_LEVELS[ri],
+        ANSI256_CUBE_LEVELS[gi],
+        ANSI256_CUBE_LEVELS[bi],
+    ];
+    // Grays run from 8 to 238 in steps of 10
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_step;
+    if color_distance([r, g, b], [gray; 3]) < color_distance([r, g, b], cube) {
+        232 + gray_step
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
+/// Shade of an entry in the 256-color palette: the 16 standard colors, then the 6x6x6 cube
+/// and the 24 grays
+fn ansi256_rgb(index: u8) -> [u8; 3] {
+    match index {
+        0..16 => ANSI16_PALETTE[index as usize].1,
+        16..232 => {
+            let cube = index - 16;
+            [cube / 36, cube / 6 % 6, cube % 6].map(|level| ANSI256_CUBE_LEVELS[level as usize])
+        }
+        _ => [8 + 10 * (index - 232); 3],
+    }
+}
+
+/// How source pixels under a cell are combined into the cell's color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterKind {
+    /// A single pixel at the cell's corner, the fastest
+    Nearest,
+    /// Plain average of the pixels under the cell
+    #[default]
+    Box,
+    /// Average weighted towards the cell center, smoothest at small grid sizes
+    Gaussian,
+}
+
+impl FilterKind {
+    pub fn next(&self) -> FilterKind {
+        match self {
+            FilterKind::Nearest => FilterKind::Box,
+            FilterKind::Box => FilterKind::Gaussian,
+            FilterKind::Gaussian => FilterKind::Nearest,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterKind::Nearest => "Nearest",
+            FilterKind::Box => "Box",
+            FilterKind::Gaussian => "Gaussian",
+        }
+    }
+}
+
+/// Integer hash (lowbias32) spreading cell indices into uncorrelated grain values
+fn grain_hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^ (x >> 16)
+}
+
+/// How a brightness falling between two ramp entries picks one of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexRounding {
+    /// Always the denser entry, as before rounding was configurable
+    Truncate,
+    /// The nearest entry
+    #[default]
+    Round,
+    /// Either neighbour in an ordered pattern, in proportion to how close each is
+    Dither,
+    /// The nearest entry, passing the difference on to the cells right and below so
+    /// gradients average out without a visible pattern
+    FloydSteinberg,
+}
+
+impl IndexRounding {
+    pub fn next(&self) -> IndexRounding {
+        match self {
+            IndexRounding::Round => IndexRounding::Dither,
+            IndexRounding::Dither => IndexRounding::FloydSteinberg,
+            IndexRounding::FloydSteinberg => IndexRounding::Truncate,
+            IndexRounding::Truncate => IndexRounding::Round,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IndexRounding::Truncate => "Truncate",
+            IndexRounding::Round => "Round",
+            IndexRounding::Dither => "Dither",
+            IndexRounding::FloydSteinberg => "Floyd-Steinberg",
+        }
+    }
+}
+
+/// Ramp index for every adjusted brightness, as the lower entry and how far towards the next
+/// (0-254); the distance is only used when dithering
+fn build_index_lut(levels: usize, rounding: IndexRounding) -> [(u8, u8); 256] {
+    let steps = levels.saturating_sub(1) as u32;
+    std::array::from_fn(|brightness| {
+        let position = (255 - brightness as u32) * steps;
+        let (lower, remainder) = (position / 255, position % 255);
+        match rounding {
+            IndexRounding::Truncate => (lower as u8, 0),
+            // Error diffusion has already moved brightness onto an entry, so this is exact
+            IndexRounding::Round | IndexRounding::FloydSteinberg => {
+                ((lower + (remainder >= 128) as u32) as u8, 0)
+            }
+            IndexRounding::Dither => (lower as u8, remainder as u8),
+        }
+    })
+}
+
+/// Floyd-Steinberg error diffusion over a row-major luma grid: each value is moved onto the
+/// nearest of `levels` evenly spaced ramp entries and the difference spread to the
+/// neighbours not yet visited
+fn diffuse_error(luma: &mut [u8], width: usize, levels: usize) {
+    let steps = levels.saturating_sub(1) as f32;
+    if width == 0 || steps == 0.0 {
+        return;
+    }
+    // Errors carried into the current and next row, padded by a cell on either side
+    let mut current = vec![0.0f32; width + 2];
+    let mut below = vec![0.0f32; width + 2];
+    for row in luma.chunks_mut(width) {
+        for (x, value) in row.iter_mut().enumerate() {
+            let wanted = *value as f32 + current[x + 1];
+            let level = ((255.0 - wanted) / 255.0 * steps).round().clamp(0.0, steps);
+            let quantized = 255 - (level * 255.0 / steps).round() as u8;
+            let error = wanted - quantized as f32;
+            *value = quantized;
+            current[x + 2] += error * 7.0 / 16.0;
+            below[x] += error * 3.0 / 16.0;
+            below[x + 1] += error * 5.0 / 16.0;
+            below[x + 2] += error / 16.0;
+        }
+        std::mem::swap(&mut current, &mut below);
+        below.fill(0.0);
+    }
+}
+
+/// Run `pass` over the sampled luma grid, with cells outside the frame reading as black
+fn map_sample_luma(samples: &mut [Option<([u8; 3], u8)>], pass: impl FnOnce(&mut Vec<u8>)) {
+    let mut luma: Vec<u8> = samples
+        .iter()
+        .map(|sample| sample.map_or(0, |(_, luma)| luma))
+        .collect();
+    pass(&mut luma);
+    for (sample, value) in samples.iter_mut().zip(luma) {
+        if let Some((_, luma)) = sample {
+            *luma = value;
+        }
+    }
+}
+
+/// Automatic contrast adjustment applied to the whole luma grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutoContrast {
+    #[default]
+    Off,
+    /// Global histogram equalization
+    Equalize,
+    /// Contrast-limited equalization within tiles, for unevenly lit scenes
+    Clahe,
+}
+
+impl AutoContrast {
+    pub fn next(&self) -> AutoContrast {
+        match self {
+            AutoContrast::Off => AutoContrast::Equalize,
+            AutoContrast::Equalize => AutoContrast::Clahe,
+            AutoContrast::Clahe => AutoContrast::Off,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AutoContrast::Off => "Off",
+            AutoContrast::Equalize => "Equalize",
+            AutoContrast::Clahe => "CLAHE",
+        }
+    }
+}
+
+/// Most taps per axis, so a tiny grid over a huge frame still costs a bounded amount per cell
+const MAX_FILTER_TAPS: usize = 16;
+
+/// Tap offsets and normalized weights for one axis of a cell's footprint
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AxisKernel {
+    offsets: Vec<u32>,
+    weights: Vec<f32>,
+}
+
+impl AxisKernel {
+    /// Taps spread evenly over a footprint `scale` source pixels wide
+    fn new(kind: FilterKind, scale: f32, sigma: f32) -> Self {
+        let taps = (scale.ceil() as usize).clamp(1, MAX_FILTER_TAPS);
+        let step = scale / taps as f32;
+        let positions: Vec<f32> = (0..taps).map(|i| (i as f32 + 0.5) * step).collect();
+        let weights: Vec<f32> = positions
+            .iter()
+            .map(|position| match kind {
+                FilterKind::Gaussian => {
+                    // Distance from the cell center, in cells
+                    let distance = (position - scale / 2.0) / scale.max(1.0);
+                    (-(distance * distance) / (2.0 * sigma * sigma)).exp()
+                }
+                _ => 1.0,
+            })
+            .collect();
+        let total: f32 = weights.iter().sum();
+        Self {
+            offsets: positions.iter().map(|position| *position as u32).collect(),
+            weights: weights.iter().map(|weight| weight / total).collect(),
+        }
+    }
+}
+
+/// Separable sampling kernel, rebuilt only when the scale or filter settings change
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SampleKernel {
+    kind: FilterKind,
+    sigma: f32,
+    x_scale: f32,
+    y_scale: f32,
+    x: AxisKernel,
+    y: AxisKernel,
+}
+
+/// Size limits for the ASCII grid, applied after scaling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridBounds {
+    pub min_width: u32,
+    pub min_height: u32,
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+impl Default for GridBounds {
+    fn default() -> Self {
+        Self {
+            min_width: 1,
+            min_height: 1,
+            max_width: u32::MAX,
+            max_height: u32::MAX,
+        }
+    }
+}
+
+impl GridBounds {
+    pub fn clamp(&self, width: u32, height: u32) -> (u32, u32) {
+        // A max below the min is a config mistake; the min wins
+        let width = width.min(self.max_width).max(self.min_width);
+        let height = height.min(self.max_height).max(self.min_height);
+        (width.max(1), height.max(1))
+    }
+}
+
+/// Terminal cells are roughly twice as tall as they are wide
+const CELL_ASPECT: f32 = 2.0;
+/// Limits of the cell aspect ratio, covering square to very narrow fonts
+const CELL_ASPECT_RANGE: (f32, f32) = (1.0, 3.0);
+/// Limits of the detail level, in cells per source pixel across
+const DETAIL_RANGE: (f32, f32) = (0.01, 1.0);
+/// Factor one detail step changes the level by
+const DETAIL_STEP: f32 = 1.25;
+/// Smoothing turned on by a toggle before any other strength was chosen
+const DEFAULT_SMOOTHING: f32 = 0.5;
+/// Grids with fewer cells than this are sampled on the calling thread, where handing rows to
+/// the thread pool would cost more than it saves
+pub const PARALLEL_MIN_CELLS: usize = 4096;
+
+/// Color of the letterbox bars in Fit mode
+pub const LETTERBOX_COLOR: Color = Color::Black;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColoredChar {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl ColoredChar {
+    /// Padding cell with an explicit background, so exporters draw real bars instead of
+    /// falling back to the terminal default or transparency
+    pub fn letterbox() -> Self {
+        Self {
+            ch: ' ',
+            style: Style::default().bg(LETTERBOX_COLOR),
+        }
+    }
+}
+
+/// RGB components of a color, in xterm's default shades for palette colors; `None` for the
+/// terminal's default
+pub fn color_rgb(color: Color) -> Option<[u8; 3]> {
+    match color {
+        Color::Rgb(r, g, b) => Some([r, g, b]),
+        Color::Indexed(index) => Some(ansi256_rgb(index)),
+        _ => ANSI16_PALETTE
+            .iter()
+            .find(|(named, _)| *named == color)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+/// Serializes as `{"ch": "@", "fg": [r, g, b], "bg": [r, g, b]}`, leaving out unset colors
+impl Serialize for ColoredChar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fg = self.style.fg.and_then(color_rgb);
+        let bg = self.style.bg.and_then(color_rgb);
+        let fields = 1 + fg.is_some() as usize + bg.is_some() as usize;
+        let mut cell = serializer.serialize_struct("ColoredChar", fields)?;
+        cell.serialize_field("ch", &self.ch)?;
+        match fg {
+            Some(fg) => cell.serialize_field("fg", &fg)?,
+            None => cell.skip_field("fg")?,
+        }
+        match bg {
+            Some(bg) => cell.serialize_field("bg", &bg)?,
+            None => cell.skip_field("bg")?,
+        }
+        cell.end()
+    }
+}
+
+/// Tints `Alt+M` cycles through after off, with their names
+pub const TINTS: &[(&str, (u8, u8, u8))] = &[
+    ("Green", (51, 255, 51)),
+    ("Amber", (255, 176, 0)),
+    ("White", (255, 255, 255)),
+    ("Cyan", (0, 255, 255)),
+];
+
+/// Parse a color given as a [`TINTS`] name in any case or as `#rrggbb`
+pub fn parse_rgb(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.trim();
+    if let Some((_, color)) = TINTS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+    {
+        return Some(*color);
+    }
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Name of a color in [`TINTS`], or its `#rrggbb` form
+pub fn color_name((r, g, b): (u8, u8, u8)) -> String {
+    TINTS
+        .iter()
+        .find(|(_, color)| *color == (r, g, b))
+        .map_or_else(
+            || format!("#{r:02x}{g:02x}{b:02x}"),
+            |(name, _)| name.to_string(),
+        )
+}
+
+/// A row of cells as runs of consecutive cells sharing a style, with their characters
+///
+/// Flat backgrounds and block glyphs often color many neighbors the same, so drawing or
+/// escaping per run instead of per cell saves a lot of work.
+pub fn style_runs(line: &[ColoredChar]) -> Vec<(Style, String)> {
+    let mut runs: Vec<(Style, String)> = Vec::new();
+    for cell in line {
+        match runs.last_mut() {
+            Some((style, run)) if *style == cell.style => run.push(cell.ch),
+            _ => runs.push((cell.style, cell.ch.to_string())),
+        }
+    }
+    runs
+}
+
+/// SGR parameters selecting `color` as the foreground, or as the background with
+/// `background`: 24-bit for RGB, 256-color for palette indices and the standard codes for
+/// the 16 named colors
+fn sgr_color(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    match color {
+        Color::Rgb(r, g, b) => Some(format!("{};2;{r};{g};{b}", base + 8)),
+        Color::Indexed(index) => Some(format!("{};5;{index}", base + 8)),
+        _ => ANSI16_PALETTE
+            .iter()
+            .position(|(named, _)| *named == color)
+            .map(|i| match i {
+                0..8 => (base + i).to_string(),
+                _ => (base + 60 + i - 8).to_string(),
+            }),
+    }
+}
+
+/// Render a grid as text, rows separated by `\n`, with an ANSI escape before every run of
+/// cells that has a foreground or background color and a reset at the end of each row that
+/// used one
+pub fn to_ansi_string(frame: &[Vec<ColoredChar>]) -> String {
+    let mut text = String::new();
+    for (y, line) in frame.iter().enumerate() {
+        if y > 0 {
+            text.push('\n');
+        }
+        let mut colored = false;
+        let mut foreground = false;
+        let mut background = false;
+        for (style, run) in style_runs(line) {
+            // Don't let the previous cell's colors run on
+            match style.fg.and_then(|color| sgr_color(color, false)) {
+                Some(sgr) => {
+                    let _ = write!(text, "\x1b[{sgr}m");
+                    colored = true;
+                    foreground = true;
+                }
+                None if foreground => {
+                    text.push_str("\x1b[39m");
+                    foreground = false;
+                }
+                None => {}
+            }
+            match style.bg.and_then(|color| sgr_color(color, true)) {
+                Some(sgr) => {
+                    let _ = write!(text, "\x1b[{sgr}m");
+                    colored = true;
+                    background = true;
+                }
+                None if background => {
+                    text.push_str("\x1b[49m");
+                    background = false;
+                }
+                None => {}
+            }
+            text.push_str(&run);
+        }
+        if colored {
+            text.push_str("\x1b[0m");
+        }
+    }
+    text
+}
+
+/// A grid as styled text for one `Paragraph`, one span per [`style_runs`] run
+pub fn frame_to_text(frame: &[Vec<ColoredChar>]) -> Text<'static> {
+    let lines = frame.iter().map(|line| {
+        style_runs(line)
+            .into_iter()
+            .map(|(style, run)| Span::styled(run, style))
+            .collect::<Line>()
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+/// Per-cell glow that fades between frames instead of vanishing, like slow CRT phosphor
+#[derive(Debug, Clone, Default)]
+struct Phosphor {
+    width: u32,
+    height: u32,
+    /// Decayed luma and color of each cell, row-major
+    cells: Vec<(f32, [f32; 3])>,
+}
+
+impl Phosphor {
+    /// Fade every cell for a new frame, starting over when the grid size changed
+    fn begin_frame(&mut self, width: u32, height: u32, decay: f32) {
+        if self.width != width || self.height != height {
+            self.width = width;
+            self.height = height;
+            self.cells = vec![(0.0, [0.0; 3]); (width * height) as usize];
+            return;
+        }
+        let retain = 1.0 - decay;
+        for (luma, rgb) in &mut self.cells {
+            *luma *= retain;
+            rgb.iter_mut().for_each(|c| *c *= retain);
+        }
+    }
+
+    /// Max an incoming pixel into its cell and return the brightness and color to show
+    fn excite(&mut self, index: usize, brightness: u32, rgb: [u8; 3]) -> (u32, [u8; 3]) {
+        let Some((luma, glow)) = self.cells.get_mut(index) else {
+            return (brightness, rgb);
+        };
+        *luma = luma.max(brightness as f32);
+        for (glow, c) in glow.iter_mut().zip(rgb) {
+            *glow = glow.max(c as f32);
+        }
+        (*luma as u32, glow.map(|c| c as u8))
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Running average of each cell's luma, damping frame-to-frame flicker
+#[derive(Debug, Clone, Default)]
+struct TemporalSmoother {
+    width: u32,
+    height: u32,
+    /// Blended luma of each cell, row-major
+    luma: Vec<f32>,
+}
+
+impl TemporalSmoother {
+    /// Blend sampled luma with the history, `alpha` being the weight kept from earlier frames
+    fn blend(
+        &mut self,
+        samples: &mut [Option<([u8; 3], u8)>],
+        width: u32,
+        height: u32,
+        alpha: f32,
+    ) {
+        let fresh = self.width != width || self.height != height;
+        if fresh {
+            // Start over from this frame rather than blending with a different grid
+            self.width = width;
+            self.height = height;
+            self.luma = vec![0.0; samples.len()];
+        }
+        for (sample, history) in samples.iter_mut().zip(&mut self.luma) {
+            let Some((_, luma)) = sample else {
+                continue;
+            };
+            *history = if fresh {
+                *luma as f32
+            } else {
+                alpha * *history + (1.0 - alpha) * *luma as f32
+            };
+            *luma = history.round() as u8;
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Custom processing of a raw RGB frame (`width * height * 3` bytes) before conversion
+///
+/// The hook runs on whichever thread calls a conversion method, and clones of a converter
+/// share it, hence `Send + Sync`. It gets a private copy of the frame, so the caller's buffer
+/// is never modified, and must not change the buffer's size.
+pub type FramePreprocessor = dyn Fn(&mut [u8], u32, u32) + Send + Sync;
+
+#[derive(Clone)]
+struct Preprocessor(Arc<FramePreprocessor>);
+
+impl fmt::Debug for Preprocessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Preprocessor")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AsciiConverter {
+    character_set: CharacterSet,
+    width: u32,
+    height: u32,
+    color_mode: ColorMode,
+    /// Mode color comes back on in after being turned off
+    last_color_mode: ColorMode,
+    scale_factor: f32,
+    scale_mode: ScaleMode,
+    /// Height of a terminal cell over its width, used wherever the source aspect is kept
+    cell_aspect: f32,
+    grid_bounds: GridBounds,
+    /// Cells per source pixel across, so detail follows the source rather than the terminal;
+    /// `None` sizes the image from the terminal alone
+    detail_level: Option<f32>,
+    /// Darken cells along contours found by a Difference of Gaussians
+    edge_overlay: bool,
+    /// How much edge cells are darkened, in `[0, 1]`
+    edge_strength: f32,
+    /// Draw the Sobel gradient magnitude instead of brightness, leaving flat areas blank
+    edge_detect: bool,
+    tone_curve: ToneCurve,
+    /// Contrast multiplier around mid-gray, 1.0 leaves luma untouched
+    contrast: f32,
+    /// Display gamma, above 1.0 brightens midtones
+    gamma: f32,
+    /// Luma levels added after contrast, in `[-128, 128]`
+    brightness_offset: i16,
+    /// Color temperature shift in `[-1, 1]`, positive is warmer
+    temperature: f32,
+    /// Single color replacing the source colors: scaled by brightness with color on, and
+    /// drawn flat on every character with color off for a retro monitor look
+    tint: Option<(u8, u8, u8)>,
+    /// Fill behind the characters instead of the terminal's own background
+    background: Option<(u8, u8, u8)>,
+    /// Part of the picture converted for a digital zoom, all of it when unset
+    roi: Option<Roi>,
+    /// Precomputed luma adjustments, indexed by raw luma
+    luma_lut: [u8; 256],
+    /// Compute luma from linear light instead of the gamma-encoded bytes
+    linearize: bool,
+    /// Draw bright areas with dense characters and dark ones with sparse ones
+    invert: bool,
+    /// Leave fading trails behind bright cells
+    phosphor: bool,
+    /// Fraction of the glow lost per frame, in `[0.05, 0.95]`
+    phosphor_decay: f32,
+    phosphor_buffer: Phosphor,
+    /// Only emit printable 7-bit ASCII, replacing Unicode ramps with ASCII ones
+    ascii_only: bool,
+    /// User ramp replacing the character set, densest first
+    custom_ramp: Option<Vec<char>>,
+    /// Measured coverage of the custom ramp's glyphs once calibrated
+    ramp_coverage: Option<glyphs::Calibration>,
+    /// Library hook run on each raw frame before downscaling
+    preprocessor: Option<Preprocessor>,
+    /// Weight of earlier frames in each cell's luma, 0.0 is no smoothing
+    smoothing: f32,
+    /// Film grain strength as a fraction of the luma range, 0.0 is off
+    grain_amount: f32,
+    /// New grain every frame rather than a fixed pattern
+    grain_animated: bool,
+    /// Frames converted with animated grain, which reseeds the pattern
+    grain_frame: u32,
+    /// Strength the smoothing toggle turns back on, the last one that wasn't off
+    last_smoothing: f32,
+    smoother: TemporalSmoother,
+    /// Bumped whenever trails and smoothing history are cleared, so a copy converting in
+    /// this converter's place knows not to carry over history from before
+    history_generation: u32,
+    /// Duration of the last conversion made through `convert_rgb_frame_timed`
+    last_conversion_time: Duration,
+    filter: FilterKind,
+    /// Space ramp entries by glyph coverage and dither between them
+    perceptual_ramp: bool,
+    perceptual: PerceptualRamp,
+    /// Width of the Gaussian filter relative to a cell
+    gaussian_sigma: f32,
+    sample_kernel: SampleKernel,
+    auto_contrast: AutoContrast,
+    /// CLAHE tiles per axis
+    clahe_tiles: u32,
+    /// CLAHE histogram clip limit, as a multiple of the mean bin height
+    clahe_clip_limit: f32,
+    index_rounding: IndexRounding,
+    /// Ramp index per adjusted brightness for the current ramp and rounding
+    index_lut: [(u8, u8); 256],
+    cell_mode: CellMode,
+    /// The terminal font has sextant glyphs; without them sextants draw as half blocks
+    sextants: bool,
+    rotation: Rotation,
+    /// Sample rows on the rayon pool once the grid has at least `PARALLEL_MIN_CELLS` cells
+    parallel: bool,
+}
+
+#[allow(dead_code)] // Public API methods for potential future use
+impl AsciiConverter {
+    pub fn new(character_set: CharacterSet, width: u32, height: u32) -> Self {
+        Self {
+            character_set,
+            width,
+            height,
+            color_mode: ColorMode::None,
+            last_color_mode: ColorMode::Truecolor,
+            scale_factor: 1.0,
+            scale_mode: ScaleMode::default(),
+            cell_aspect: CELL_ASPECT,
+            grid_bounds: GridBounds::default(),
+            detail_level: None,
+            edge_overlay: false,
+            edge_strength: 0.6,
+            edge_detect: false,
+            tone_curve: ToneCurve::default(),
+            contrast: 1.0,
+            gamma: 1.0,
+            brightness_offset: 0,
+            temperature: 0.0,
+            tint: None,
+            background: None,
+            roi: None,
+            luma_lut: Self::build_luma_lut(ToneCurve::default(), 1.0, 1.0, 0),
+            linearize: false,
+            invert: false,
+            phosphor: false,
+            phosphor_decay: 0.15,
+            phosphor_buffer: Phosphor::default(),
+            ascii_only: false,
+            custom_ramp: None,
+            ramp_coverage: None,
+            preprocessor: None,
+            smoothing: 0.0,
+            grain_amount: 0.0,
+            grain_animated: true,
+            grain_frame: 0,
+            last_smoothing: DEFAULT_SMOOTHING,
+            smoother: TemporalSmoother::default(),
+            history_generation: 0,
+            last_conversion_time: Duration::ZERO,
+            filter: FilterKind::default(),
+            perceptual_ramp: false,
+            perceptual: PerceptualRamp::default(),
+            gaussian_sigma: 0.5,
+            sample_kernel: SampleKernel::default(),
+            auto_contrast: AutoContrast::default(),
+            clahe_tiles: 4,
+            clahe_clip_limit: 2.0,
+            index_rounding: IndexRounding::default(),
+            index_lut: build_index_lut(character_set.chars().len(), IndexRounding::default()),
+            cell_mode: CellMode::default(),
+            sextants: false,
+            rotation: Rotation::default(),
+            parallel: true,
+        }
+    }
+
+    /// Bake all luma adjustments into a lookup table so the hot loops stay a single index
+    fn build_luma_lut(tone_curve: ToneCurve, contrast: f32, gamma: f32, offset: i16) -> [u8; 256] {
+        let mut lut = [0u8; 256];
+        for (luma, entry) in lut.iter_mut().enumerate() {
+            let value = tone_curve.apply(luma as f32 / 255.0).powf(1.0 / gamma);
+            let value = (value - 0.5) * contrast + 0.5 + offset as f32 / 255.0;
+            *entry = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+        lut
+    }
+
+    fn rebuild_luma_lut(&mut self) {
+        self.luma_lut = Self::build_luma_lut(
+            self.tone_curve,
+            self.contrast,
+            self.gamma,
+            self.brightness_offset,
+        );
+    }
+
+    /// Rebuild the index table after the ramp length or rounding mode changed
+    fn rebuild_index_lut(&mut self) {
+        self.index_lut = build_index_lut(self.ramp().len(), self.index_rounding);
+    }
+
+    /// Ramp index for an adjusted brightness at a cell
+    fn ramp_index(&self, brightness: u8, x: u32, y: u32) -> usize {
+        let (lower, blend) = self.index_lut[self.ramp_brightness(brightness) as usize];
+        let threshold = BAYER_4X4[(y % 4) as usize][(x % 4) as usize] * 16 + 8;
+        let index = lower as usize + (blend > threshold) as usize;
+        index.min(self.ramp().len() - 1)
+    }
+
+    /// Brightness the ramp is indexed with, flipped when inverted
+    fn ramp_brightness(&self, brightness: u8) -> u8 {
+        if self.invert {
+            255 - brightness
+        } else {
+            brightness
+        }
+    }
+
+    /// Style of a drawn character while color is off: plain, or in the tint
+    fn monochrome_style(&self) -> Style {
+        match self.tint {
+            Some((r, g, b)) => Style::default().fg(Color::Rgb(r, g, b)),
+            None => Style::default(),
+        }
+    }
+
+    /// Foreground color for a pixel after color grading
+    fn graded_color(&self, r: u8, g: u8, b: u8, brightness: u32) -> Color {
+        if let Some((tr, tg, tb)) = self.tint {
+            let scale = |c: u8| (c as u32 * brightness / 255) as u8;
+            return Color::Rgb(scale(tr), scale(tg), scale(tb));
+        }
+        if self.temperature == 0.0 {
+            return Color::Rgb(r, g, b);
+        }
+        let shift = (self.temperature * 40.0) as i16;
+        let adjust = |c: u8, delta: i16| (c as i16 + delta).clamp(0, 255) as u8;
+        Color::Rgb(adjust(r, shift), g, adjust(b, -shift))
+    }
+
+    pub fn new_dense(width: u32, height: u32) -> Self {
+        Self::new(CharacterSet::Dense, width, height)
+    }
+
+    #[allow(dead_code)]
+    pub fn new_simple(width: u32, height: u32) -> Self {
+        Self::new(CharacterSet::Simple, width, height)
+    }
+
+    #[allow(dead_code)]
+    pub fn new_blocks(width: u32, height: u32) -> Self {
+        Self::new(CharacterSet::Blocks, width, height)
+    }
+
+    /// Convert image to ASCII art with optimized performance
+    #[allow(dead_code)]
+    pub fn convert_image(&self, image: &DynamicImage) -> Vec<String> {
+        let (target_width, target_height) = self.get_scaled_dimensions();
+        let image = self.preprocess_image(image);
+
+        // Use Triangle filtering for better quality while still being faster than Lanczos3
+        let resized = image.resize_exact(target_width, target_height, FilterType::Triangle);
+
+        // Convert to grayscale
+        let gray = resized.to_luma8();
+
+        let mut luma: Vec<u8> = gray
+            .pixels()
+            .map(|pixel| self.luma_lut[pixel[0] as usize])
+            .collect();
+        self.luma_passes(&mut luma, target_width, target_height);
+
+        let chars = self.ramp();
+        let mut result = Vec::with_capacity(target_height as usize);
+        let mut luma = luma.into_iter();
+
+        for y in 0..target_height {
+            let mut line = String::with_capacity(target_width as usize);
+            for x in 0..target_width {
+                let brightness = luma.next().unwrap_or(0);
+                line.push(chars[self.ramp_index(brightness, x, y)]);
+            }
+            result.push(line);
+        }
+
+        result
+    }
+
+    /// Convert image to colored ASCII art with optimized performance
+    pub fn convert_image_colored(&self, image: &DynamicImage) -> Vec<Vec<ColoredChar>> {
+        let (target_width, target_height) =
+            self.get_content_dimensions(image.width(), image.height());
+        let image = self.preprocess_image(image);
+
+        // Use Triangle filtering for better quality while still being faster than Lanczos3
+        let resized = image.resize_exact(target_width, target_height, FilterType::Triangle);
+        let rgb_image = resized.to_rgb8();
+
+        let mut brightnesses: Vec<u8> = rgb_image
+            .pixels()
+            .map(|pixel| {
+                self.luma_lut[pixel_luma(pixel[0], pixel[1], pixel[2], self.linearize) as usize]
+            })
+            .collect();
+        self.luma_passes(&mut brightnesses, target_width, target_height);
+
+        let chars = self.ramp();
+        let mut result = Vec::with_capacity(target_height as usize);
+        let mut luma = Vec::new();
+        let mut brightnesses = brightnesses.into_iter();
+
+        for y in 0..target_height {
+            let mut line = Vec::with_capacity(target_width as usize);
+            for x in 0..target_width {
+                let pixel = rgb_image.get_pixel(x, y);
+                let (r, g, b) = (pixel[0], pixel[1], pixel[2]);
+
+                let brightness = brightnesses.next().unwrap_or(0) as u32;
+                if self.edge_overlay {
+                    luma.push(brightness as f32 / 255.0);
+                }
+                let char_index = self.ramp_index(brightness as u8, x, y);
+
+                let style = if self.color_enabled() {
+                    Style::default().fg(self.graded_color(r, g, b, brightness))
+                } else {
+                    self.monochrome_style()
+                };
+
+                line.push(ColoredChar {
+                    ch: chars[char_index],
+                    style,
+                });
+            }
+            result.push(line);
+        }
+
+        self.overlay_edges(&mut result, &luma, target_width, target_height);
+        self.letterbox(result)
+    }
+
+    /// Convert raw RGB frame to ASCII art with optimized performance
+    pub fn convert_rgb_frame(
+        &self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Vec<String> {
+        if frame.len() != (frame_width * frame_height * 3) as usize {
+            return vec!["Invalid frame data".to_string()];
+        }
+
+        // Create image buffer from raw RGB data
+        let img_buffer = match ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(
+            frame_width,
+            frame_height,
+            frame.to_vec(),
+        ) {
+            Some(buffer) => buffer,
+            None => return vec!["Failed to create image buffer".to_string()],
+        };
+
+        let dynamic_image = DynamicImage::ImageRgb8(img_buffer);
+        self.convert_image(&dynamic_image)
+    }
+
+    /// Convert raw RGB frame to colored ASCII art with optimized performance
+    pub fn convert_rgb_frame_colored(
+        &self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Vec<Vec<ColoredChar>> {
+        if frame.len() != (frame_width * frame_height * 3) as usize {
+            return vec![vec![ColoredChar {
+                ch: 'E',
+                style: Style::default(),
+            }]];
+        }
+
+        // Create image buffer from raw RGB data
+        let img_buffer = match ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(
+            frame_width,
+            frame_height,
+            frame.to_vec(),
+        ) {
+            Some(buffer) => buffer,
+            None => {
+                return vec![vec![ColoredChar {
+                    ch: 'E',
+                    style: Style::default(),
+                }]];
+            }
+        };
+
+        let dynamic_image = DynamicImage::ImageRgb8(img_buffer);
+        self.convert_image_colored(&dynamic_image)
+    }
+
+    /// Fast RGB frame to ASCII conversion with improved quality sampling
+    ///
+    /// Takes `&mut self` because the phosphor trail carries state from frame to frame.
+    pub fn convert_rgb_frame_direct(
+        &mut self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Vec<Vec<ColoredChar>> {
+        if frame.len() != (frame_width * frame_height * 3) as usize {
+            return vec![vec![ColoredChar {
+                ch: 'E',
+                style: Style::default(),
+            }]];
+        }
+
+        let mode = self.effective_cell_mode();
+        if mode != CellMode::Ramp {
+            return self.convert_blocks(frame, frame_width, frame_height, mode);
+        }
+
+        // Sample every cell first so whole-grid passes like auto-contrast see the full frame
+        let samples = self.sample_cells(frame, frame_width, frame_height);
+        self.render_samples(samples, frame_width, frame_height)
+    }
+
+    /// Whether [`convert_yuyv_frame`](Self::convert_yuyv_frame) can stand in for decoding to
+    /// RGB first: monochrome character output with nearest sampling and no preprocessor, none
+    /// of which need the color channels
+    ///
+    /// Y is the same BT.601 luma the RGB path computes, so the grids only differ where
+    /// rounding or clipped saturated colors move a cell across a character boundary.
+    pub fn accepts_luma(&self) -> bool {
+        !self.color_enabled()
+            && self.effective_cell_mode() == CellMode::Ramp
+            && self.filter == FilterKind::Nearest
+            && self.preprocessor.is_none()
+    }
+
+    /// Convert a YUYV frame straight from its luma bytes, skipping the RGB decode
+    ///
+    /// Meant for when [`accepts_luma`](Self::accepts_luma) holds; otherwise the grid comes
+    /// out as if color, filters and the preprocessor were off.
+    pub fn convert_yuyv_frame(
+        &mut self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Vec<Vec<ColoredChar>> {
+        if frame.len() != yuyv::frame_len(frame_width, frame_height) {
+            return vec![vec![ColoredChar {
+                ch: 'E',
+                style: Style::default(),
+            }]];
+        }
+        let (target_width, target_height) =
+            self.frame_content_dimensions(frame_width, frame_height);
+        let (view_x, view_y, view_width, view_height) = self.view(frame_width, frame_height);
+        let x_scale = view_width as f32 / target_width as f32;
+        let y_scale = view_height as f32 / target_height as f32;
+        self.refresh_perceptual_ramp();
+
+        let mut samples = Vec::with_capacity((target_width * target_height) as usize);
+        for y in 0..target_height {
+            for x in 0..target_width {
+                let src_x = (x as f32 * x_scale) as u32;
+                let src_y = (y as f32 * y_scale) as u32;
+                if src_x >= view_width || src_y >= view_height {
+                    samples.push(None);
+                    continue;
+                }
+                let (src_x, src_y) = (view_x + src_x, view_y + src_y);
+                let (src_x, src_y) =
+                    self.rotation
+                        .source_pixel(src_x, src_y, frame_width, frame_height);
+                let index = (src_y * frame_width + src_x) as usize;
+                let luma = yuyv::luma_at(frame, index).map(yuyv::luma);
+                samples.push(luma.map(|luma| ([luma; 3], self.luma_lut[luma as usize])));
+            }
+        }
+        self.render_samples(samples, frame_width, frame_height)
+    }
+
+    /// Turn sampled cells into characters, applying the whole-grid passes, phosphor trails
+    /// and the edge overlay
+    fn render_samples(
+        &mut self,
+        mut samples: Vec<Option<([u8; 3], u8)>>,
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Vec<Vec<ColoredChar>> {
+        let (target_width, target_height) =
+            self.frame_content_dimensions(frame_width, frame_height);
+        let mut result = Vec::with_capacity(target_height as usize);
+        // Per-cell luma for the edge overlay, only collected when it's on
+        let mut luma = Vec::new();
+
+        if self.phosphor {
+            self.phosphor_buffer
+                .begin_frame(target_width, target_height, self.phosphor_decay);
+        }
+        self.apply_auto_contrast(&mut samples, target_width, target_height);
+        if self.edge_detect {
+            map_sample_luma(&mut samples, |luma| {
+                *luma = edges::sobel(luma, target_width as usize, target_height as usize);
+            });
+        }
+        if self.smoothing > 0.0 {
+            self.smoother
+                .blend(&mut samples, target_width, target_height, self.smoothing);
+        }
+        self.apply_grain(&mut samples);
+        if self.index_rounding == IndexRounding::FloydSteinberg && !self.perceptual_ramp {
+            let levels = self.ramp().len();
+            map_sample_luma(&mut samples, |luma| {
+                diffuse_error(luma, target_width as usize, levels);
+            });
+        }
+
+        let mut samples = samples.into_iter();
+        for y in 0..target_height {
+            let mut line = Vec::with_capacity(target_width as usize);
+            for x in 0..target_width {
+                let Some(([r, g, b], brightness)) = samples.next().flatten() else {
+                    if self.edge_overlay {
+                        luma.push(0.0);
+                    }
+                    line.push(ColoredChar {
+                        ch: ' ',
+                        style: Style::default(),
+                    });
+                    continue;
+                };
+                let brightness = brightness as u32;
+                let (brightness, [r, g, b]) = if self.phosphor {
+                    let index = (y * target_width + x) as usize;
+                    self.phosphor_buffer.excite(index, brightness, [r, g, b])
+                } else {
+                    (brightness, [r, g, b])
+                };
+                if self.edge_overlay {
+                    luma.push(brightness as f32 / 255.0);
+                }
+                let ch = self.cell_char(brightness, x, y);
+
+                let style = if self.color_enabled() {
+                    Style::default().fg(self.graded_color(r, g, b, brightness))
+                } else {
+                    self.monochrome_style()
+                };
+
+                line.push(ColoredChar { ch, style });
+            }
+            result.push(line);
+        }
+
+        self.overlay_edges(&mut result, &luma, target_width, target_height);
+        self.letterbox(result)
+    }
+
+    /// The image after the preprocessor and rotation, untouched without either
+    fn preprocess_image<'a>(&self, image: &'a DynamicImage) -> Cow<'a, DynamicImage> {
+        let mut image = Cow::Borrowed(image);
+        if let Some(preprocessor) = &self.preprocessor {
+            let mut rgb = image.to_rgb8();
+            let (width, height) = rgb.dimensions();
+            (preprocessor.0)(&mut rgb, width, height);
+            image = Cow::Owned(DynamicImage::ImageRgb8(rgb));
+        }
+        match self.rotation {
+            Rotation::None => image,
+            Rotation::Cw90 => Cow::Owned(image.rotate90()),
+            Rotation::Cw180 => Cow::Owned(image.rotate180()),
+            Rotation::Cw270 => Cow::Owned(image.rotate270()),
+        }
+    }
+
+    /// Offset each cell's luma by noise of up to `grain_amount` either way
+    fn apply_grain(&mut self, samples: &mut [Option<([u8; 3], u8)>]) {
+        let amplitude = (self.grain_amount * 255.0) as u32;
+        if amplitude == 0 {
+            return;
+        }
+        let seed = if self.grain_animated {
+            self.grain_frame = self.grain_frame.wrapping_add(1);
+            self.grain_frame.wrapping_mul(0x9e37_79b9)
+        } else {
+            0
+        };
+        for (index, (_, luma)) in samples.iter_mut().flatten().enumerate() {
+            let noise = (grain_hash(index as u32 ^ seed) % (2 * amplitude + 1)) as i32;
+            *luma = (*luma as i32 + noise - amplitude as i32).clamp(0, 255) as u8;
+        }
+    }
+
+    /// Pixel rectangle `(x, y, width, height)` of the rotated picture that's converted: the
+    /// region of interest, or all of it
+    ///
+    /// The samplers spread the grid over this rectangle and offset into it, so zooming
+    /// doesn't copy the frame.
+    fn view(&self, frame_width: u32, frame_height: u32) -> (u32, u32, u32, u32) {
+        let (width, height) = self.rotation.rotated_size(frame_width, frame_height);
+        match self.roi {
+            Some(roi) if width > 0 && height > 0 => roi.pixels(width, height),
+            _ => (0, 0, width, height),
+        }
+    }
+
+    /// Grid size for a raw frame, fitted to its [`view`](Self::view)
+    fn frame_content_dimensions(&self, frame_width: u32, frame_height: u32) -> (u32, u32) {
+        let (_, _, width, height) = self.view(frame_width, frame_height);
+        // The view is in the rotated picture and the fit rotates the size it's given, so
+        // turn it back to the source's orientation
+        let (width, height) = self.rotation.rotated_size(width, height);
+        self.get_content_dimensions(width, height)
+    }
+
+    /// The raw frame after the preprocessor, untouched without one
+    fn preprocess_frame<'a>(&self, frame: &'a [u8], width: u32, height: u32) -> Cow<'a, [u8]> {
+        let Some(preprocessor) = &self.preprocessor else {
+            return Cow::Borrowed(frame);
+        };
+        let mut buffer = frame.to_vec();
+        (preprocessor.0)(&mut buffer, width, height);
+        Cow::Owned(buffer)
+    }
+
+    /// Convert a frame with block glyphs, sampling each sub-cell and coloring the glyph with
+    /// the average of its lit sub-cells and the background with the rest
+    ///
+    /// Edges, phosphor trails, smoothing, grain and auto-contrast don't apply.
+    fn convert_blocks(
+        &self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+        mode: CellMode,
+    ) -> Vec<Vec<ColoredChar>> {
+        let frame = self.preprocess_frame(frame, frame_width, frame_height);
+        let (target_width, target_height) =
+            self.frame_content_dimensions(frame_width, frame_height);
+        let (columns, rows) = mode.sub_cells();
+        let (view_x, view_y, view_width, view_height) = self.view(frame_width, frame_height);
+        let x_scale = view_width as f32 / (target_width * columns) as f32;
+        let y_scale = view_height as f32 / (target_height * rows) as f32;
+        // Monochrome output has no background color to split around, so use mid gray
+        let threshold = (!self.color_enabled()).then_some(blocks::MID_GRAY);
+
+        let mut result = Vec::with_capacity(target_height as usize);
+        let mut samples = Vec::with_capacity((columns * rows) as usize);
+        for y in 0..target_height {
+            let mut line = Vec::with_capacity(target_width as usize);
+            for x in 0..target_width {
+                if frame_width == 0 || frame_height == 0 {
+                    line.push(ColoredChar {
+                        ch: ' ',
+                        style: Style::default(),
+                    });
+                    continue;
+                }
+                samples.clear();
+                for sub_y in 0..rows {
+                    for sub_x in 0..columns {
+                        // Sample each sub-cell at its center
+                        let src_x = ((x * columns + sub_x) as f32 + 0.5) * x_scale;
+                        let src_y = ((y * rows + sub_y) as f32 + 0.5) * y_scale;
+                        let src_x = view_x + (src_x as u32).min(view_width - 1);
+                        let src_y = view_y + (src_y as u32).min(view_height - 1);
+                        let (src_x, src_y) =
+                            self.rotation
+                                .source_pixel(src_x, src_y, frame_width, frame_height);
+                        let index = ((src_y * frame_width + src_x) * 3) as usize;
+                        let [r, g, b] = [frame[index], frame[index + 1], frame[index + 2]];
+                        let luma = self.luma_lut[pixel_luma(r, g, b, self.linearize) as usize];
+                        samples.push(([r, g, b], luma));
+                    }
+                }
+                let mut style = self.monochrome_style();
+                let pattern = if mode == CellMode::Braille {
+                    // Dots are too thin to set off a background, so they take the whole
+                    // block's color
+                    if self.color_enabled() {
+                        let ([r, g, b], luma) = blocks::average(&samples);
+                        style = style.fg(self.graded_color(r, g, b, luma as u32));
+                    }
+                    blocks::braille_pattern(&samples)
+                } else {
+                    let cell = blocks::split(&samples, threshold);
+                    if self.color_enabled() {
+                        let ([r, g, b], luma) = cell.fg;
+                        style = style.fg(self.graded_color(r, g, b, luma as u32));
+                        if let Some(([r, g, b], luma)) = cell.bg {
+                            style = style.bg(self.graded_color(r, g, b, luma as u32));
+                        }
+                    }
+                    cell.pattern
+                };
+                line.push(ColoredChar {
+                    ch: mode.glyph(pattern),
+                    style,
+                });
+            }
+            result.push(line);
+        }
+        self.letterbox(result)
+    }
+
+    /// Downscale a frame to one (color, adjusted luma) sample per cell, row by row, with
+    /// `None` for cells outside the frame
+    fn sample_cells(
+        &mut self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Vec<Option<([u8; 3], u8)>> {
+        let frame = self.preprocess_frame(frame, frame_width, frame_height);
+        let frame = &frame[..];
+        let (target_width, target_height) =
+            self.frame_content_dimensions(frame_width, frame_height);
+        // Cells are laid out over the view of the rotated picture and mapped back to source
+        // pixels
+        let (view_x, view_y, view_width, view_height) = self.view(frame_width, frame_height);
+        let x_scale = view_width as f32 / target_width as f32;
+        let y_scale = view_height as f32 / target_height as f32;
+        self.update_sample_kernel(x_scale, y_scale);
+        self.refresh_perceptual_ramp();
+
+        let mut samples = vec![None; (target_width * target_height) as usize];
+        let sample_row = |(y, row): (usize, &mut [_])| {
+            let src_y = (y as f32 * y_scale) as u32;
+            for (x, sample) in row.iter_mut().enumerate() {
+                // Use single pixel sampling for better performance, not 2x2 sampling
+                let src_x = (x as f32 * x_scale) as u32;
+
+                // Bounds checking
+                if src_x >= view_width || src_y >= view_height {
+                    continue;
+                }
+                let (src_x, src_y) = (view_x + src_x, view_y + src_y);
+                let (pixel_x, pixel_y) =
+                    self.rotation
+                        .source_pixel(src_x, src_y, frame_width, frame_height);
+                let pixel_idx = ((pixel_y * frame_width + pixel_x) * 3) as usize;
+                if pixel_idx + 2 >= frame.len() {
+                    continue;
+                }
+                let [r, g, b] = if self.filter == FilterKind::Nearest {
+                    [frame[pixel_idx], frame[pixel_idx + 1], frame[pixel_idx + 2]]
+                } else {
+                    self.filtered_pixel(frame, frame_width, frame_height, src_x, src_y)
+                };
+
+                let brightness = pixel_luma(r, g, b, self.linearize);
+                *sample = Some(([r, g, b], self.luma_lut[brightness as usize]));
+            }
+        };
+        // Rows are independent, so large grids are split across the thread pool
+        let row_len = target_width.max(1) as usize;
+        if self.parallel && samples.len() >= PARALLEL_MIN_CELLS {
+            samples
+                .par_chunks_mut(row_len)
+                .enumerate()
+                .for_each(sample_row);
+        } else {
+            samples.chunks_mut(row_len).enumerate().for_each(sample_row);
+        }
+        samples
+    }
+
+    /// Rebuild the perceptual ramp if it's on and the ramp changed since it was built
+    fn refresh_perceptual_ramp(&mut self) {
+        let coverage = self
+            .ramp_coverage
+            .as_deref()
+            .filter(|_| self.uses_custom_ramp());
+        if self.perceptual_ramp && !self.perceptual.built_from(self.ramp(), coverage.is_some()) {
+            let perceptual = PerceptualRamp::new(self.ramp(), coverage);
+            self.perceptual = perceptual;
+        }
+    }
+
+    /// Ramp character for an adjusted brightness at a cell
+    fn cell_char(&self, brightness: u32, x: u32, y: u32) -> char {
+        if self.perceptual_ramp {
+            let brightness = self.ramp_brightness(brightness.min(255) as u8) as u32;
+            return self.perceptual.pick(brightness, x, y);
+        }
+        self.ramp()[self.ramp_index(brightness.min(255) as u8, x, y)]
+    }
+
+    /// Convert a frame into one grid per color channel (red, green, blue), each using that
+    /// channel's intensity as brightness
+    ///
+    /// The frame is downscaled once and shared by the three grids. In color mode each grid is
+    /// tinted with its channel; edges, phosphor trails and auto-contrast don't apply.
+    pub fn convert_rgb_channels(
+        &mut self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> [Vec<Vec<ColoredChar>>; 3] {
+        if frame.len() != (frame_width * frame_height * 3) as usize {
+            let error = self.convert_rgb_frame_direct(frame, frame_width, frame_height);
+            return [error.clone(), error.clone(), error];
+        }
+        let (target_width, target_height) =
+            self.frame_content_dimensions(frame_width, frame_height);
+        let samples = self.sample_cells(frame, frame_width, frame_height);
+        [0, 1, 2].map(|channel| {
+            let mut cells = samples.iter();
+            let grid = (0..target_height)
+                .map(|y| {
+                    (0..target_width)
+                        .map(|x| match cells.next().copied().flatten() {
+                            Some((rgb, _)) => {
+                                let brightness = self.luma_lut[rgb[channel] as usize];
+                                let mut tint = [0u8; 3];
+                                tint[channel] = brightness;
+                                let style = if self.color_enabled() {
+                                    Style::default().fg(Color::Rgb(tint[0], tint[1], tint[2]))
+                                } else {
+                                    self.monochrome_style()
+                                };
+                                ColoredChar {
+                                    ch: self.cell_char(brightness as u32, x, y),
+                                    style,
+                                }
+                            }
+                            None => ColoredChar {
+                                ch: ' ',
+                                style: Style::default(),
+                            },
+                        })
+                        .collect()
+                })
+                .collect();
+            self.letterbox(grid)
+        })
+    }
+
+    /// Remap the sampled luma grid with the selected auto-contrast mode
+    fn apply_auto_contrast(&self, samples: &mut [Option<([u8; 3], u8)>], width: u32, height: u32) {
+        if self.auto_contrast == AutoContrast::Off {
+            return;
+        }
+        let mut luma: Vec<u8> = samples
+            .iter()
+            .map(|sample| sample.map_or(0, |(_, luma)| luma))
+            .collect();
+        match self.auto_contrast {
+            AutoContrast::Off => {}
+            AutoContrast::Equalize => contrast::equalize(&mut luma),
+            AutoContrast::Clahe => contrast::clahe(
+                &mut luma,
+                width as usize,
+                height as usize,
+                self.clahe_tiles as usize,
+                self.clahe_clip_limit,
+            ),
+        }
+        for (sample, value) in samples.iter_mut().zip(luma) {
+            if let Some((_, luma)) = sample {
+                *luma = value;
+            }
+        }
+    }
+
+    /// Rebuild the sampling kernel when the cell footprint or filter settings changed
+    fn update_sample_kernel(&mut self, x_scale: f32, y_scale: f32) {
+        let kernel = &self.sample_kernel;
+        if self.filter == FilterKind::Nearest
+            || (kernel.kind == self.filter
+                && kernel.sigma == self.gaussian_sigma
+                && kernel.x_scale == x_scale
+                && kernel.y_scale == y_scale)
+        {
+            return;
+        }
+        self.sample_kernel = SampleKernel {
+            kind: self.filter,
+            sigma: self.gaussian_sigma,
+            x_scale,
+            y_scale,
+            x: AxisKernel::new(self.filter, x_scale, self.gaussian_sigma),
+            y: AxisKernel::new(self.filter, y_scale, self.gaussian_sigma),
+        };
+    }
+
+    /// Weighted average of the pixels under the cell whose top-left pixel of the rotated
+    /// picture is given
+    fn filtered_pixel(
+        &self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+        src_x: u32,
+        src_y: u32,
+    ) -> [u8; 3] {
+        let kernel = &self.sample_kernel;
+        let (rotated_width, rotated_height) = self.rotation.rotated_size(frame_width, frame_height);
+        let mut sum = [0.0f32; 3];
+        for (dy, wy) in kernel.y.offsets.iter().zip(&kernel.y.weights) {
+            let y = (src_y + dy).min(rotated_height - 1);
+            for (dx, wx) in kernel.x.offsets.iter().zip(&kernel.x.weights) {
+                let x = (src_x + dx).min(rotated_width - 1);
+                let (x, y) = self.rotation.source_pixel(x, y, frame_width, frame_height);
+                let index = ((y * frame_width + x) * 3) as usize;
+                let weight = wx * wy;
+                for (channel, total) in sum.iter_mut().enumerate() {
+                    *total += frame[index + channel] as f32 * weight;
+                }
+            }
+        }
+        sum.map(|c| c.round().clamp(0.0, 255.0) as u8)
+    }
+
+    /// Same as `convert_rgb_frame_direct`, also returning how long the conversion took
+    ///
+    /// The duration is kept for `last_conversion_time`. The untimed method doesn't touch the
+    /// clock, so only callers that want metrics pay for them.
+    pub fn convert_rgb_frame_timed(
+        &mut self,
+        frame: &[u8],
+        frame_width: u32,
+        frame_height: u32,
+    ) -> (Vec<Vec<ColoredChar>>, Duration) {
+        let start = Instant::now();
+        let grid = self.convert_rgb_frame_direct(frame, frame_width, frame_height);
+        self.last_conversion_time = start.elapsed();
+        (grid, self.last_conversion_time)
+    }
+
+    /// Outline contours with a Difference of Gaussians mask computed on the cell grid
+    fn overlay_edges(&self, grid: &mut [Vec<ColoredChar>], luma: &[f32], width: u32, height: u32) {
+        if !self.edge_overlay {
+            return;
+        }
+        let edges = edges::difference_of_gaussians(luma, width as usize, height as usize);
+        edges::apply_edge_overlay(grid, &edges, self.edge_strength);
+    }
+
+    /// Edge detection and error diffusion over the luma grid of the image paths; the direct
+    /// path runs them between its own passes
+    fn luma_passes(&self, luma: &mut Vec<u8>, width: u32, height: u32) {
+        if self.edge_detect {
+            *luma = edges::sobel(luma, width as usize, height as usize);
+        }
+        if self.index_rounding == IndexRounding::FloydSteinberg && !self.perceptual_ramp {
+            diffuse_error(luma, width as usize, self.ramp().len());
+        }
+    }
+
+    /// Center a converted grid in the full scaled grid, padding with letterbox cells
+    fn letterbox(&self, content: Vec<Vec<ColoredChar>>) -> Vec<Vec<ColoredChar>> {
+        let (width, height) = self.get_scaled_dimensions();
+        let (width, height) = (width as usize, height as usize);
+        let content_height = content.len();
+        let content_width = content.first().map_or(0, Vec::len);
+        if content_width == width && content_height == height {
+            return content;
+        }
+
+        let top = (height - content_height) / 2;
+        let left = (width - content_width) / 2;
+        let right = width - content_width - left;
+        let mut result = Vec::with_capacity(height);
+        result.extend((0..top).map(|_| vec![ColoredChar::letterbox(); width]));
+        for row in content {
+            let mut line = Vec::with_capacity(width);
+            line.extend((0..left).map(|_| ColoredChar::letterbox()));
+            line.extend(row);
+            line.extend((0..right).map(|_| ColoredChar::letterbox()));
+            result.push(line);
+        }
+        result.resize_with(height, || vec![ColoredChar::letterbox(); width]);
+        result
+    }
+
+    // Getters and setters
+    pub fn character_set(&self) -> CharacterSet {
+        self.character_set
+    }
+
+    /// Select a built-in set, replacing any custom ramp
+    pub fn set_character_set(&mut self, character_set: CharacterSet) {
+        self.character_set = character_set;
+        self.set_custom_ramp(None);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Restore every tunable setting to its default, keeping the grid size and bounds
+    pub fn reset(&mut self) {
+        *self = Self {
+            grid_bounds: self.grid_bounds,
+            preprocessor: self.preprocessor.take(),
+            history_generation: self.history_generation.wrapping_add(1),
+            ..Self::new_dense(self.width, self.height)
+        };
+    }
+
+    /// Copy of this converter producing exactly `width` x `height` cells, for renders that
+    /// must not depend on the terminal size
+    pub fn with_grid_size(&self, width: u32, height: u32) -> Self {
+        let mut converter = self.clone();
+        converter.resize(width, height);
+        converter.scale_factor = 1.0;
+        converter.grid_bounds = GridBounds::default();
+        converter
+    }
+
+    /// Carry phosphor trails and smoothing history over from `previous`, for a copy of the
+    /// settings that converts the next frame in its place
+    ///
+    /// History a setting turned off is left behind, so turning it back on starts clean, and
+    /// so is history from before this copy's settings last cleared theirs.
+    pub fn take_frame_state(&mut self, previous: &mut AsciiConverter) {
+        self.grain_frame = previous.grain_frame;
+        if self.history_generation != previous.history_generation {
+            return;
+        }
+        if self.phosphor {
+            self.phosphor_buffer = std::mem::take(&mut previous.phosphor_buffer);
+        }
+        if self.smoothing > 0.0 {
+            self.smoother = std::mem::take(&mut previous.smoother);
+        }
+    }
+
+    /// Forget phosphor trails and smoothing history, e.g. when the source changes
+    pub fn clear_history(&mut self) {
+        self.phosphor_buffer.reset();
+        self.smoother.reset();
+        self.history_generation = self.history_generation.wrapping_add(1);
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.clear_history();
+    }
+
+    pub fn color_enabled(&self) -> bool {
+        self.color_mode != ColorMode::None
+    }
+
+    /// Turn color on in the last color mode used, or off
+    pub fn set_color_enabled(&mut self, enabled: bool) {
+        let mode = if enabled {
+            self.last_color_mode
+        } else {
+            ColorMode::None
+        };
+        self.set_color_mode(mode);
+    }
+
+    pub fn color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        if mode != ColorMode::None {
+            self.last_color_mode = mode;
+        }
+        self.color_mode = mode;
+    }
+
+    pub fn next_color_mode(&mut self) {
+        self.set_color_mode(self.color_mode.next());
+    }
+
+    /// Map every RGB color in a finished grid to the nearest one the color mode can show
+    pub fn quantize_colors(&self, grid: &mut [Vec<ColoredChar>]) {
+        if matches!(self.color_mode, ColorMode::Truecolor | ColorMode::None) {
+            return;
+        }
+        for cell in grid.iter_mut().flatten() {
+            cell.style.fg = cell.style.fg.map(|color| self.color_mode.quantize(color));
+            cell.style.bg = cell.style.bg.map(|color| self.color_mode.quantize(color));
+        }
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    pub fn set_scale_factor(&mut self, factor: f32) {
+        self.scale_factor = factor.clamp(0.1, 2.0);
+    }
+
+    pub fn increase_scale(&mut self) {
+        self.scale_factor = (self.scale_factor + 0.1).min(2.0);
+    }
+
+    pub fn decrease_scale(&mut self) {
+        self.scale_factor = (self.scale_factor - 0.1).max(0.1);
+    }
+
+    pub fn cell_aspect(&self) -> f32 {
+        self.cell_aspect
+    }
+
+    pub fn set_cell_aspect(&mut self, aspect: f32) {
+        let (min, max) = CELL_ASPECT_RANGE;
+        self.cell_aspect = aspect.clamp(min, max);
+    }
+
+    pub fn increase_cell_aspect(&mut self) {
+        // Snap to tenths so repeated steps don't drift
+        self.set_cell_aspect(((self.cell_aspect + 0.1) * 10.0).round() / 10.0);
+    }
+
+    pub fn decrease_cell_aspect(&mut self) {
+        self.set_cell_aspect(((self.cell_aspect - 0.1) * 10.0).round() / 10.0);
+    }
+
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) {
+        self.scale_mode = scale_mode;
+    }
+
+    pub fn next_scale_mode(&mut self) {
+        self.scale_mode = self.scale_mode.next();
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Trails and smoothing are cleared, as they'd smear the old orientation over the new
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        if rotation != self.rotation {
+            self.clear_history();
+        }
+        self.rotation = rotation;
+    }
+
+    /// Turn the picture another quarter clockwise
+    pub fn rotate_cw(&mut self) {
+        self.set_rotation(self.rotation.next());
+    }
+
+    pub fn contrast(&self) -> f32 {
+        self.contrast
+    }
+
+    pub fn set_contrast(&mut self, contrast: f32) {
+        self.contrast = contrast.clamp(0.1, 3.0);
+        self.rebuild_luma_lut();
+    }
+
+    pub fn increase_contrast(&mut self) {
+        // Snap to tenths so repeated steps don't drift
+        self.set_contrast(((self.contrast + 0.1) * 10.0).round() / 10.0);
+    }
+
+    pub fn decrease_contrast(&mut self) {
+        self.set_contrast(((self.contrast - 0.1) * 10.0).round() / 10.0);
+    }
+
+    pub fn brightness_offset(&self) -> i16 {
+        self.brightness_offset
+    }
+
+    pub fn set_brightness_offset(&mut self, offset: i16) {
+        self.brightness_offset = offset.clamp(-128, 128);
+        self.rebuild_luma_lut();
+    }
+
+    /// Shift brightness by `delta` luma levels
+    pub fn adjust_brightness(&mut self, delta: i16) {
+        self.set_brightness_offset(self.brightness_offset.saturating_add(delta));
+    }
+
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.gamma = gamma.clamp(0.1, 5.0);
+        self.rebuild_luma_lut();
+    }
+
+    pub fn increase_gamma(&mut self) {
+        // Snap to tenths so repeated steps don't drift
+        self.set_gamma(((self.gamma + 0.1) * 10.0).round() / 10.0);
+    }
+
+    pub fn decrease_gamma(&mut self) {
+        self.set_gamma(((self.gamma - 0.1) * 10.0).round() / 10.0);
+    }
+
+    pub fn linearize(&self) -> bool {
+        self.linearize
+    }
+
+    pub fn set_linearize(&mut self, linearize: bool) {
+        self.linearize = linearize;
+    }
+
+    pub fn invert(&self) -> bool {
+        self.invert
+    }
+
+    pub fn set_invert(&mut self, invert: bool) {
+        self.invert = invert;
+    }
+
+    pub fn toggle_invert(&mut self) {
+        self.invert = !self.invert;
+    }
+
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    pub fn set_temperature(&mut self, temperature: f32) {
+        self.temperature = temperature.clamp(-1.0, 1.0);
+    }
+
+    pub fn tint(&self) -> Option<(u8, u8, u8)> {
+        self.tint
+    }
+
+    pub fn set_tint(&mut self, tint: Option<(u8, u8, u8)>) {
+        self.tint = tint;
+    }
+
+    /// Step through [`TINTS`] and back to off; a color not in the list steps to the first one
+    pub fn next_tint(&mut self) {
+        let current = self
+            .tint
+            .and_then(|color| TINTS.iter().position(|(_, c)| *c == color));
+        self.tint = match (self.tint, current) {
+            (None, _) | (Some(_), None) => Some(TINTS[0].1),
+            (Some(_), Some(index)) => TINTS.get(index + 1).map(|(_, color)| *color),
+        };
+    }
+
+    pub fn background(&self) -> Option<(u8, u8, u8)> {
+        self.background
+    }
+
+    pub fn set_background(&mut self, color: Option<(u8, u8, u8)>) {
+        self.background = color;
+    }
+
+    /// The background as drawn, reduced to the palette of the color mode
+    pub fn background_color(&self) -> Option<Color> {
+        self.background
+            .map(|(r, g, b)| self.color_mode.quantize(Color::Rgb(r, g, b)))
+    }
+
+    pub fn roi(&self) -> Option<Roi> {
+        self.roi
+    }
+
+    /// Convert only `roi` of the picture, kept inside it; `None` converts all of it
+    pub fn set_roi(&mut self, roi: Option<Roi>) {
+        self.roi = roi.map(|roi| roi.clamped());
+    }
+
+    /// Magnification of the digital zoom, 1.0 when off
+    pub fn zoom(&self) -> f32 {
+        self.roi.map_or(1.0, |roi| roi.zoom())
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.roi = Some(self.roi.unwrap_or(Roi::FULL).zoom_in());
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.roi = self.roi.and_then(|roi| roi.zoom_out());
+    }
+
+    /// Move the zoomed region by `dx`, `dy` pan steps; nothing to move without a zoom
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.roi = self.roi.map(|roi| roi.pan(dx, dy));
+    }
+
+    pub fn grid_bounds(&self) -> GridBounds {
+        self.grid_bounds
+    }
+
+    pub fn set_grid_bounds(&mut self, grid_bounds: GridBounds) {
+        self.grid_bounds = grid_bounds;
+    }
+
+    pub fn edge_overlay(&self) -> bool {
+        self.edge_overlay
+    }
+
+    pub fn toggle_edge_overlay(&mut self) {
+        self.edge_overlay = !self.edge_overlay;
+    }
+
+    pub fn edge_detect(&self) -> bool {
+        self.edge_detect
+    }
+
+    pub fn set_edge_detect(&mut self, enabled: bool) {
+        self.edge_detect = enabled;
+    }
+
+    pub fn toggle_edge_detect(&mut self) {
+        self.edge_detect = !self.edge_detect;
+    }
+
+    pub fn edge_strength(&self) -> f32 {
+        self.edge_strength
+    }
+
+    pub fn set_edge_strength(&mut self, strength: f32) {
+        self.edge_strength = strength.clamp(0.0, 1.0);
+    }
+
+    pub fn increase_edge_strength(&mut self) {
+        self.set_edge_strength(self.edge_strength + 0.1);
+    }
+
+    pub fn decrease_edge_strength(&mut self) {
+        self.set_edge_strength(self.edge_strength - 0.1);
+    }
+
+    pub fn filter(&self) -> FilterKind {
+        self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: FilterKind) {
+        self.filter = filter;
+    }
+
+    pub fn next_filter(&mut self) {
+        self.filter = self.filter.next();
+    }
+
+    pub fn perceptual_ramp(&self) -> bool {
+        self.perceptual_ramp
+    }
+
+    pub fn set_perceptual_ramp(&mut self, enabled: bool) {
+        self.perceptual_ramp = enabled;
+    }
+
+    pub fn toggle_perceptual_ramp(&mut self) {
+        self.perceptual_ramp = !self.perceptual_ramp;
+    }
+
+    pub fn gaussian_sigma(&self) -> f32 {
+        self.gaussian_sigma
+    }
+
+    pub fn set_gaussian_sigma(&mut self, sigma: f32) {
+        self.gaussian_sigma = sigma.clamp(0.1, 2.0);
+    }
+
+    pub fn auto_contrast(&self) -> AutoContrast {
+        self.auto_contrast
+    }
+
+    pub fn set_auto_contrast(&mut self, mode: AutoContrast) {
+        self.auto_contrast = mode;
+    }
+
+    pub fn next_auto_contrast(&mut self) {
+        self.auto_contrast = self.auto_contrast.next();
+    }
+
+    pub fn clahe_tiles(&self) -> u32 {
+        self.clahe_tiles
+    }
+
+    pub fn set_clahe_tiles(&mut self, tiles: u32) {
+        self.clahe_tiles = tiles.clamp(1, 16);
+    }
+
+    pub fn clahe_clip_limit(&self) -> f32 {
+        self.clahe_clip_limit
+    }
+
+    pub fn set_clahe_clip_limit(&mut self, clip_limit: f32) {
+        self.clahe_clip_limit = clip_limit.clamp(1.0, 10.0);
+    }
+
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Sample large grids across threads; off keeps every conversion on the calling thread
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    /// How long the last timed conversion took, zero before the first one
+    pub fn last_conversion_time(&self) -> Duration {
+        self.last_conversion_time
+    }
+
+    pub fn ascii_only(&self) -> bool {
+        self.ascii_only
+    }
+
+    pub fn set_ascii_only(&mut self, ascii_only: bool) {
+        self.ascii_only = ascii_only;
+        self.rebuild_index_lut();
+    }
+
+    pub fn index_rounding(&self) -> IndexRounding {
+        self.index_rounding
+    }
+
+    pub fn set_index_rounding(&mut self, rounding: IndexRounding) {
+        self.index_rounding = rounding;
+        self.rebuild_index_lut();
+    }
+
+    pub fn cell_mode(&self) -> CellMode {
+        self.cell_mode
+    }
+
+    pub fn set_cell_mode(&mut self, mode: CellMode) {
+        self.cell_mode = mode;
+    }
+
+    /// The render mode matching the cell mode and edge detection
+    pub fn render_mode(&self) -> RenderMode {
+        match self.cell_mode {
+            CellMode::Ramp if self.edge_detect => RenderMode::Edges,
+            CellMode::Ramp => RenderMode::Ascii,
+            CellMode::HalfBlock => RenderMode::HalfBlock,
+            CellMode::Sextant => RenderMode::Sextant,
+            CellMode::Braille => RenderMode::Braille,
+        }
+    }
+
+    /// Switch cell mode and edge detection together
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        self.cell_mode = mode.cell_mode();
+        self.edge_detect = mode == RenderMode::Edges;
+    }
+
+    pub fn set_sextants_supported(&mut self, supported: bool) {
+        self.sextants = supported;
+    }
+
+    /// The cell mode actually drawn: block glyphs aren't ASCII, and sextants need font support
+    pub fn effective_cell_mode(&self) -> CellMode {
+        match self.cell_mode {
+            _ if self.ascii_only => CellMode::Ramp,
+            CellMode::Sextant if !self.sextants => CellMode::HalfBlock,
+            mode => mode,
+        }
+    }
+
+    /// Run `preprocessor` on every raw frame before it's downscaled, or stop with `None`
+    ///
+    /// Applies to all conversion methods; see [`FramePreprocessor`] for the contract.
+    pub fn set_frame_preprocessor(&mut self, preprocessor: Option<Box<FramePreprocessor>>) {
+        self.preprocessor = preprocessor.map(|preprocessor| Preprocessor(preprocessor.into()));
+    }
+
+    pub fn custom_ramp(&self) -> Option<&[char]> {
+        self.custom_ramp.as_deref()
+    }
+
+    /// Use `ramp` (densest first) instead of the character set; needs at least two characters,
+    /// shorter ones keep the character set with a warning
+    pub fn set_custom_ramp(&mut self, ramp: Option<&str>) {
+        self.custom_ramp = ramp
+            .map(|ramp| ramp.chars().collect::<Vec<_>>())
+            .filter(|chars| {
+                if chars.len() < 2 {
+                    warn!(
+                        "Custom ramp {:?} needs at least two characters, using {} instead",
+                        ramp.unwrap_or_default(),
+                        self.character_set.name()
+                    );
+                }
+                chars.len() >= 2
+            });
+        self.ramp_coverage = None;
+        self.rebuild_index_lut();
+    }
+
+    /// Reorder the custom ramp densest to lightest by each glyph's coverage in the bundled
+    /// font, and space the perceptual ramp by those measurements
+    pub fn calibrate_ramp(&mut self) {
+        if let Some(ramp) = &mut self.custom_ramp {
+            let calibrated = glyphs::calibrate(ramp);
+            *ramp = calibrated.iter().map(|(ch, _)| *ch).collect();
+            self.ramp_coverage = Some(calibrated);
+        }
+    }
+
+    /// Whether the custom ramp is drawn, which ASCII-only mode rules out for Unicode ramps
+    pub fn uses_custom_ramp(&self) -> bool {
+        self.custom_ramp.as_ref().is_some_and(|ramp| {
+            !self.ascii_only || ramp.iter().all(|c| c.is_ascii_graphic() || *c == ' ')
+        })
+    }
+
+    /// Characters in use, densest first
+    fn ramp(&self) -> &[char] {
+        match &self.custom_ramp {
+            Some(ramp) if self.uses_custom_ramp() => ramp,
+            _ => self.effective_character_set().chars(),
+        }
+    }
+
+    /// The character set actually used, which differs from the selected one in ASCII-only mode
+    pub fn effective_character_set(&self) -> CharacterSet {
+        if self.ascii_only {
+            self.character_set.ascii_fallback()
+        } else {
+            self.character_set
+        }
+    }
+
+    pub fn smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    /// Set the weight of earlier frames, where 0.0 passes frames through untouched
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        // Snap so stepping down lands exactly on zero
+        let smoothing = (smoothing.clamp(0.0, 0.95) * 100.0).round() / 100.0;
+        if smoothing == 0.0 {
+            self.clear_history();
+        } else {
+            self.last_smoothing = smoothing;
+        }
+        self.smoothing = smoothing;
+    }
+
+    /// Turn smoothing off, or back on at the last strength used
+    pub fn toggle_smoothing(&mut self) {
+        if self.smoothing > 0.0 {
+            self.set_smoothing(0.0);
+        } else {
+            self.set_smoothing(self.last_smoothing);
+        }
+    }
+
+    pub fn increase_smoothing(&mut self) {
+        self.set_smoothing(self.smoothing + 0.05);
+    }
+
+    pub fn decrease_smoothing(&mut self) {
+        self.set_smoothing(self.smoothing - 0.05);
+    }
+
+    pub fn grain_amount(&self) -> f32 {
+        self.grain_amount
+    }
+
+    /// Set the film grain strength, from 0.0 (off) to 1.0
+    pub fn set_grain_amount(&mut self, amount: f32) {
+        // Snap so stepping down lands exactly on zero
+        self.grain_amount = (amount.clamp(0.0, 1.0) * 100.0).round() / 100.0;
+    }
+
+    pub fn increase_grain(&mut self) {
+        self.set_grain_amount(self.grain_amount + 0.05);
+    }
+
+    pub fn decrease_grain(&mut self) {
+        self.set_grain_amount(self.grain_amount - 0.05);
+    }
+
+    pub fn grain_animated(&self) -> bool {
+        self.grain_animated
+    }
+
+    pub fn set_grain_animated(&mut self, animated: bool) {
+        self.grain_animated = animated;
+    }
+
+    pub fn detail_level(&self) -> Option<f32> {
+        self.detail_level
+    }
+
+    pub fn set_detail_level(&mut self, level: Option<f32>) {
+        self.detail_level = level.map(|level| level.clamp(DETAIL_RANGE.0, DETAIL_RANGE.1));
+    }
+
+    /// Detail of the image for a source of this size right now, whether or not it's fixed
+    pub fn current_detail_level(&self, source_width: u32, source_height: u32) -> f32 {
+        let (width, _) = self.get_content_dimensions(source_width, source_height);
+        let (source_width, _) = self.rotation.rotated_size(source_width, source_height);
+        (width as f32 / source_width.max(1) as f32).clamp(DETAIL_RANGE.0, DETAIL_RANGE.1)
+    }
+
+    /// One step more detail, starting from the current detail when the level isn't fixed yet
+    pub fn increase_detail_level(&mut self, source_width: u32, source_height: u32) {
+        let level = self
+            .detail_level
+            .unwrap_or_else(|| self.current_detail_level(source_width, source_height));
+        self.set_detail_level(Some(level * DETAIL_STEP));
+    }
+
+    pub fn decrease_detail_level(&mut self, source_width: u32, source_height: u32) {
+        let level = self
+            .detail_level
+            .unwrap_or_else(|| self.current_detail_level(source_width, source_height));
+        self.set_detail_level(Some(level / DETAIL_STEP));
+    }
+
+    pub fn phosphor(&self) -> bool {
+        self.phosphor
+    }
+
+    pub fn toggle_phosphor(&mut self) {
+        self.phosphor = !self.phosphor;
+        self.clear_history();
+    }
+
+    pub fn phosphor_decay(&self) -> f32 {
+        self.phosphor_decay
+    }
+
+    pub fn set_phosphor_decay(&mut self, decay: f32) {
+        self.phosphor_decay = decay.clamp(0.05, 0.95);
+    }
+
+    pub fn increase_phosphor_decay(&mut self) {
+        self.set_phosphor_decay(self.phosphor_decay + 0.05);
+    }
+
+    pub fn decrease_phosphor_decay(&mut self) {
+        self.set_phosphor_decay(self.phosphor_decay - 0.05);
+    }
+
+    pub fn tone_curve(&self) -> ToneCurve {
+        self.tone_curve
+    }
+
+    pub fn set_tone_curve(&mut self, tone_curve: ToneCurve) {
+        self.tone_curve = tone_curve;
+        self.rebuild_luma_lut();
+    }
+
+    pub fn next_tone_curve(&mut self) {
+        self.set_tone_curve(self.tone_curve.next());
+    }
+
+    fn get_unclamped_dimensions(&self) -> (u32, u32) {
+        let width = (self.width as f32 * self.scale_factor) as u32;
+        let height = (self.height as f32 * self.scale_factor) as u32;
+        (width.max(1), height.max(1))
+    }
+
+    fn get_scaled_dimensions(&self) -> (u32, u32) {
+        let (width, height) = self.get_unclamped_dimensions();
+        self.grid_bounds.clamp(width, height)
+    }
+
+    /// The grid size actually produced, after scaling and bounds
+    pub fn grid_dimensions(&self) -> (u32, u32) {
+        self.get_scaled_dimensions()
+    }
+
+    /// Whether the grid bounds currently override the terminal size and scale
+    pub fn is_clamped(&self) -> bool {
+        self.get_scaled_dimensions() != self.get_unclamped_dimensions()
+    }
+
+    /// Dimensions of the image area inside the scaled grid for a source of the given size
+    fn get_content_dimensions(&self, source_width: u32, source_height: u32) -> (u32, u32) {
+        let (width, height) = self.get_scaled_dimensions();
+        let (source_width, source_height) = self.rotation.rotated_size(source_width, source_height);
+        if let Some(detail) = self.detail_level
+            && source_width > 0
+            && source_height > 0
+        {
+            // Sized by the source, shrunk with its aspect kept only when it doesn't fit
+            let wanted_width = source_width as f32 * detail;
+            let wanted_height = source_height as f32 * detail / self.cell_aspect;
+            let fit = (width as f32 / wanted_width)
+                .min(height as f32 / wanted_height)
+                .min(1.0);
+            return (
+                ((wanted_width * fit).round() as u32).clamp(1, width),
+                ((wanted_height * fit).round() as u32).clamp(1, height),
+            );
+        }
+        if self.scale_mode == ScaleMode::Stretch || source_width == 0 || source_height == 0 {
+            return (width, height);
+        }
+
+        // Source aspect in cells, accounting for tall terminal cells
+        let aspect = source_width as f32 / source_height as f32 * self.cell_aspect;
+        if width as f32 / height as f32 > aspect {
+            let fitted = (height as f32 * aspect).round() as u32;
+            (fitted.clamp(1, width), height)
+        } else {
+            let fitted = (width as f32 / aspect).round() as u32;
+            (width, fitted.clamp(1, height))
+        }
+    }
+
+    pub fn toggle_color(&mut self) {
+        self.set_color_enabled(!self.color_enabled());
+    }
+
+    pub fn next_character_set(&mut self) {
+        self.set_character_set(self.character_set.next());
+    }
+
+    pub fn previous_character_set(&mut self) {
+        self.set_character_set(self.character_set.previous());
     }
 }
 
@@ -363,3 +2775,982 @@ impl Default for AsciiConverter {
         Self::new_dense(80, 24)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_curves_keep_black_and_white_and_bend_midtones() {
+        let curves = [
+            ToneCurve::Linear,
+            ToneCurve::Log,
+            ToneCurve::Exp,
+            ToneCurve::Sigmoid,
+        ];
+        for curve in curves {
+            assert!(curve.apply(0.0).abs() < 1e-5, "{}", curve.name());
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-5, "{}", curve.name());
+        }
+        assert_eq!(ToneCurve::Linear.apply(0.3), 0.3);
+        assert!(ToneCurve::Log.apply(0.25) > 0.25);
+        assert!(ToneCurve::Exp.apply(0.25) < 0.25);
+        let sigmoid = ToneCurve::Sigmoid;
+        assert!(sigmoid.apply(0.25) < 0.25 && sigmoid.apply(0.75) > 0.75);
+        assert!((sigmoid.apply(0.5) - 0.5).abs() < 1e-5);
+
+        // Cycling visits every curve once
+        let mut curve = ToneCurve::Linear;
+        for expected in curves.iter().cycle().skip(1).take(4) {
+            curve = curve.next();
+            assert_eq!(curve, *expected);
+        }
+    }
+
+    #[test]
+    fn test_fit_letterboxes_with_explicit_background() {
+        // 16:9 source into an 80x40 cell grid
+        let mut converter = AsciiConverter::new_dense(80, 40);
+        converter.set_scale_mode(ScaleMode::Fit);
+        let frame = vec![255u8; 160 * 90 * 3];
+        let grid = converter.convert_rgb_frame_direct(&frame, 160, 90);
+
+        assert_eq!(grid.len(), 40);
+        assert!(grid.iter().all(|line| line.len() == 80));
+        // 80 cells wide at 16:9 with 2:1 cells leaves 23 rows of image, centered
+        let bar = &grid[0][0];
+        assert_eq!(bar.ch, ' ');
+        assert_eq!(bar.style.bg, Some(LETTERBOX_COLOR));
+        assert_eq!(grid[39][79].style.bg, Some(LETTERBOX_COLOR));
+        assert_eq!(grid[20][40].style.bg, None);
+        let image_rows: Vec<usize> = (0..grid.len())
+            .filter(|&y| grid[y][40].style.bg.is_none())
+            .collect();
+        assert_eq!(image_rows.len(), 23);
+        let (top, bottom) = (image_rows[0], 39 - image_rows[22]);
+        assert!(top.abs_diff(bottom) <= 1);
+    }
+
+    #[test]
+    fn test_cell_aspect_sets_fitted_height() {
+        let mut converter = AsciiConverter::new_dense(80, 40);
+        converter.set_scale_mode(ScaleMode::Fit);
+        assert_eq!(converter.get_content_dimensions(160, 90), (80, 23));
+        // Squarer cells need more rows for the same picture
+        for _ in 0..5 {
+            converter.decrease_cell_aspect();
+        }
+        assert_eq!(converter.cell_aspect(), 1.5);
+        assert_eq!(converter.get_content_dimensions(160, 90), (80, 30));
+        converter.set_cell_aspect(0.2);
+        assert_eq!(converter.cell_aspect(), 1.0);
+        assert_eq!(converter.get_content_dimensions(160, 90), (71, 40));
+
+        // Stretch fills the grid whatever the cells look like
+        converter.set_scale_mode(ScaleMode::Stretch);
+        assert_eq!(converter.get_content_dimensions(160, 90), (80, 40));
+    }
+
+    #[test]
+    fn test_edge_overlay_darkens_contours_only() {
+        // Left half red, right half blue, with a hard vertical edge in the middle
+        let (width, height) = (40u32, 20u32);
+        let mut frame = Vec::with_capacity((width * height * 3) as usize);
+        for _ in 0..height {
+            for x in 0..width {
+                frame.extend_from_slice(if x < width / 2 {
+                    &[200, 40, 40]
+                } else {
+                    &[40, 40, 200]
+                });
+            }
+        }
+        let mut converter = AsciiConverter::new_dense(width, height);
+        converter.set_color_enabled(true);
+        let plain = converter.convert_rgb_frame_direct(&frame, width, height);
+        converter.toggle_edge_overlay();
+        let outlined = converter.convert_rgb_frame_direct(&frame, width, height);
+
+        let red = |cell: &ColoredChar| match cell.style.fg {
+            Some(Color::Rgb(r, _, _)) => r,
+            _ => panic!("expected an RGB color"),
+        };
+        // Cells at the contour get darker, flat areas keep their color
+        assert!(red(&outlined[10][19]) < red(&plain[10][19]));
+        assert_eq!(red(&outlined[10][2]), red(&plain[10][2]));
+        assert_eq!(outlined[10][2].ch, plain[10][2].ch);
+    }
+
+    #[test]
+    fn test_stretch_fills_grid() {
+        let mut converter = AsciiConverter::new_dense(80, 40);
+        let frame = vec![255u8; 160 * 90 * 3];
+        let grid = converter.convert_rgb_frame_direct(&frame, 160, 90);
+
+        assert!(
+            grid.iter()
+                .flatten()
+                .all(|cell| cell.style.bg.is_none() && cell.ch == '@')
+        );
+    }
+
+    #[test]
+    fn test_channel_grids_use_each_channel() {
+        let mut converter = AsciiConverter::new_dense(4, 2);
+        converter.set_color_enabled(true);
+        // Pure red frame
+        let frame: Vec<u8> = [255u8, 0, 0].repeat(8 * 4);
+        let [red, green, blue] = converter.convert_rgb_channels(&frame, 8, 4);
+
+        let chars = CharacterSet::Dense.chars();
+        assert!(red.iter().flatten().all(|cell| cell.ch == chars[0]));
+        assert_eq!(red[0][0].style.fg, Some(Color::Rgb(255, 0, 0)));
+        for grid in [green, blue] {
+            assert_eq!(grid.len(), 2);
+            assert!(
+                grid.iter()
+                    .flatten()
+                    .all(|cell| cell.ch == chars[chars.len() - 1])
+            );
+        }
+    }
+
+    #[test]
+    fn test_grid_bounds_clamp_with_the_min_winning() {
+        let bounds = GridBounds {
+            min_width: 20,
+            min_height: 10,
+            max_width: 100,
+            max_height: 40,
+        };
+        assert_eq!(bounds.clamp(200, 5), (100, 10));
+        assert_eq!(bounds.clamp(50, 30), (50, 30));
+        // A max below the min is a config mistake
+        let crossed = GridBounds {
+            min_width: 50,
+            max_width: 30,
+            ..bounds
+        };
+        assert_eq!(crossed.clamp(40, 20), (50, 20));
+        // Never an empty grid, whatever the bounds
+        let zero = GridBounds {
+            min_width: 0,
+            min_height: 0,
+            ..GridBounds::default()
+        };
+        assert_eq!(zero.clamp(0, 0), (1, 1));
+
+        // Scaling is bounded too
+        let mut converter = AsciiConverter::new_dense(200, 100);
+        converter.set_grid_bounds(bounds);
+        assert_eq!(converter.grid_dimensions(), (100, 40));
+        assert!(converter.is_clamped());
+    }
+
+    #[test]
+    fn test_with_grid_size_ignores_scale_and_bounds() {
+        let mut converter = AsciiConverter::new_dense(80, 24);
+        converter.set_scale_factor(0.5);
+        converter.set_scale_mode(ScaleMode::Fit);
+        converter.set_grid_bounds(GridBounds {
+            min_width: 20,
+            min_height: 8,
+            max_width: 100,
+            max_height: 40,
+        });
+        let frame = vec![128u8; 64 * 48 * 3];
+        let grid = converter
+            .with_grid_size(120, 50)
+            .convert_rgb_frame_direct(&frame, 64, 48);
+
+        assert_eq!(grid.len(), 50);
+        assert!(grid.iter().all(|line| line.len() == 120));
+        // The live converter keeps its own size
+        assert_eq!(converter.grid_dimensions(), (40, 12));
+    }
+
+    #[test]
+    fn test_filters_average_fine_detail() {
+        // One-pixel checkerboard, 4x4 pixels per cell
+        let (width, height) = (64u32, 32u32);
+        let mut frame = Vec::with_capacity((width * height * 3) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let value = if (x + y) % 2 == 0 { 255 } else { 0 };
+                frame.extend_from_slice(&[value, value, value]);
+            }
+        }
+        let mut converter = AsciiConverter::new_dense(16, 8);
+        converter.set_color_enabled(true);
+        let gray = |grid: &Vec<Vec<ColoredChar>>| match grid[4][8].style.fg {
+            Some(Color::Rgb(r, _, _)) => r,
+            _ => panic!("expected an RGB color"),
+        };
+
+        // Nearest always lands on the same parity and aliases to solid white
+        converter.set_filter(FilterKind::Nearest);
+        let nearest = converter.convert_rgb_frame_direct(&frame, width, height);
+        assert_eq!(gray(&nearest), 255);
+        for filter in [FilterKind::Box, FilterKind::Gaussian] {
+            converter.set_filter(filter);
+            let grid = converter.convert_rgb_frame_direct(&frame, width, height);
+            assert!((120..=135).contains(&gray(&grid)), "{filter:?}");
+        }
+    }
+
+    #[test]
+    fn test_gaussian_weights_favor_cell_center() {
+        let kernel = AxisKernel::new(FilterKind::Gaussian, 6.0, 0.5);
+        assert_eq!(kernel.offsets, vec![0, 1, 2, 3, 4, 5]);
+        assert!(kernel.weights[2] > kernel.weights[0]);
+        assert!((kernel.weights.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        // Huge footprints are capped
+        assert_eq!(
+            AxisKernel::new(FilterKind::Box, 100.0, 0.5).offsets.len(),
+            16
+        );
+    }
+
+    #[test]
+    fn test_perceptual_ramp_dithers_between_levels() {
+        let mut converter = AsciiConverter::new(CharacterSet::Minimal, 8, 8);
+        converter.set_perceptual_ramp(true);
+        // Between '▒' (0.5 coverage) and '▓' (0.75)
+        let frame = vec![160u8; 8 * 8 * 3];
+        let grid = converter.convert_rgb_frame_direct(&frame, 8, 8);
+        let count = |ch: char| grid.iter().flatten().filter(|cell| cell.ch == ch).count();
+
+        assert_eq!(count('▒') + count('▓'), 64);
+        assert!(count('▒') > 16 && count('▓') > 16);
+
+        // Darkest and brightest still land on the ends of the ramp
+        let black = converter.convert_rgb_frame_direct(&[0u8; 8 * 8 * 3], 8, 8);
+        let white = converter.convert_rgb_frame_direct(&[255u8; 8 * 8 * 3], 8, 8);
+        assert!(black.iter().flatten().all(|cell| cell.ch == ' '));
+        assert!(white.iter().flatten().all(|cell| cell.ch == '█'));
+    }
+
+    #[test]
+    fn test_reset_keeps_grid_size() {
+        let mut converter = AsciiConverter::new_dense(120, 40);
+        let bounds = GridBounds {
+            min_width: 20,
+            min_height: 8,
+            max_width: 100,
+            max_height: 30,
+        };
+        converter.set_grid_bounds(bounds);
+        converter.set_character_set(CharacterSet::Blocks);
+        converter.set_scale_factor(0.5);
+        converter.set_contrast(1.8);
+        converter.toggle_color();
+        converter.toggle_phosphor();
+        converter.set_filter(FilterKind::Gaussian);
+        converter.reset();
+
+        assert_eq!((converter.width(), converter.height()), (120, 40));
+        assert_eq!(converter.grid_bounds(), bounds);
+        assert_eq!(converter.character_set(), CharacterSet::Dense);
+        assert_eq!(converter.scale_factor(), 1.0);
+        assert_eq!(converter.contrast(), 1.0);
+        assert!(!converter.color_enabled());
+        assert!(!converter.phosphor());
+        assert_eq!(converter.filter(), FilterKind::Box);
+    }
+
+    #[test]
+    fn test_timed_conversion_reports_duration() {
+        let mut converter = AsciiConverter::new_dense(80, 24);
+        assert_eq!(converter.last_conversion_time(), Duration::ZERO);
+        let frame = vec![128u8; 640 * 480 * 3];
+        let (grid, elapsed) = converter.convert_rgb_frame_timed(&frame, 640, 480);
+
+        assert_eq!(grid.len(), 24);
+        assert!(elapsed > Duration::ZERO);
+        assert!(elapsed < Duration::from_secs(5));
+        assert_eq!(converter.last_conversion_time(), elapsed);
+    }
+
+    #[test]
+    fn test_calibrated_custom_ramp_is_densest_first() {
+        let mut converter = AsciiConverter::new_dense(4, 2);
+        converter.set_custom_ramp(Some(" .#:@"));
+        converter.calibrate_ramp();
+        assert_eq!(
+            converter.custom_ramp(),
+            Some(&['@', '#', ':', '.', ' '][..])
+        );
+
+        // Bright cells take the densest glyph, as with the built-in sets
+        let white = converter.convert_rgb_frame_direct(&[255u8; 4 * 2 * 3], 4, 2);
+        assert!(white.iter().flatten().all(|cell| cell.ch == '@'));
+        converter.next_character_set();
+        assert_eq!(converter.custom_ramp(), None);
+    }
+
+    #[test]
+    fn test_frame_preprocessor_runs_on_a_copy() {
+        let mut converter = AsciiConverter::new_dense(4, 2);
+        converter.set_frame_preprocessor(Some(Box::new(|rgb: &mut [u8], width, height| {
+            assert_eq!(rgb.len(), (width * height * 3) as usize);
+            rgb.iter_mut().for_each(|channel| *channel = 255 - *channel);
+        })));
+        let frame = [255u8; 4 * 2 * 3];
+        let grid = converter.convert_rgb_frame_direct(&frame, 4, 2);
+        assert!(grid.iter().flatten().all(|cell| cell.ch == ' '));
+        assert_eq!(frame, [255u8; 4 * 2 * 3]);
+
+        // Kept across a settings reset, gone once cleared
+        converter.reset();
+        let grid = converter.convert_rgb_frame_direct(&frame, 4, 2);
+        assert!(grid.iter().flatten().all(|cell| cell.ch == ' '));
+        converter.set_frame_preprocessor(None);
+        let grid = converter.convert_rgb_frame_direct(&frame, 4, 2);
+        assert!(grid.iter().flatten().all(|cell| cell.ch == '@'));
+    }
+
+    #[test]
+    fn test_detail_level_follows_source_not_terminal() {
+        let frame = vec![128u8; 320 * 240 * 3];
+        let mut small = AsciiConverter::new_dense(100, 40);
+        let mut large = AsciiConverter::new_dense(200, 80);
+        for converter in [&mut small, &mut large] {
+            converter.set_detail_level(Some(0.25));
+            // 80 cells across a 320 pixel source, half as many rows for the tall cells
+            assert_eq!(converter.get_content_dimensions(320, 240), (80, 30));
+        }
+        let grid = large.convert_rgb_frame_direct(&frame, 320, 240);
+        assert_eq!((grid[0].len(), grid.len()), (200, 80));
+
+        // Too much detail for the terminal shrinks to fit, keeping the aspect
+        let mut tiny = AsciiConverter::new_dense(40, 40);
+        tiny.set_detail_level(Some(0.25));
+        assert_eq!(tiny.get_content_dimensions(320, 240), (40, 15));
+
+        // Stepping starts from the detail shown so far
+        let mut converter = AsciiConverter::new_dense(64, 24);
+        converter.increase_detail_level(320, 240);
+        let level = converter.detail_level().unwrap();
+        assert!((level - 0.25).abs() < 1e-6, "level {level}");
+    }
+
+    #[test]
+    fn test_block_modes_split_cells_into_colored_halves() {
+        // Top half red, bottom half blue
+        let frame: Vec<u8> = (0..8u32)
+            .flat_map(|y| [0u8; 8].map(|_| if y < 4 { [255, 0, 0] } else { [0, 0, 255] }))
+            .flatten()
+            .collect();
+        let mut converter = AsciiConverter::new_dense(1, 1);
+        converter.set_color_enabled(true);
+        converter.set_cell_mode(CellMode::HalfBlock);
+        let cell = &converter.convert_rgb_frame_direct(&frame, 8, 8)[0][0];
+        assert_eq!(cell.ch, '▀');
+        assert_eq!(cell.style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(cell.style.bg, Some(Color::Rgb(0, 0, 255)));
+
+        // Without font support sextants fall back to half blocks
+        converter.set_cell_mode(CellMode::Sextant);
+        assert_eq!(converter.effective_cell_mode(), CellMode::HalfBlock);
+        converter.set_sextants_supported(true);
+        let cell = &converter.convert_rgb_frame_direct(&frame, 8, 8)[0][0];
+        // The middle row straddles the boundary and samples the bottom half
+        assert_eq!(cell.ch, '\u{1FB02}');
+
+        converter.set_ascii_only(true);
+        assert_eq!(converter.effective_cell_mode(), CellMode::Ramp);
+    }
+
+    #[test]
+    fn test_braille_dots() {
+        // Bright left column over a dark right one
+        let frame: Vec<u8> = (0..8u32)
+            .flat_map(|_| (0..8u32).map(|x| if x < 4 { [200, 200, 0] } else { [0, 0, 40] }))
+            .flatten()
+            .collect();
+        let mut converter = AsciiConverter::new_dense(1, 1);
+        converter.set_cell_mode(CellMode::Braille);
+        let cell = &converter.convert_rgb_frame_direct(&frame, 8, 8)[0][0];
+        assert_eq!(cell.ch, '⡇');
+        assert_eq!(cell.style, Style::default());
+
+        // In color the dots take the average of the whole block, with no background
+        converter.set_color_enabled(true);
+        let cell = &converter.convert_rgb_frame_direct(&frame, 8, 8)[0][0];
+        assert_eq!(cell.ch, '⡇');
+        assert_eq!(cell.style.fg, Some(Color::Rgb(100, 100, 20)));
+        assert_eq!(cell.style.bg, None);
+    }
+
+    #[test]
+    fn test_yuyv_luma_matches_rgb_decode() {
+        // Neutral chroma, so the decoded pixels are gray and their luma is exactly Y's
+        let (width, height) = (64u32, 32u32);
+        let mut frame = Vec::new();
+        for y in 0..height {
+            for x in (0..width).step_by(2) {
+                let luma = |x: u32| (16 + (x * 7 + y * 3) % 220) as u8;
+                frame.extend_from_slice(&[luma(x), 128, luma(x + 1), 128]);
+            }
+        }
+        let mut converter = AsciiConverter::new_dense(20, 10);
+        converter.set_filter(FilterKind::Nearest);
+        converter.toggle_phosphor();
+        converter.toggle_edge_overlay();
+        assert!(converter.accepts_luma());
+        let to_string = |grid: Vec<Vec<ColoredChar>>| -> String {
+            grid.iter().flatten().map(|cell| cell.ch).collect()
+        };
+        let mut rgb_converter = converter.clone();
+        let direct = to_string(converter.convert_yuyv_frame(&frame, width, height));
+        let decoded =
+            to_string(rgb_converter.convert_rgb_frame_direct(&yuyv::to_rgb(&frame), width, height));
+        assert_eq!(direct, decoded);
+
+        converter.set_color_enabled(true);
+        assert!(!converter.accepts_luma());
+        assert_eq!(
+            to_string(converter.convert_yuyv_frame(&frame[1..], width, height)),
+            "E"
+        );
+    }
+
+    #[test]
+    fn test_grain_is_static_or_animated() {
+        let frame = vec![128u8; 40 * 20 * 3];
+        let mut converter = AsciiConverter::new_dense(40, 20);
+        let convert = |converter: &mut AsciiConverter| -> String {
+            converter
+                .convert_rgb_frame_direct(&frame, 40, 20)
+                .iter()
+                .flatten()
+                .map(|cell| cell.ch)
+                .collect()
+        };
+        let plain = convert(&mut converter);
+
+        converter.set_grain_amount(0.3);
+        converter.set_grain_animated(false);
+        let first = convert(&mut converter);
+        assert_ne!(first, plain);
+        assert_eq!(convert(&mut converter), first);
+        // Noise goes both ways around the flat gray
+        let mut distinct: Vec<char> = first.chars().collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!(distinct.len() > 3);
+
+        converter.set_grain_animated(true);
+        let third = convert(&mut converter);
+        assert_ne!(convert(&mut converter), third);
+
+        for _ in 0..10 {
+            converter.decrease_grain();
+        }
+        assert_eq!(converter.grain_amount(), 0.0);
+        assert_eq!(convert(&mut converter), plain);
+    }
+
+    #[test]
+    fn test_index_rounding_boundaries() {
+        // Dense has 12 entries, so each step spans 255 / 11 ≈ 23.2 brightness levels
+        let mut converter = AsciiConverter::new_dense(4, 4);
+        converter.set_index_rounding(IndexRounding::Truncate);
+        let index = |converter: &AsciiConverter, brightness| converter.ramp_index(brightness, 0, 0);
+        assert_eq!(index(&converter, 255), 0);
+        assert_eq!(index(&converter, 232), 0);
+        assert_eq!(index(&converter, 231), 1);
+        assert_eq!(index(&converter, 0), 11);
+
+        converter.set_index_rounding(IndexRounding::Round);
+        assert_eq!(index(&converter, 244), 0);
+        assert_eq!(index(&converter, 243), 1);
+        assert_eq!(index(&converter, 232), 1);
+        assert_eq!(index(&converter, 0), 11);
+
+        // 132/255 of the way to the next entry picks it in 8 of the 16 pattern cells
+        converter.set_index_rounding(IndexRounding::Dither);
+        let picks: Vec<usize> = (0..16)
+            .map(|cell| converter.ramp_index(243, cell % 4, cell / 4))
+            .collect();
+        assert!(picks.iter().all(|&index| index <= 1));
+        assert_eq!(picks.iter().filter(|&&index| index == 1).count(), 8);
+        assert_eq!(converter.ramp_index(0, 3, 3), 11);
+        assert_eq!(converter.ramp_index(255, 3, 3), 0);
+
+        // The table follows the ramp length
+        converter.set_character_set(CharacterSet::Minimal);
+        converter.set_index_rounding(IndexRounding::Round);
+        assert_eq!(index(&converter, 0), 4);
+    }
+
+    #[test]
+    fn test_contrast_and_brightness_offset() {
+        let mut converter = AsciiConverter::new_dense(4, 4);
+        // The defaults leave luma untouched
+        assert!((0..=255u8).all(|luma| converter.luma_lut[luma as usize] == luma));
+
+        converter.adjust_brightness(20);
+        assert_eq!(converter.luma_lut[0], 20);
+        assert_eq!(converter.luma_lut[100], 120);
+        assert_eq!(converter.luma_lut[250], 255);
+        converter.adjust_brightness(-300);
+        assert_eq!(converter.brightness_offset(), -128);
+        assert_eq!(converter.luma_lut[100], 0);
+
+        converter.set_brightness_offset(0);
+        for _ in 0..5 {
+            converter.increase_contrast();
+        }
+        assert_eq!(converter.contrast(), 1.5);
+        // Stretched around mid gray
+        assert_eq!(converter.luma_lut[100], 86);
+        assert_eq!(converter.luma_lut[200], 236);
+        assert_eq!(converter.luma_lut[250], 255);
+        for _ in 0..30 {
+            converter.decrease_contrast();
+        }
+        assert_eq!(converter.contrast(), 0.1);
+    }
+
+    #[test]
+    fn test_floyd_steinberg_keeps_average_brightness() {
+        // Minimal has five entries, so flat 96 falls between two of them
+        let mut luma = vec![96u8; 16 * 16];
+        diffuse_error(&mut luma, 16, 5);
+        assert!(
+            luma.iter()
+                .all(|value| [0, 64, 127, 191, 255].contains(value))
+        );
+        let mean = luma.iter().map(|&value| value as f32).sum::<f32>() / luma.len() as f32;
+        assert!((mean - 96.0).abs() < 2.0, "mean {mean}");
+        assert!(luma.contains(&64) && luma.contains(&127));
+
+        // Quantized brightness lands exactly on its entry
+        let mut converter = AsciiConverter::new_blocks(16, 16);
+        converter.set_character_set(CharacterSet::Minimal);
+        converter.set_index_rounding(IndexRounding::FloydSteinberg);
+        let picks: Vec<usize> = [255, 191, 127, 64, 0]
+            .map(|brightness| converter.ramp_index(brightness, 0, 0))
+            .into();
+        assert_eq!(picks, [0, 1, 2, 3, 4]);
+        let frame = vec![96u8; 16 * 16 * 3];
+        let grid = converter.convert_rgb_frame_direct(&frame, 16, 16);
+        let cells: String = grid.iter().flatten().map(|cell| cell.ch).collect();
+        assert!(cells.contains('▒') && cells.contains('░'));
+    }
+
+    #[test]
+    fn test_ansi_string_colors_cells() {
+        let red = ColoredChar {
+            ch: '@',
+            style: Style::default().fg(Color::Rgb(255, 0, 0)),
+        };
+        let plain = ColoredChar {
+            ch: '.',
+            style: Style::default(),
+        };
+        assert_eq!(
+            to_ansi_string(&[vec![red.clone()], vec![plain.clone()]]),
+            "\x1b[38;2;255;0;0m@\x1b[0m\n."
+        );
+        // A plain cell after a colored one goes back to the default foreground
+        assert_eq!(
+            to_ansi_string(&[vec![red, plain]]),
+            "\x1b[38;2;255;0;0m@\x1b[39m.\x1b[0m"
+        );
+        // Letterbox bars keep their black background
+        assert_eq!(
+            to_ansi_string(&[vec![ColoredChar::letterbox()]]),
+            "\x1b[40m \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_style_runs_group_neighbors_with_one_style() {
+        let cell = |ch, color| ColoredChar {
+            ch,
+            style: Style::default().fg(color),
+        };
+        let red = Color::Rgb(255, 0, 0);
+        let blue = Color::Rgb(0, 0, 255);
+        let row = [
+            cell('a', red),
+            cell('b', red),
+            cell('c', blue),
+            cell('d', red),
+            cell('e', red),
+            cell('f', red),
+        ];
+        let runs = style_runs(&row);
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0], (Style::default().fg(red), "ab".to_string()));
+        assert_eq!(runs[2].1, "def");
+        assert!(style_runs(&[]).is_empty());
+
+        // One escape per run rather than per cell
+        assert_eq!(
+            to_ansi_string(&[row.to_vec()]),
+            "\x1b[38;2;255;0;0mab\x1b[38;2;0;0;255mc\x1b[38;2;255;0;0mdef\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_frame_to_text_merges_runs_of_one_style() {
+        let cell = |ch, style| ColoredChar { ch, style };
+        let red = Style::default().fg(Color::Rgb(255, 0, 0));
+        let plain = Style::default();
+        let text = frame_to_text(&[
+            vec![
+                cell('@', red),
+                cell('#', red),
+                cell('.', plain),
+                cell('@', red),
+            ],
+            vec![cell(' ', plain)],
+            vec![],
+        ]);
+        assert_eq!(
+            text.lines,
+            [
+                Line::from(vec![
+                    Span::styled("@#", red),
+                    Span::styled(".", plain),
+                    Span::styled("@", red),
+                ]),
+                Line::from(vec![Span::styled(" ", plain)]),
+                Line::default(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linearized_luma() {
+        // Grays keep their value either way
+        for value in [0, 1, 60, 128, 200, 255] {
+            assert_eq!(pixel_luma(value, value, value, true), value);
+        }
+        // Saturated colors are perceived brighter than the encoded weighting suggests
+        assert_eq!(pixel_luma(255, 0, 0, false), 76);
+        assert_eq!(pixel_luma(255, 0, 0, true), 127);
+        assert!(pixel_luma(0, 0, 255, true) > pixel_luma(0, 0, 255, false));
+    }
+
+    #[test]
+    fn test_ascii_only_never_emits_unicode() {
+        let frame: Vec<u8> = (0..16 * 8 * 3).map(|i| (i * 7 % 256) as u8).collect();
+        for set in [CharacterSet::Blocks, CharacterSet::Minimal] {
+            assert!(!set.is_ascii());
+            let mut converter = AsciiConverter::new(set, 16, 8);
+            converter.set_ascii_only(true);
+            for perceptual in [false, true] {
+                converter.set_perceptual_ramp(perceptual);
+                let grid = converter.convert_rgb_frame_direct(&frame, 16, 8);
+                let text: String = grid.iter().flatten().map(|cell| cell.ch).collect();
+                assert!(text.bytes().all(|b| (0x20..0x7f).contains(&b)), "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_smoothing_blends_towards_new_frames() {
+        let mut converter = AsciiConverter::new_dense(2, 1);
+        let dark = vec![0u8; 4 * 2 * 3];
+        let bright = vec![255u8; 4 * 2 * 3];
+        let chars = CharacterSet::Dense.chars();
+
+        // Pass-through by default
+        converter.convert_rgb_frame_direct(&dark, 4, 2);
+        assert_eq!(
+            converter.convert_rgb_frame_direct(&bright, 4, 2)[0][0].ch,
+            chars[0]
+        );
+
+        converter.set_smoothing(0.5);
+        converter.convert_rgb_frame_direct(&dark, 4, 2);
+        let blended = converter.convert_rgb_frame_direct(&bright, 4, 2)[0][0].ch;
+        assert!(blended != chars[0] && blended != chars[chars.len() - 1]);
+
+        // A new grid size starts from the incoming frame
+        converter.resize(3, 1);
+        assert_eq!(
+            converter.convert_rgb_frame_direct(&bright, 4, 2)[0][0].ch,
+            chars[0]
+        );
+
+        for _ in 0..30 {
+            converter.decrease_smoothing();
+        }
+        assert_eq!(converter.smoothing(), 0.0);
+
+        // The toggle brings back the last strength, or the default before there was one
+        converter.set_smoothing(0.3);
+        converter.toggle_smoothing();
+        assert_eq!(converter.smoothing(), 0.0);
+        converter.toggle_smoothing();
+        assert_eq!(converter.smoothing(), 0.3);
+        let mut fresh = AsciiConverter::new_dense(2, 1);
+        fresh.toggle_smoothing();
+        assert_eq!(fresh.smoothing(), DEFAULT_SMOOTHING);
+    }
+
+    #[test]
+    fn test_phosphor_leaves_fading_trail() {
+        let (width, height) = (10u32, 5u32);
+        // A bright column at x, on black
+        let frame_with_column = |column: u32| {
+            let mut frame = vec![0u8; (width * height * 3) as usize];
+            for y in 0..height {
+                let index = ((y * width + column) * 3) as usize;
+                frame[index..index + 3].copy_from_slice(&[255, 255, 255]);
+            }
+            frame
+        };
+        let mut converter = AsciiConverter::new_dense(width, height);
+        converter.toggle_phosphor();
+        converter.set_phosphor_decay(0.5);
+
+        converter.convert_rgb_frame_direct(&frame_with_column(2), width, height);
+        let grid = converter.convert_rgb_frame_direct(&frame_with_column(6), width, height);
+        // The old position still glows at half brightness, dimmer than the live column
+        assert_eq!(grid[2][6].ch, '@');
+        assert_ne!(grid[2][2].ch, ' ');
+        assert_ne!(grid[2][2].ch, '@');
+
+        // The trail dies out after enough frames, and a resize clears it at once
+        for _ in 0..8 {
+            converter.convert_rgb_frame_direct(&frame_with_column(6), width, height);
+        }
+        let grid = converter.convert_rgb_frame_direct(&frame_with_column(6), width, height);
+        assert_eq!(grid[2][2].ch, ' ');
+        converter.convert_rgb_frame_direct(&frame_with_column(2), width, height);
+        converter.resize(width, height);
+        let grid = converter.convert_rgb_frame_direct(&frame_with_column(6), width, height);
+        assert_eq!(grid[2][2].ch, ' ');
+    }
+
+    #[test]
+    fn test_frame_state_carries_over_until_history_is_cleared() {
+        let (width, height) = (10u32, 5u32);
+        let mut frame = vec![0u8; (width * height * 3) as usize];
+        for y in 0..height {
+            let index = ((y * width + 2) * 3) as usize;
+            frame[index..index + 3].copy_from_slice(&[255, 255, 255]);
+        }
+        let black = vec![0u8; frame.len()];
+        let mut live = AsciiConverter::new_dense(width, height);
+        live.toggle_phosphor();
+        live.set_phosphor_decay(0.5);
+
+        // A copy of the live settings picks up the trail the previous copy left
+        let mut previous = live.clone();
+        previous.convert_rgb_frame_direct(&frame, width, height);
+        let mut next = live.clone();
+        next.take_frame_state(&mut previous);
+        let grid = next.convert_rgb_frame_direct(&black, width, height);
+        assert_ne!(grid[2][2].ch, ' ');
+
+        // Clearing the live history, e.g. for a new camera, leaves the old trail behind
+        live.clear_history();
+        let mut after_clear = live.clone();
+        after_clear.take_frame_state(&mut next);
+        let grid = after_clear.convert_rgb_frame_direct(&black, width, height);
+        assert_eq!(grid[2][2].ch, ' ');
+    }
+
+    #[test]
+    fn test_parallel_sampling_matches_serial() {
+        // 200x60 is above the threshold, so the default converter splits the rows
+        let (width, height) = (640u32, 360u32);
+        let frame: Vec<u8> = (0..width * height * 3)
+            .map(|i| (i % 251) as u8 ^ (i / (width * 3)) as u8)
+            .collect();
+        for filter in [FilterKind::Nearest, FilterKind::Gaussian] {
+            let mut converter = AsciiConverter::new_dense(200, 60);
+            converter.set_color_enabled(true);
+            converter.set_filter(filter);
+            assert!(converter.parallel());
+            let parallel = converter.convert_rgb_frame_direct(&frame, width, height);
+            converter.set_parallel(false);
+            let serial = converter.convert_rgb_frame_direct(&frame, width, height);
+            assert_eq!(
+                to_ansi_string(&parallel),
+                to_ansi_string(&serial),
+                "{filter:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotation_turns_the_picture_clockwise() {
+        // 4x2 black frame with a white top-left pixel, one cell per pixel
+        let mut frame = vec![0u8; 4 * 2 * 3];
+        frame[..3].copy_from_slice(&[255, 255, 255]);
+        let image = DynamicImage::ImageRgb8(ImageBuffer::from_raw(4, 2, frame.clone()).unwrap());
+        // Dark cells are blank, so the only drawn cell is the white pixel
+        let white_at = |grid: &Vec<Vec<ColoredChar>>| -> Vec<(usize, usize)> {
+            (0..grid.len())
+                .flat_map(|y| (0..grid[y].len()).map(move |x| (x, y)))
+                .filter(|&(x, y)| grid[y][x].ch != ' ')
+                .collect()
+        };
+        for (rotation, size, corner) in [
+            (Rotation::None, (4, 2), (0, 0)),
+            (Rotation::Cw90, (2, 4), (1, 0)),
+            (Rotation::Cw180, (4, 2), (3, 1)),
+            (Rotation::Cw270, (2, 4), (0, 3)),
+        ] {
+            let mut converter = AsciiConverter::new_dense(size.0, size.1);
+            converter.set_rotation(rotation);
+            let grid = converter.convert_rgb_frame_direct(&frame, 4, 2);
+            assert_eq!(white_at(&grid), [corner], "{rotation:?}");
+            // The image path turns the picture the same way
+            let grid = converter.convert_image_colored(&image);
+            assert_eq!(white_at(&grid), [corner], "{rotation:?}");
+        }
+
+        // Fit keeps the upright aspect, so a 16:9 source turned sideways is tall
+        let mut converter = AsciiConverter::new_dense(80, 40);
+        converter.set_scale_mode(ScaleMode::Fit);
+        converter.set_rotation(Rotation::Cw90);
+        assert_eq!(converter.get_content_dimensions(160, 90), (45, 40));
+    }
+
+    #[test]
+    fn test_roi_converts_only_the_zoomed_region() {
+        // 4x2 black frame with a white top-left pixel, one cell per pixel
+        let mut frame = vec![0u8; 4 * 2 * 3];
+        frame[..3].copy_from_slice(&[255, 255, 255]);
+        let mut converter = AsciiConverter::new_dense(4, 2);
+        converter.set_roi(Some(Roi {
+            x: 0.0,
+            y: 0.0,
+            width: 0.5,
+            height: 0.5,
+        }));
+        // The white pixel now spans half the grid's width and all of its height
+        let grid = converter.convert_rgb_frame_direct(&frame, 4, 2);
+        let drawn: Vec<bool> = grid.iter().flatten().map(|cell| cell.ch != ' ').collect();
+        assert_eq!(drawn, [true, true, false, false, true, true, false, false]);
+
+        // Turned a quarter clockwise, the source's top left is the picture's top right
+        converter.set_rotation(Rotation::Cw90);
+        converter.set_roi(Some(Roi {
+            x: 0.5,
+            y: 0.0,
+            width: 0.5,
+            height: 0.5,
+        }));
+        let grid = converter.convert_rgb_frame_direct(&frame, 4, 2);
+        assert!(grid.iter().flatten().any(|cell| cell.ch != ' '));
+
+        converter.set_roi(None);
+        assert_eq!(converter.zoom(), 1.0);
+    }
+
+    #[test]
+    fn test_render_modes_cycle_and_set_cell_mode_and_edges() {
+        let mut converter = AsciiConverter::new_dense(8, 4);
+        assert_eq!(converter.render_mode(), RenderMode::Ascii);
+        let mut mode = RenderMode::default();
+        for _ in 0..5 {
+            assert_eq!(mode.next().previous(), mode);
+            converter.set_render_mode(mode);
+            assert_eq!(converter.render_mode(), mode);
+            assert_eq!(converter.cell_mode(), mode.cell_mode());
+            assert_eq!(converter.edge_detect(), mode == RenderMode::Edges);
+            mode = mode.next();
+        }
+        assert_eq!(mode, RenderMode::Ascii);
+
+        // Edge detection turned on separately reads as the Edges mode
+        converter.set_render_mode(RenderMode::Ascii);
+        converter.toggle_edge_detect();
+        assert_eq!(converter.render_mode(), RenderMode::Edges);
+    }
+
+    #[test]
+    fn test_color_modes_map_to_the_nearest_palette_color() {
+        assert_eq!(
+            ColorMode::Ansi16.quantize(Color::Rgb(250, 10, 5)),
+            Color::LightRed
+        );
+        assert_eq!(
+            ColorMode::Ansi16.quantize(Color::Rgb(20, 20, 20)),
+            Color::Black
+        );
+        // Cube corner and a gray that the cube would only approximate
+        assert_eq!(
+            ColorMode::Ansi256.quantize(Color::Rgb(255, 0, 0)),
+            Color::Indexed(196)
+        );
+        assert_eq!(
+            ColorMode::Ansi256.quantize(Color::Rgb(128, 128, 128)),
+            Color::Indexed(244)
+        );
+        assert_eq!(
+            ColorMode::Truecolor.quantize(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+        // Every palette color has a shade for exporters
+        assert_eq!(color_rgb(Color::LightRed), Some([255, 0, 0]));
+        assert_eq!(color_rgb(Color::Indexed(9)), Some([255, 0, 0]));
+        assert_eq!(color_rgb(Color::Indexed(196)), Some([255, 0, 0]));
+        assert_eq!(color_rgb(Color::Indexed(244)), Some([128, 128, 128]));
+        assert_eq!(color_rgb(Color::Reset), None);
+
+        let mut converter = AsciiConverter::new_dense(2, 1);
+        converter.set_color_mode(ColorMode::Ansi16);
+        converter.set_color_enabled(false);
+        assert!(!converter.color_enabled());
+        // Color comes back in the mode it was turned off in
+        converter.toggle_color();
+        assert_eq!(converter.color_mode(), ColorMode::Ansi16);
+        let mut grid = vec![vec![ColoredChar {
+            ch: '@',
+            style: Style::default().fg(Color::Rgb(0, 250, 0)),
+        }]];
+        converter.quantize_colors(&mut grid);
+        assert_eq!(grid[0][0].style.fg, Some(Color::LightGreen));
+    }
+
+    #[test]
+    fn test_tint_draws_every_character_in_one_color() {
+        assert_eq!(parse_rgb("#33ff33"), Some((0x33, 0xff, 0x33)));
+        assert_eq!(parse_rgb(" amber "), Some((255, 176, 0)));
+        assert_eq!(parse_rgb("#33ff3"), None);
+        assert_eq!(parse_rgb("33ff33"), None);
+        assert_eq!(parse_rgb("#3ff3é"), None);
+        assert_eq!(color_name((255, 176, 0)), "Amber");
+        assert_eq!(color_name((1, 2, 255)), "#0102ff");
+
+        let frame: Vec<u8> = (0..16u32 * 8)
+            .flat_map(|i| [(i * 2) as u8, 40, 200])
+            .collect();
+        let mut converter = AsciiConverter::new_dense(8, 4);
+        converter.set_tint(parse_rgb("#33ff33"));
+        let grid = converter.convert_rgb_frame_direct(&frame, 16, 8);
+        let green = Style::default().fg(Color::Rgb(0x33, 0xff, 0x33));
+        assert!(
+            grid.iter()
+                .flatten()
+                .all(|cell| cell.ch == ' ' || cell.style == green)
+        );
+        // Color output scales the tint by each pixel's brightness instead
+        converter.set_color_enabled(true);
+        let grid = converter.convert_rgb_frame_direct(&frame, 16, 8);
+        assert_ne!(grid[0][0].style, green);
+        assert!(matches!(grid[0][0].style.fg, Some(Color::Rgb(r, g, b)) if r < g && b < g));
+
+        // Cycling runs through the presets and back to off
+        converter.set_tint(Some((1, 2, 3)));
+        converter.next_tint();
+        assert_eq!(converter.tint(), Some(TINTS[0].1));
+        for _ in 1..TINTS.len() {
+            converter.next_tint();
+        }
+        assert_eq!(converter.tint(), Some((0, 255, 255)));
+        converter.next_tint();
+        assert_eq!(converter.tint(), None);
+    }
+}
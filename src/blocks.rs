@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+/// How a cell is drawn: a character from the ramp, or a block glyph splitting the cell into
+/// separately colored sub-cells
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellMode {
+    #[default]
+    Ramp,
+    /// Upper half block with the top half as foreground and the bottom as background,
+    /// doubling vertical resolution
+    HalfBlock,
+    /// 2x3 sextant glyphs (U+1FB00), which not every font has
+    Sextant,
+    /// 2x4 braille dots (U+2800) in one color, the finest detail per cell
+    Braille,
+}
+
+impl CellMode {
+    pub fn next(&self) -> CellMode {
+        match self {
+            CellMode::Ramp => CellMode::HalfBlock,
+            CellMode::HalfBlock => CellMode::Sextant,
+            CellMode::Sextant => CellMode::Braille,
+            CellMode::Braille => CellMode::Ramp,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CellMode::Ramp => "Characters",
+            CellMode::HalfBlock => "Half blocks",
+            CellMode::Sextant => "Sextants",
+            CellMode::Braille => "Braille",
+        }
+    }
+
+    /// Sub-cells across and down each cell
+    pub fn sub_cells(&self) -> (u32, u32) {
+        match self {
+            CellMode::Ramp => (1, 1),
+            CellMode::HalfBlock => (1, 2),
+            CellMode::Sextant => (2, 3),
+            CellMode::Braille => (2, 4),
+        }
+    }
+
+    /// Glyph covering the sub-cells set in `pattern`, bit 0 being the top-left sub-cell and
+    /// the rest following row by row
+    pub fn glyph(&self, pattern: u8) -> char {
+        match self {
+            CellMode::Ramp => ' ',
+            CellMode::HalfBlock => match pattern & 0b11 {
+                0 => ' ',
+                1 => '▀',
+                2 => '▄',
+                _ => '█',
+            },
+            CellMode::Sextant => sextant(pattern),
+            CellMode::Braille => braille(pattern),
+        }
+    }
+}
+
+/// Sextant glyph for a 6-bit pattern; the empty, full and half-column patterns aren't in
+/// the sextant block and use the older block elements
+fn sextant(pattern: u8) -> char {
+    let pattern = pattern & 0b11_1111;
+    match pattern {
+        0 => ' ',
+        0b01_0101 => '▌',
+        0b10_1010 => '▐',
+        0b11_1111 => '█',
+        _ => {
+            let skipped = (pattern > 0b01_0101) as u32 + (pattern > 0b10_1010) as u32;
+            char::from_u32(0x1FB00 + pattern as u32 - 1 - skipped).unwrap_or('?')
+        }
+    }
+}
+
+/// Braille dot bit for each sub-cell in row order
+const BRAILLE_DOTS: [u8; 8] = [0, 3, 1, 4, 2, 5, 6, 7];
+
+/// Braille glyph for an 8-bit pattern; braille numbers its dots down the left column first,
+/// with the bottom row added last
+fn braille(pattern: u8) -> char {
+    let dots = BRAILLE_DOTS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| pattern >> bit & 1 == 1)
+        .fold(0u32, |dots, (_, dot)| dots | 1 << dot);
+    char::from_u32(0x2800 + dots).unwrap_or('?')
+}
+
+/// The mode and sub-cell pattern a sextant or braille glyph was drawn from, the inverse of
+/// [`CellMode::glyph`] for glyphs outside the older block elements
+pub fn glyph_pattern(ch: char) -> Option<(CellMode, u8)> {
+    match ch as u32 {
+        code @ 0x1FB00..=0x1FB3B => {
+            // Step over the half-column patterns left out of the block
+            let mut pattern = code - 0x1FB00 + 1;
+            if pattern >= 0b01_0101 {
+                pattern += 1;
+            }
+            if pattern >= 0b10_1010 {
+                pattern += 1;
+            }
+            Some((CellMode::Sextant, pattern as u8))
+        }
+        code @ 0x2800..=0x28FF => {
+            let dots = code - 0x2800;
+            let pattern = BRAILLE_DOTS
+                .iter()
+                .enumerate()
+                .filter(|(_, dot)| dots >> **dot & 1 == 1)
+                .fold(0u8, |pattern, (bit, _)| pattern | 1 << bit);
+            Some((CellMode::Braille, pattern))
+        }
+        _ => None,
+    }
+}
+
+/// A cell split into lit and unlit sub-cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockCell {
+    /// Lit sub-cells, bit 0 being the first sample
+    pub pattern: u8,
+    /// Average color and luma of the lit sub-cells
+    pub fg: ([u8; 3], u8),
+    /// Average color and luma of the unlit ones, if any
+    pub bg: Option<([u8; 3], u8)>,
+}
+
+/// Average color and luma of all the samples
+pub fn average(samples: &[([u8; 3], u8)]) -> ([u8; 3], u8) {
+    let mut sum = [0u32; 4];
+    for ([r, g, b], luma) in samples {
+        for (total, value) in sum.iter_mut().zip([*r, *g, *b, *luma]) {
+            *total += value as u32;
+        }
+    }
+    let [r, g, b, luma] = sum.map(|total| (total / samples.len().max(1) as u32) as u8);
+    ([r, g, b], luma)
+}
+
+/// Luma splitting lit from unlit sub-cells where there's no mean to split around
+pub const MID_GRAY: u8 = 127;
+
+/// Braille dots to light for (color, luma) samples: those brighter than the cell's mean, so
+/// detail shows in dark and bright areas alike, or all of them in a flat cell brighter than
+/// [`MID_GRAY`]
+pub fn braille_pattern(samples: &[([u8; 3], u8)]) -> u8 {
+    let (_, mean) = average(samples);
+    let pattern = samples
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, luma))| *luma > mean)
+        .fold(0u8, |pattern, (bit, _)| pattern | 1 << bit);
+    if pattern == 0 && mean > MID_GRAY {
+        return (1u16 << samples.len()).wrapping_sub(1) as u8;
+    }
+    pattern
+}
+
+/// Split (color, luma) samples into lit and unlit sub-cells
+///
+/// With a `threshold`, sub-cells brighter than it are lit, as suits monochrome output.
+/// Without one they're split around their mean luma so each side gets its own color, and a
+/// flat cell is lit entirely.
+pub fn split(samples: &[([u8; 3], u8)], threshold: Option<u8>) -> BlockCell {
+    let mean =
+        samples.iter().map(|(_, luma)| *luma as u32).sum::<u32>() / samples.len().max(1) as u32;
+    let lit = |luma: u8| match threshold {
+        Some(threshold) => luma > threshold,
+        None => luma as u32 > mean,
+    };
+    let mut pattern = samples
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, luma))| lit(*luma))
+        .fold(0u8, |pattern, (bit, _)| pattern | 1 << bit);
+    if threshold.is_none() && pattern == 0 {
+        pattern = (1u16 << samples.len()).wrapping_sub(1) as u8;
+    }
+    let average = |lit: bool| {
+        let mut sum = [0u32; 4];
+        let mut count = 0;
+        for (bit, ([r, g, b], luma)) in samples.iter().enumerate() {
+            if (pattern >> bit & 1 == 1) == lit {
+                for (total, value) in sum.iter_mut().zip([*r, *g, *b, *luma]) {
+                    *total += value as u32;
+                }
+                count += 1;
+            }
+        }
+        (count > 0).then(|| {
+            let [r, g, b, luma] = sum.map(|total| (total / count) as u8);
+            ([r, g, b], luma)
+        })
+    };
+    BlockCell {
+        pattern,
+        fg: average(true).unwrap_or(([0; 3], 0)),
+        bg: average(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sextant_glyphs() {
+        assert_eq!(sextant(0b00_0001), '\u{1FB00}');
+        assert_eq!(sextant(0b01_0100), '\u{1FB13}');
+        assert_eq!(sextant(0b01_0110), '\u{1FB14}');
+        assert_eq!(sextant(0b10_1011), '\u{1FB28}');
+        assert_eq!(sextant(0b11_1110), '\u{1FB3B}');
+        assert_eq!(sextant(0b01_0101), '▌');
+        assert_eq!(sextant(0b11_1111), '█');
+        for pattern in (1..0b11_1111).filter(|p| ![0b01_0101, 0b10_1010].contains(p)) {
+            assert_eq!(
+                glyph_pattern(sextant(pattern)),
+                Some((CellMode::Sextant, pattern))
+            );
+        }
+        assert_eq!(glyph_pattern('▌'), None);
+    }
+
+    #[test]
+    fn test_braille_glyphs() {
+        assert_eq!(braille(0), '\u{2800}');
+        // Left column top to bottom, then the right column
+        assert_eq!(braille(0b0101_0101), '\u{2847}');
+        assert_eq!(braille(0b1010_1010), '\u{28B8}');
+        assert_eq!(braille(0b0000_0011), '\u{2809}');
+        assert_eq!(braille(0xFF), '⣿');
+        for pattern in 0..=0xFF {
+            assert_eq!(
+                glyph_pattern(braille(pattern)),
+                Some((CellMode::Braille, pattern))
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_separates_colors() {
+        let red = ([255, 0, 0], 76);
+        let blue = ([0, 0, 255], 29);
+        // Top row red, the two rows below blue
+        let cell = split(&[red, red, blue, blue, blue, blue], None);
+        assert_eq!(cell.pattern, 0b00_0011);
+        assert_eq!(cell.fg, red);
+        assert_eq!(cell.bg, Some(blue));
+        assert_eq!(CellMode::Sextant.glyph(cell.pattern), '\u{1FB02}');
+
+        let flat = split(&[blue, blue], None);
+        assert_eq!((flat.pattern, flat.bg), (0b11, None));
+        assert_eq!(CellMode::HalfBlock.glyph(flat.pattern), '█');
+
+        // A fixed threshold can leave every sub-cell unlit
+        let dark = split(&[blue, blue], Some(128));
+        assert_eq!(CellMode::HalfBlock.glyph(dark.pattern), ' ');
+
+        assert_eq!(average(&[red, blue]), ([127, 0, 127], 52));
+    }
+
+    #[test]
+    fn test_braille_dots_split_around_the_cell_mean() {
+        let dim = ([40, 40, 40], 40);
+        let darker = ([10, 10, 10], 10);
+        // A dark cell still shows its detail, the dim left column lit over the darker right
+        let samples = [dim, darker, dim, darker, dim, darker, dim, darker];
+        assert_eq!(CellMode::Braille.glyph(braille_pattern(&samples)), '⡇');
+
+        // Flat cells are lit only when bright
+        assert_eq!(braille_pattern(&[darker; 8]), 0);
+        assert_eq!(braille_pattern(&[([200; 3], 200); 8]), 0xFF);
+    }
+}
@@ -1,69 +1,478 @@
-use std::time::Duration;
+use std::{fmt, time::Duration};
 
+use clap::ValueEnum;
 use color_eyre::Result;
 use nokhwa::{
-    Camera,
+    Camera, NokhwaError,
     pixel_format::RgbFormat,
-    utils::{ApiBackend, CameraIndex, RequestedFormat, RequestedFormatType, Resolution},
+    utils::{
+        ApiBackend, CameraFormat, CameraIndex, ControlValueDescription, ControlValueSetter,
+        FrameFormat, KnownCameraControl, RequestedFormat, RequestedFormatType, Resolution,
+    },
 };
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use crate::action::Action;
 
+/// What to do about a denied camera, per platform
+#[cfg(target_os = "macos")]
+const PERMISSION_HINT: &str = "grant permission in System Settings > Privacy & Security > Camera";
+#[cfg(target_os = "windows")]
+const PERMISSION_HINT: &str = "allow camera access in Settings > Privacy & security > Camera";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const PERMISSION_HINT: &str = "add your user to the video group or check the device's udev rules";
+
+/// Fragments of backend error messages that mean the OS refused access. nokhwa has no
+/// dedicated error kind for this, so the text is all we have to go on
+const PERMISSION_PATTERNS: &[&str] = &[
+    "permission denied",
+    "os error 13",
+    "access denied",
+    "access is denied",
+    "not authorized",
+    "notauthorized",
+    "authorization",
+    "0x80070005",
+];
+
+/// The OS refused access to the camera, as opposed to a missing or busy device
+#[derive(Debug)]
+pub struct PermissionDenied;
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Camera access denied — {PERMISSION_HINT}, then press R to retry"
+        )
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+/// Whether a nokhwa error means the OS refused access to the device
+fn is_permission_error(error: &NokhwaError) -> bool {
+    let message = error.to_string().to_lowercase();
+    PERMISSION_PATTERNS
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
+
+/// Turn a nokhwa error into a report, singling out permission problems
+fn camera_error(error: NokhwaError) -> color_eyre::Report {
+    if is_permission_error(&error) {
+        warn!("Camera access denied: {}", error);
+        PermissionDenied.into()
+    } else {
+        error.into()
+    }
+}
+
+/// Capture backend to request from nokhwa
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum CameraBackend {
+    #[default]
+    Auto,
+    /// Video4Linux (Linux)
+    #[value(name = "v4l2")]
+    V4L2,
+    /// AVFoundation (macOS)
+    #[value(name = "avfoundation")]
+    AVFoundation,
+    /// Media Foundation (Windows)
+    #[value(name = "mediafoundation")]
+    MediaFoundation,
+}
+
+impl CameraBackend {
+    /// Whether this backend exists on the platform we were built for
+    pub fn is_supported(&self) -> bool {
+        match self {
+            CameraBackend::Auto => true,
+            CameraBackend::V4L2 => cfg!(target_os = "linux"),
+            CameraBackend::AVFoundation => cfg!(target_os = "macos"),
+            CameraBackend::MediaFoundation => cfg!(target_os = "windows"),
+        }
+    }
+
+    /// The nokhwa backend to use, falling back to Auto when unavailable on this platform
+    pub fn resolve(&self) -> ApiBackend {
+        if !self.is_supported() {
+            warn!(
+                "Camera backend {:?} is not available on this platform, using Auto",
+                self
+            );
+            return ApiBackend::Auto;
+        }
+        match self {
+            CameraBackend::Auto => ApiBackend::Auto,
+            CameraBackend::V4L2 => ApiBackend::Video4Linux,
+            CameraBackend::AVFoundation => ApiBackend::AVFoundation,
+            CameraBackend::MediaFoundation => ApiBackend::MediaFoundation,
+        }
+    }
+}
+
+/// How the capture format is negotiated with the camera
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResolutionRequest {
+    /// The supported format closest to the configured resolution and frame rate
+    #[default]
+    Closest,
+    /// The highest resolution the camera offers, regardless of the configured size
+    Highest,
+}
+
+impl ResolutionRequest {
+    fn format_type(
+        &self,
+        width: u32,
+        height: u32,
+        fps: u32,
+        format: FrameFormat,
+    ) -> RequestedFormatType {
+        match self {
+            ResolutionRequest::Closest => RequestedFormatType::Closest(CameraFormat::new(
+                Resolution::new(width, height),
+                format,
+                fps,
+            )),
+            ResolutionRequest::Highest => RequestedFormatType::AbsoluteHighestResolution,
+        }
+    }
+}
+
+/// Pixels sampled per cell along its width; cells are twice as tall, so they get twice as many rows
+pub const CAPTURE_SAMPLES_PER_CELL: u32 = 2;
+
+/// Names used by virtual and loopback devices (OBS Virtual Camera, v4l2loopback)
+pub const VIRTUAL_CAMERA_PATTERNS: &[&str] = &["virtual", "dummy", "loopback"];
+
+/// Tag appended to virtual device names when they are listed
+const VIRTUAL_CAMERA_TAG: &str = " [virtual]";
+
+/// Which detected devices `list_cameras` keeps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CameraFilter {
+    /// Case-insensitive name substrings marking a device as virtual
+    pub exclude_patterns: Vec<String>,
+    /// List virtual devices anyway, tagged
+    pub include_virtual: bool,
+    /// Keep only the first device of each name; otherwise repeats are numbered
+    pub dedup_names: bool,
+}
+
+impl Default for CameraFilter {
+    fn default() -> Self {
+        Self {
+            exclude_patterns: VIRTUAL_CAMERA_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            include_virtual: false,
+            dedup_names: true,
+        }
+    }
+}
+
+/// A camera found by [`CameraCapture::list_cameras`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CameraInfo {
+    /// System index the camera is opened by
+    pub index: u32,
+    /// Name to show, tagged when the device is virtual
+    pub name: String,
+    /// Backend-specific description, e.g. the device path
+    pub description: String,
+    /// Supported (width, height, fps) formats, smallest first
+    pub formats: Vec<(u32, u32, u32)>,
+}
+
+impl CameraInfo {
+    /// Formats as "1280x720@30" labels for a menu
+    pub fn format_labels(&self) -> Vec<String> {
+        self.formats
+            .iter()
+            .map(|(width, height, fps)| format!("{width}x{height}@{fps}"))
+            .collect()
+    }
+}
+
+/// Reduce formats to distinct (width, height, fps) triples, smallest and slowest first;
+/// pixel formats are left out since frames are decoded either way
+pub fn camera_formats(formats: &[CameraFormat]) -> Vec<(u32, u32, u32)> {
+    let mut formats: Vec<(u32, u32, u32)> = formats
+        .iter()
+        .map(|format| (format.width(), format.height(), format.frame_rate()))
+        .collect();
+    formats.sort_by_key(|&(width, height, fps)| (width as u64 * height as u64, width, fps));
+    formats.dedup();
+    formats
+}
+
+/// Drop duplicate names and, unless asked for, virtual devices, keeping system indices
+pub fn filter_cameras(devices: Vec<(u32, String)>, filter: &CameraFilter) -> Vec<(u32, String)> {
+    let patterns: Vec<String> = filter
+        .exclude_patterns
+        .iter()
+        .map(|p| p.to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let mut camera_list = Vec::new();
+    let mut seen_names = std::collections::HashMap::new();
+    for (index, name) in devices {
+        let lower = name.to_lowercase();
+        let is_virtual = patterns.iter().any(|p| lower.contains(p.as_str()));
+        let seen = seen_names.entry(name.clone()).or_insert(0u32);
+        *seen += 1;
+        if (*seen > 1 && filter.dedup_names) || (is_virtual && !filter.include_virtual) {
+            debug!(
+                "Filtered out camera {}: {} (duplicate or virtual)",
+                index, name
+            );
+            continue;
+        }
+        // Identical cameras stay apart by the order they were found in
+        let name = if *seen > 1 {
+            format!("{name} ({seen})")
+        } else {
+            name
+        };
+        let name = if is_virtual {
+            format!("{name}{VIRTUAL_CAMERA_TAG}")
+        } else {
+            name
+        };
+        info!("Added camera with system ID {}: {}", index, name);
+        camera_list.push((index, name));
+    }
+    camera_list
+}
+
+/// Pick the capture resolution that best serves an ASCII grid of `grid_width` x `grid_height`
+///
+/// The ideal capture gives every cell a small integer block of pixels: `CAPTURE_SAMPLES_PER_CELL`
+/// columns by twice that many rows, since terminal cells are about twice as tall as they are wide.
+/// Fewer pixels than that throws away detail, many more only costs decoding time. Cameras only
+/// offer a handful of modes, so this returns the smallest `supported` resolution that covers the
+/// ideal size, preferring the aspect ratio of `current`, or the largest one if none is big enough.
+/// With no supported list it returns the ideal size itself.
+pub fn capture_resolution_for_grid(
+    grid_width: u32,
+    grid_height: u32,
+    current: (u32, u32),
+    supported: &[(u32, u32)],
+) -> (u32, u32) {
+    let ideal = (
+        grid_width * CAPTURE_SAMPLES_PER_CELL,
+        grid_height * CAPTURE_SAMPLES_PER_CELL * 2,
+    );
+    if supported.is_empty() {
+        return ideal;
+    }
+
+    let aspect = |(width, height): (u32, u32)| width as f32 / height.max(1) as f32;
+    let same_aspect: Vec<(u32, u32)> = supported
+        .iter()
+        .copied()
+        .filter(|&resolution| (aspect(resolution) - aspect(current)).abs() < 0.05)
+        .collect();
+    let candidates = if same_aspect.is_empty() {
+        supported
+    } else {
+        &same_aspect
+    };
+
+    let area = |(width, height): (u32, u32)| width as u64 * height as u64;
+    candidates
+        .iter()
+        .copied()
+        .filter(|&(width, height)| width >= ideal.0 && height >= ideal.1)
+        .min_by_key(|&resolution| area(resolution))
+        .or_else(|| {
+            candidates
+                .iter()
+                .copied()
+                .max_by_key(|&resolution| area(resolution))
+        })
+        .unwrap_or(ideal)
+}
+
+/// Common capture sizes stepped through when the camera doesn't report its own
+pub const RESOLUTION_LADDER: &[(u32, u32)] = &[(320, 240), (640, 480), (1280, 720), (1920, 1080)];
+
+/// The next resolution up or down from `current` by pixel count, among the `supported` ones or
+/// [`RESOLUTION_LADDER`] when there are none; `None` at either end
+pub fn step_resolution(
+    current: (u32, u32),
+    supported: &[(u32, u32)],
+    up: bool,
+) -> Option<(u32, u32)> {
+    let ladder = if supported.is_empty() {
+        RESOLUTION_LADDER
+    } else {
+        supported
+    };
+    let area = |(width, height): (u32, u32)| width as u64 * height as u64;
+    let candidates = ladder.iter().copied();
+    if up {
+        candidates
+            .filter(|&resolution| area(resolution) > area(current))
+            .min_by_key(|&resolution| area(resolution))
+    } else {
+        candidates
+            .filter(|&resolution| area(resolution) < area(current))
+            .max_by_key(|&resolution| area(resolution))
+    }
+}
+
+/// Capture rates stepped through live, from a slideshow to as smooth as terminals go
+pub const FPS_LADDER: &[u32] = &[1, 2, 5, 10, 15, 20, 24, 30, 45, 60];
+
+/// The next rate up or down [`FPS_LADDER`] from `current`; `None` at either end
+pub fn step_fps(current: f64, up: bool) -> Option<u32> {
+    let candidates = FPS_LADDER.iter().copied();
+    if up {
+        candidates.filter(|&fps| fps as f64 > current).min()
+    } else {
+        candidates.filter(|&fps| (fps as f64) < current).max()
+    }
+}
+
+/// Key presses needed to sweep a hardware control across its whole range
+const CONTROL_STEPS: i64 = 20;
+
+/// Failed captures in a row after which the camera counts as unplugged
+pub const DISCONNECT_AFTER_FAILURES: u32 = 10;
+
+/// Camera to reopen after a disconnect: the same index if it's back, otherwise the first
+/// one detected, since a replugged USB camera often comes back under a new index
+pub fn reconnect_index(previous: u32, cameras: &[(u32, String)]) -> Option<u32> {
+    if cameras.iter().any(|(index, _)| *index == previous) {
+        return Some(previous);
+    }
+    cameras.first().map(|(index, _)| *index)
+}
+
+/// Move a control `steps` key presses from `value`, each at least the control's own step
+/// and together covering the range in [`CONTROL_STEPS`] presses, clamped to the range
+pub fn step_control(value: i64, min: i64, max: i64, step: i64, steps: i64) -> i64 {
+    let stride = step.max(1).max((max - min) / CONTROL_STEPS);
+    value
+        .saturating_add(stride.saturating_mul(steps))
+        .clamp(min, max)
+}
+
 pub struct CameraCapture {
     camera: Option<Camera>,
     is_active: bool,
     frame_sender: Option<mpsc::UnboundedSender<Action>>,
     last_frame_time: std::time::Instant,
+    /// Frames asked for sooner than this after the last one are skipped
     frame_skip_threshold: Duration,
+    burst_mode: bool,
+    backend: ApiBackend,
+    /// Pixel format asked for when the camera is opened
+    frame_format: FrameFormat,
+    /// Send YUYV frames undecoded for conversion straight from their luma
+    luma_only: bool,
+    /// Captures that failed in a row, reset by a good frame or a restart
+    consecutive_failures: u32,
 }
 
 impl CameraCapture {
-    pub fn new() -> Self {
+    pub fn new(backend: CameraBackend) -> Self {
         Self {
             camera: None,
             is_active: false,
             frame_sender: None,
             last_frame_time: std::time::Instant::now(),
-            frame_skip_threshold: Duration::from_millis(50), // ~20 FPS max for better responsiveness
+            frame_skip_threshold: Duration::from_millis(50),
+            burst_mode: false,
+            backend: backend.resolve(),
+            frame_format: FrameFormat::MJPEG,
+            luma_only: false,
+            consecutive_failures: 0,
         }
     }
 
-    /// Initialize camera with specified index and resolution
+    /// Deliver at most `fps` frames per second, however often frames are asked for
+    pub fn set_target_fps(&mut self, fps: f64) {
+        self.frame_skip_threshold = Duration::from_secs_f64(1.0 / fps.max(1.0));
+    }
+
+    /// Ask for YUYV instead of MJPEG the next time the camera is initialized
+    pub fn set_yuyv(&mut self, enabled: bool) {
+        self.frame_format = if enabled {
+            FrameFormat::YUYV
+        } else {
+            FrameFormat::MJPEG
+        };
+    }
+
+    /// Initialize camera with specified index, resolution and frame rate
     pub fn initialize(
         &mut self,
         camera_index: u32,
         width: u32,
         height: u32,
+        fps: u32,
+        request: ResolutionRequest,
         frame_sender: mpsc::UnboundedSender<Action>,
     ) -> Result<()> {
         info!(
-            "Initializing camera {} with resolution {}x{}",
-            camera_index, width, height
+            "Initializing camera {} with resolution {}x{} ({:?})",
+            camera_index, width, height, request
         );
 
         let index = CameraIndex::Index(camera_index);
-        let requested =
-            RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
+        let requested = RequestedFormat::new::<RgbFormat>(request.format_type(
+            width,
+            height,
+            fps,
+            self.frame_format,
+        ));
 
         debug!(
-            "Creating camera with index: {:?}, format: {:?}",
-            index, requested
+            "Creating camera with index: {:?}, format: {:?}, backend: {:?}",
+            index, requested, self.backend
         );
 
-        match Camera::new(index, requested) {
-            Ok(mut camera) => {
-                debug!("Camera created successfully, setting resolution");
-                // Try to set the requested resolution
-                if let Err(e) = camera.set_resolution(Resolution::new(width, height)) {
-                    warn!(
-                        "Failed to set resolution {}x{}: {}, using default",
-                        width, height, e
-                    );
-                }
+        let mut camera = self.open_camera(index.clone(), requested);
+        let denied = camera.as_ref().is_err_and(is_permission_error);
+        if camera.is_err() && !denied && request == ResolutionRequest::Closest {
+            // Webcams that only stream YUYV (or only MJPEG) still get the requested size in
+            // their own format, and YUYV ones then take the luma path in monochrome
+            let native = if self.frame_format == FrameFormat::YUYV {
+                FrameFormat::MJPEG
+            } else {
+                FrameFormat::YUYV
+            };
+            debug!(
+                "No {:?} format close to {}x{}, trying {:?}",
+                self.frame_format, width, height, native
+            );
+            camera = self.open_camera(
+                index.clone(),
+                RequestedFormat::new::<RgbFormat>(request.format_type(width, height, fps, native)),
+            );
+        }
+        if camera.is_err() && !denied && request == ResolutionRequest::Closest {
+            // Some drivers can't match a closest format at all; let them pick
+            warn!(
+                "No format close to {}x{}, falling back to the highest resolution",
+                width, height
+            );
+            let highest = RequestedFormat::new::<RgbFormat>(
+                ResolutionRequest::Highest.format_type(width, height, fps, self.frame_format),
+            );
+            camera = self.open_camera(index, highest);
+        }
 
-                // Don't open stream here - wait for start() to be called
+        match camera {
+            Ok(camera) => {
                 let actual_resolution = camera.resolution();
                 info!(
                     "Camera initialized successfully with resolution: {}x{} (stream not yet opened)",
@@ -77,14 +486,27 @@ impl CameraCapture {
             }
             Err(e) => {
                 error!("Failed to initialize camera: {}", e);
-                Err(e.into())
+                Err(camera_error(e))
             }
         }
     }
 
+    fn open_camera(
+        &self,
+        index: CameraIndex,
+        requested: RequestedFormat,
+    ) -> std::result::Result<Camera, nokhwa::NokhwaError> {
+        if self.backend == ApiBackend::Auto {
+            Camera::new(index, requested)
+        } else {
+            Camera::with_backend(index, requested, self.backend)
+        }
+    }
+
     /// Start capturing frames
     pub fn start(&mut self) -> Result<()> {
         debug!("start() called, current active state: {}", self.is_active);
+        self.consecutive_failures = 0;
         if self.camera.is_none() {
             error!("Cannot start: camera not initialized");
             return Err(color_eyre::eyre::eyre!("Camera not initialized"));
@@ -100,7 +522,7 @@ impl CameraCapture {
                     }
                     Err(e) => {
                         error!("Failed to open camera stream: {}", e);
-                        return Err(e.into());
+                        return Err(camera_error(e));
                     }
                 }
             }
@@ -110,6 +532,14 @@ impl CameraCapture {
 
         self.is_active = true;
         info!("Camera capture started successfully");
+        match self.list_controls() {
+            Ok(controls) => {
+                for (name, min, max, value) in controls {
+                    debug!("Camera control {}: {} ({}..={})", name, value, min, max);
+                }
+            }
+            Err(e) => debug!("Camera controls unavailable: {}", e),
+        }
         Ok(())
     }
 
@@ -203,6 +633,161 @@ impl CameraCapture {
         self.is_active
     }
 
+    /// Bypass the frame-skip limiter so frames are captured as fast as the camera allows
+    pub fn set_burst_mode(&mut self, enabled: bool) {
+        debug!("Burst mode: {}", enabled);
+        self.burst_mode = enabled;
+    }
+
+    /// Send YUYV frames as [`Action::CameraYuyvFrame`] without decoding them, for
+    /// monochrome output that only needs their luma; other formats are decoded as usual
+    pub fn set_luma_only(&mut self, enabled: bool) {
+        if enabled != self.luma_only {
+            debug!("Luma-only capture: {}", enabled);
+        }
+        self.luma_only = enabled;
+    }
+
+    /// Resolutions the camera reports for any pixel format, smallest first
+    pub fn supported_resolutions(&mut self) -> Vec<(u32, u32)> {
+        let Some(camera) = self.camera.as_mut() else {
+            return Vec::new();
+        };
+        match camera.compatible_camera_formats() {
+            Ok(formats) => {
+                let mut resolutions: Vec<(u32, u32)> = formats
+                    .iter()
+                    .map(|format| (format.width(), format.height()))
+                    .collect();
+                resolutions.sort_by_key(|&(width, height)| width as u64 * height as u64);
+                resolutions.dedup();
+                resolutions
+            }
+            Err(e) => {
+                warn!("Failed to query supported resolutions: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// The largest resolution the camera reports, if it reports any
+    pub fn highest_resolution(&mut self) -> Option<(u32, u32)> {
+        self.supported_resolutions().last().copied()
+    }
+
+    /// Change the capture resolution, returning the resolution the camera actually chose
+    ///
+    /// Many backends can't change resolution while streaming, so an open stream is stopped
+    /// and reopened around the change.
+    pub fn set_resolution(&mut self, width: u32, height: u32) -> Result<(u32, u32)> {
+        let camera = match &mut self.camera {
+            Some(cam) => cam,
+            None => return Err(color_eyre::eyre::eyre!("Camera not initialized")),
+        };
+
+        info!("Changing capture resolution to {}x{}", width, height);
+        if self.is_active {
+            camera.stop_stream()?;
+        }
+        let result = camera.set_resolution(Resolution::new(width, height));
+        if self.is_active {
+            // Reopen even if the change failed so capture carries on at the old resolution
+            camera.open_stream()?;
+        }
+        result?;
+
+        let actual = camera.resolution();
+        info!(
+            "Capture resolution is now {}x{}",
+            actual.width(),
+            actual.height()
+        );
+        Ok((actual.width(), actual.height()))
+    }
+
+    /// Integer hardware controls the camera reports, as (name, min, max, current)
+    ///
+    /// Controls without a range, like on/off switches, are left out.
+    pub fn list_controls(&self) -> Result<Vec<(String, i64, i64, i64)>> {
+        let camera = match &self.camera {
+            Some(cam) => cam,
+            None => return Err(color_eyre::eyre::eyre!("Camera not initialized")),
+        };
+        Ok(camera
+            .camera_controls()?
+            .iter()
+            .filter_map(|control| match *control.description() {
+                ControlValueDescription::IntegerRange {
+                    min, max, value, ..
+                } => Some((control.name().to_string(), min, max, value)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Set the hardware brightness, returning the value now in effect
+    pub fn set_brightness(&mut self, value: i64) -> Option<i64> {
+        self.set_control(KnownCameraControl::Brightness, |_, _, _| value)
+    }
+
+    /// Set the hardware exposure, returning the value now in effect
+    pub fn set_exposure(&mut self, value: i64) -> Option<i64> {
+        self.set_control(KnownCameraControl::Exposure, |_, _, _| value)
+    }
+
+    /// Move the hardware exposure `steps` key presses up or down its range, returning the
+    /// value now in effect
+    pub fn adjust_exposure(&mut self, steps: i64) -> Option<i64> {
+        self.set_control(KnownCameraControl::Exposure, |value, (min, max), step| {
+            step_control(value, min, max, step, steps)
+        })
+    }
+
+    /// Set an integer control to `target(current, (min, max), step)`, clamped to its range
+    ///
+    /// A camera or backend without the control, or one that refuses the value, is logged
+    /// and left as it was, giving `None`.
+    fn set_control(
+        &mut self,
+        control: KnownCameraControl,
+        target: impl FnOnce(i64, (i64, i64), i64) -> i64,
+    ) -> Option<i64> {
+        let camera = self.camera.as_mut()?;
+        let (min, max, value, step) = match camera.camera_control(control) {
+            Ok(current) => match *current.description() {
+                ControlValueDescription::IntegerRange {
+                    min,
+                    max,
+                    value,
+                    step,
+                    ..
+                } => (min, max, value, step),
+                _ => {
+                    warn!("Camera control {} isn't an integer range", control);
+                    return None;
+                }
+            },
+            Err(e) => {
+                warn!("Camera has no {} control: {}", control, e);
+                return None;
+            }
+        };
+        let new_value = target(value, (min, max), step).clamp(min, max);
+        if new_value == value {
+            return Some(value);
+        }
+        match camera.set_camera_control(control, ControlValueSetter::Integer(new_value)) {
+            Ok(()) => {
+                info!("Camera {} set to {}", control, new_value);
+                Some(new_value)
+            }
+            Err(e) => {
+                warn!("Failed to set camera {} to {}: {}", control, new_value, e);
+                None
+            }
+        }
+    }
+
     /// Check if camera stream is actually running (not just our flag)
     #[allow(dead_code)]
     pub fn is_stream_open(&self) -> bool {
@@ -215,15 +800,27 @@ impl CameraCapture {
         }
     }
 
+    /// Whether captures kept failing long enough that the device is most likely gone
+    pub fn is_disconnected(&self) -> bool {
+        self.consecutive_failures >= DISCONNECT_AFTER_FAILURES
+    }
+
     /// Capture a single frame and send it via the action channel
+    ///
+    /// After [`DISCONNECT_AFTER_FAILURES`] failures in a row, [`Action::CameraDisconnected`]
+    /// is sent once and every later call fails straight away until the camera is restarted.
     pub fn capture_frame(&mut self) -> Result<()> {
         if !self.is_active {
             return Ok(());
         }
+        if self.is_disconnected() {
+            return Err(color_eyre::eyre::eyre!("Camera disconnected"));
+        }
 
-        // Frame rate limiting - skip if too soon since last frame
+        // Frame rate limiting - skip if too soon since last frame (disabled during a burst)
         let now = std::time::Instant::now();
-        if now.duration_since(self.last_frame_time) < self.frame_skip_threshold {
+        if !self.burst_mode && now.duration_since(self.last_frame_time) < self.frame_skip_threshold
+        {
             return Ok(());
         }
 
@@ -239,7 +836,25 @@ impl CameraCapture {
 
         match camera.frame() {
             Ok(frame) => {
+                let captured = std::time::Instant::now();
                 self.last_frame_time = now;
+                self.consecutive_failures = 0;
+
+                if self.luma_only && frame.source_frame_format() == FrameFormat::YUYV {
+                    let resolution = frame.resolution();
+                    let (width, height) = (resolution.width(), resolution.height());
+                    debug!("Captured YUYV frame: {}x{}", width, height);
+                    if let Err(e) = frame_sender.send(Action::CameraYuyvFrame(
+                        frame.buffer().to_vec(),
+                        width,
+                        height,
+                        captured,
+                    )) {
+                        debug!("Skipped frame due to channel full: {}", e);
+                    }
+                    let _ = frame_sender.send(Action::FrameCaptured);
+                    return Ok(());
+                }
 
                 // Convert frame to RGB format first to get dimensions
                 let rgb_frame = frame
@@ -252,19 +867,31 @@ impl CameraCapture {
 
                 // Send frame data through action channel with dimensions
                 // Use try_send to avoid blocking if the channel is full (frame skipping)
-                if let Err(e) =
-                    frame_sender.send(Action::CameraFrame(rgb_frame.into_raw(), width, height))
-                {
+                if let Err(e) = frame_sender.send(Action::CameraFrame(
+                    rgb_frame.into_raw(),
+                    width,
+                    height,
+                    captured,
+                )) {
                     // Channel full or closed - skip this frame to prevent backup
                     debug!("Skipped frame due to channel full: {}", e);
                 }
+                let _ = frame_sender.send(Action::FrameCaptured);
                 Ok(())
             }
             Err(e) => {
                 error!("Failed to capture frame: {}", e);
-                if let Err(send_err) =
-                    frame_sender.send(Action::CameraError(format!("Frame capture failed: {e}")))
-                {
+                self.consecutive_failures += 1;
+                let action = if self.is_disconnected() {
+                    warn!(
+                        "{} captures failed in a row, treating the camera as disconnected",
+                        self.consecutive_failures
+                    );
+                    Action::CameraDisconnected
+                } else {
+                    Action::CameraError(format!("Frame capture failed: {e}"))
+                };
+                if let Err(send_err) = frame_sender.send(action) {
                     error!("Failed to send camera error: {}", send_err);
                 }
                 Err(e.into())
@@ -272,41 +899,75 @@ impl CameraCapture {
         }
     }
 
-    /// Get available cameras
-    pub fn list_cameras() -> Result<Vec<(u32, String)>> {
-        debug!("Querying available cameras...");
-        match nokhwa::query(ApiBackend::Auto) {
+    /// Get available cameras along with the formats each one supports
+    ///
+    /// Virtual devices such as OBS Virtual Camera or v4l2loopback are left out unless
+    /// `filter.include_virtual` is set, in which case their names are tagged. Every camera
+    /// is opened briefly to ask for its formats; one that can't be opened, e.g. because it
+    /// is in use, is listed without any.
+    pub fn list_cameras(backend: CameraBackend, filter: &CameraFilter) -> Result<Vec<CameraInfo>> {
+        let backend = backend.resolve();
+        let mut cameras = Self::query_cameras(backend, filter)?;
+        for camera in &mut cameras {
+            camera.formats = Self::query_formats(backend, camera.index);
+            debug!(
+                "Camera {} supports {} formats",
+                camera.index,
+                camera.formats.len()
+            );
+        }
+        Ok(cameras)
+    }
+
+    /// Get available cameras as (system index, name) pairs, without opening them
+    pub fn list_camera_names(
+        backend: CameraBackend,
+        filter: &CameraFilter,
+    ) -> Result<Vec<(u32, String)>> {
+        Ok(Self::query_cameras(backend.resolve(), filter)?
+            .into_iter()
+            .map(|camera| (camera.index, camera.name))
+            .collect())
+    }
+
+    /// Enumerate and filter the cameras, leaving their formats empty
+    fn query_cameras(backend: ApiBackend, filter: &CameraFilter) -> Result<Vec<CameraInfo>> {
+        debug!("Querying available cameras with backend {:?}...", backend);
+        match nokhwa::query(backend) {
             Ok(cameras) => {
                 debug!("Raw camera query returned {} cameras", cameras.len());
-                let mut camera_list: Vec<(u32, String)> = Vec::new();
-                let mut seen_names = std::collections::HashSet::new();
-
-                for (i, info) in cameras.into_iter().enumerate() {
-                    let name = info.human_name().to_string();
-                    debug!(
-                        "Raw camera {}: {} (desc: {:?})",
-                        i,
+                let mut descriptions = std::collections::HashMap::new();
+                let devices = cameras
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, info)| {
+                        let name = info.human_name().to_string();
+                        debug!(
+                            "Raw camera {}: {} (desc: {:?})",
+                            i,
+                            name,
+                            info.description()
+                        );
+                        // The index the device is opened by, e.g. N in /dev/videoN, which
+                        // differs from the query position when there are gaps
+                        let index = info.index().as_index().unwrap_or(i as u32);
+                        descriptions.insert(index, info.description().to_string());
+                        (index, name)
+                    })
+                    .collect();
+                let camera_list: Vec<CameraInfo> = filter_cameras(devices, filter)
+                    .into_iter()
+                    .map(|(index, name)| CameraInfo {
+                        index,
                         name,
-                        info.description()
-                    );
-
-                    // Filter out duplicate cameras and virtual cameras
-                    if !seen_names.contains(&name)
-                        && !name.to_lowercase().contains("virtual")
-                        && !name.to_lowercase().contains("dummy")
-                    {
-                        // Keep the original system index, not the filtered position
-                        camera_list.push((i as u32, name.clone()));
-                        seen_names.insert(name.clone());
-                        info!("Added camera with system ID {}: {}", i, name);
-                    } else {
-                        debug!("Filtered out camera {}: {} (duplicate or virtual)", i, name);
-                    }
-                }
+                        description: descriptions.remove(&index).unwrap_or_default(),
+                        formats: Vec::new(),
+                    })
+                    .collect();
 
                 info!("Found {} cameras after filtering", camera_list.len());
-                for (index, name) in &camera_list {
-                    info!("Camera {}: {}", index, name);
+                for camera in &camera_list {
+                    info!("Camera {}: {}", camera.index, camera.name);
                 }
 
                 Ok(camera_list)
@@ -318,8 +979,24 @@ impl CameraCapture {
         }
     }
 
+    /// Open a camera without streaming just to read the formats it supports
+    fn query_formats(backend: ApiBackend, index: u32) -> Vec<(u32, u32, u32)> {
+        let requested = RequestedFormat::new::<RgbFormat>(RequestedFormatType::None);
+        let opened = if backend == ApiBackend::Auto {
+            Camera::new(CameraIndex::Index(index), requested)
+        } else {
+            Camera::with_backend(CameraIndex::Index(index), requested, backend)
+        };
+        match opened.and_then(|mut camera| camera.compatible_camera_formats()) {
+            Ok(formats) => camera_formats(&formats),
+            Err(e) => {
+                warn!("Failed to query formats of camera {}: {}", index, e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Get current camera resolution
-    #[allow(dead_code)]
     pub fn get_resolution(&self) -> Option<(u32, u32)> {
         self.camera.as_ref().map(|cam| {
             let res = cam.resolution();
@@ -344,6 +1021,8 @@ impl CameraCapture {
         }
 
         self.is_active = false;
+        self.burst_mode = false;
+        self.consecutive_failures = 0;
         self.frame_sender = None;
         self.last_frame_time = std::time::Instant::now();
         info!("Camera cleanup completed");
@@ -380,3 +1059,182 @@ pub async fn camera_capture_loop(mut camera: CameraCapture, fps: f64) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [(u32, u32); 5] = [
+        (320, 240),
+        (640, 480),
+        (1280, 720),
+        (1920, 1080),
+        (800, 600),
+    ];
+
+    #[test]
+    fn test_capture_resolution_covers_grid() {
+        // 150x50 cells want 300x200 pixels, the smallest 4:3 mode covering that is 320x240
+        assert_eq!(
+            capture_resolution_for_grid(150, 50, (640, 480), &MODES),
+            (320, 240)
+        );
+        // 300x100 cells want 600x400 pixels
+        assert_eq!(
+            capture_resolution_for_grid(300, 100, (640, 480), &MODES),
+            (640, 480)
+        );
+        // Keeps 16:9 when that's what the camera is using
+        assert_eq!(
+            capture_resolution_for_grid(300, 100, (1280, 720), &MODES),
+            (1280, 720)
+        );
+    }
+
+    #[test]
+    fn test_step_resolution() {
+        assert_eq!(step_resolution((640, 480), &MODES, true), Some((800, 600)));
+        assert_eq!(step_resolution((640, 480), &MODES, false), Some((320, 240)));
+        assert_eq!(step_resolution((1920, 1080), &MODES, true), None);
+        assert_eq!(step_resolution((320, 240), &MODES, false), None);
+        // A size between modes steps to its neighbours
+        assert_eq!(
+            step_resolution((1000, 700), &MODES, false),
+            Some((800, 600))
+        );
+        // Without a list the built-in ladder is used
+        assert_eq!(step_resolution((640, 480), &[], true), Some((1280, 720)));
+        assert_eq!(step_resolution((100, 100), &[], true), Some((320, 240)));
+    }
+
+    #[test]
+    fn test_step_control() {
+        // Exposure from 3 to 2047 in steps of 1 moves a twentieth of the range per press
+        assert_eq!(step_control(100, 3, 2047, 1, 1), 202);
+        assert_eq!(step_control(100, 3, 2047, 1, -1), 3);
+        // A coarse control keeps its own step
+        assert_eq!(step_control(0, -64, 64, 16, 1), 16);
+        assert_eq!(step_control(64, -64, 64, 16, 1), 64);
+    }
+
+    #[test]
+    fn test_step_fps() {
+        assert_eq!(step_fps(30.0, true), Some(45));
+        assert_eq!(step_fps(30.0, false), Some(24));
+        // Rates off the ladder step to their neighbours
+        assert_eq!(step_fps(29.97, true), Some(30));
+        assert_eq!(step_fps(29.97, false), Some(24));
+        assert_eq!(step_fps(60.0, true), None);
+        assert_eq!(step_fps(1.0, false), None);
+        // Above the ladder, stepping down comes back to its top
+        assert_eq!(step_fps(120.0, false), Some(60));
+    }
+
+    #[test]
+    fn test_capture_resolution_falls_back_to_largest() {
+        assert_eq!(
+            capture_resolution_for_grid(1000, 400, (640, 480), &MODES),
+            (800, 600)
+        );
+        assert_eq!(
+            capture_resolution_for_grid(100, 30, (640, 480), &[]),
+            (200, 120)
+        );
+    }
+
+    #[test]
+    fn test_virtual_cameras_are_opt_in_and_keep_system_indices() {
+        let devices = vec![
+            (0, "Integrated Webcam".to_string()),
+            (2, "OBS Virtual Camera".to_string()),
+            (10, "Dummy video device (0x0000)".to_string()),
+            (11, "Integrated Webcam".to_string()),
+        ];
+        let mut filter = CameraFilter::default();
+        assert_eq!(
+            filter_cameras(devices.clone(), &filter),
+            vec![(0, "Integrated Webcam".to_string())]
+        );
+        filter.include_virtual = true;
+        assert_eq!(
+            filter_cameras(devices, &filter),
+            vec![
+                (0, "Integrated Webcam".to_string()),
+                (2, "OBS Virtual Camera [virtual]".to_string()),
+                (10, "Dummy video device (0x0000) [virtual]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_camera_formats_are_distinct_and_labelled() {
+        let formats = [
+            CameraFormat::new_from(1280, 720, FrameFormat::MJPEG, 30),
+            CameraFormat::new_from(640, 480, FrameFormat::YUYV, 30),
+            CameraFormat::new_from(1280, 720, FrameFormat::YUYV, 10),
+            CameraFormat::new_from(640, 480, FrameFormat::MJPEG, 30),
+        ];
+        let camera = CameraInfo {
+            index: 0,
+            name: "USB Camera".to_string(),
+            description: "/dev/video0".to_string(),
+            formats: camera_formats(&formats),
+        };
+        assert_eq!(
+            camera.format_labels(),
+            vec!["640x480@30", "1280x720@10", "1280x720@30"]
+        );
+    }
+
+    #[test]
+    fn test_camera_filter_patterns_and_duplicates_are_configurable() {
+        let devices = vec![
+            (0, "USB Camera".to_string()),
+            (2, "OBS Virtual Camera".to_string()),
+            (4, "USB Camera".to_string()),
+            (10, "Dummy video device".to_string()),
+        ];
+        let filter = CameraFilter {
+            exclude_patterns: vec!["DUMMY".to_string()],
+            include_virtual: false,
+            dedup_names: false,
+        };
+        assert_eq!(
+            filter_cameras(devices, &filter),
+            vec![
+                (0, "USB Camera".to_string()),
+                (2, "OBS Virtual Camera".to_string()),
+                (4, "USB Camera (2)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_permission_errors_are_recognized() {
+        let denied = NokhwaError::OpenDeviceError(
+            "/dev/video0".to_string(),
+            "Permission denied (os error 13)".to_string(),
+        );
+        let busy = NokhwaError::OpenDeviceError(
+            "/dev/video0".to_string(),
+            "Device or resource busy (os error 16)".to_string(),
+        );
+        assert!(is_permission_error(&denied));
+        assert!(!is_permission_error(&busy));
+        assert!(camera_error(denied).is::<PermissionDenied>());
+        assert!(!camera_error(busy).is::<PermissionDenied>());
+    }
+
+    #[test]
+    fn test_reconnect_index() {
+        let cameras = vec![
+            (2, "USB Camera".to_string()),
+            (4, "Capture Card".to_string()),
+        ];
+        // Back under the same index
+        assert_eq!(reconnect_index(4, &cameras), Some(4));
+        // Replugged under a new index, so the first camera found takes over
+        assert_eq!(reconnect_index(0, &cameras), Some(2));
+        assert_eq!(reconnect_index(0, &[]), None);
+    }
+}
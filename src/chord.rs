@@ -0,0 +1,140 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::KeyEvent;
+
+use crate::{action::Action, config::key_event_to_string};
+
+/// Keys typed so far towards a multi-key binding
+///
+/// The buffer is cleared when a binding matches, when the keys can no longer lead to
+/// any binding, or when no key arrives within the timeout.
+#[derive(Debug, Clone)]
+pub struct ChordBuffer {
+    keys: Vec<KeyEvent>,
+    last_key: Instant,
+    timeout: Duration,
+}
+
+impl ChordBuffer {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            keys: Vec::new(),
+            last_key: Instant::now(),
+            timeout,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// Drop the pending keys if the chord timed out, returning whether anything was dropped
+    pub fn expire(&mut self, now: Instant) -> bool {
+        if !self.keys.is_empty() && now.duration_since(self.last_key) >= self.timeout {
+            self.keys.clear();
+            return true;
+        }
+        false
+    }
+
+    /// Feed a key press, returning the bound action once a binding is complete
+    pub fn resolve(
+        &mut self,
+        keymap: &HashMap<Vec<KeyEvent>, Action>,
+        key: KeyEvent,
+        now: Instant,
+    ) -> Option<Action> {
+        self.expire(now);
+
+        // Single-key bindings take precedence over continuing a chord
+        if let Some(action) = keymap.get(&vec![key]) {
+            self.keys.clear();
+            return Some(action.clone());
+        }
+
+        self.keys.push(key);
+        self.last_key = now;
+        if let Some(action) = keymap.get(&self.keys) {
+            self.keys.clear();
+            return Some(action.clone());
+        }
+        if !keymap.keys().any(|keys| keys.starts_with(&self.keys)) {
+            self.keys.clear();
+        }
+        None
+    }
+
+    /// The pending keys formatted like keybindings in the config, e.g. `<g><h>`
+    pub fn display(&self) -> String {
+        self.keys
+            .iter()
+            .map(|key| format!("<{}>", key_event_to_string(key)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_key_sequence;
+
+    fn keymap() -> HashMap<Vec<KeyEvent>, Action> {
+        let mut keymap = HashMap::new();
+        keymap.insert(parse_key_sequence("<q>").unwrap(), Action::Quit);
+        keymap.insert(parse_key_sequence("<g><h>").unwrap(), Action::Help);
+        keymap
+    }
+
+    fn key(raw: &str) -> KeyEvent {
+        parse_key_sequence(raw).unwrap()[0]
+    }
+
+    #[test]
+    fn test_chord_matches_within_timeout() {
+        let keymap = keymap();
+        let mut chord = ChordBuffer::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        assert_eq!(chord.resolve(&keymap, key("<g>"), start), None);
+        assert_eq!(chord.display(), "<g>");
+        let action = chord.resolve(&keymap, key("<h>"), start + Duration::from_millis(400));
+        assert_eq!(action, Some(Action::Help));
+        assert!(chord.is_empty());
+    }
+
+    #[test]
+    fn test_chord_times_out() {
+        let keymap = keymap();
+        let mut chord = ChordBuffer::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        chord.resolve(&keymap, key("<g>"), start);
+        assert!(!chord.expire(start + Duration::from_millis(100)));
+        assert!(chord.expire(start + Duration::from_millis(600)));
+        assert!(chord.is_empty());
+
+        // A late second key starts over instead of completing the chord
+        chord.resolve(&keymap, key("<g>"), start);
+        let late = chord.resolve(&keymap, key("<h>"), start + Duration::from_millis(600));
+        assert_eq!(late, None);
+        assert!(chord.is_empty());
+    }
+
+    #[test]
+    fn test_unbound_keys_are_dropped() {
+        let keymap = keymap();
+        let mut chord = ChordBuffer::new(Duration::from_millis(500));
+        let now = Instant::now();
+
+        assert_eq!(chord.resolve(&keymap, key("<x>"), now), None);
+        assert!(chord.is_empty());
+        assert_eq!(chord.resolve(&keymap, key("<q>"), now), Some(Action::Quit));
+    }
+}
@@ -1,6 +1,13 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
-use crate::config::{get_config_dir, get_data_dir};
+use crate::{
+    ascii::CharacterSet,
+    camera::CameraBackend,
+    config::{Config, get_config_dir, get_data_dir},
+    screen::{CaptureSource, ScreenRegion},
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -12,6 +19,128 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 20.0)]
     pub frame_rate: f64,
+
+    /// Camera backend, overriding `camera.backend` from the config file
+    #[arg(short, long, value_enum)]
+    pub backend: Option<CameraBackend>,
+
+    /// List virtual cameras (OBS, v4l2loopback) too, overriding `camera.include_virtual`
+    #[arg(long)]
+    pub include_virtual: bool,
+
+    /// Only emit printable 7-bit ASCII, overriding `ascii.ascii_only`
+    #[arg(long)]
+    pub ascii_only: bool,
+
+    /// Character set to start with, overriding `ascii.character_set`
+    #[arg(long, value_enum, value_name = "SET")]
+    pub charset: Option<CharacterSet>,
+
+    /// Start in color, overriding `ascii.color`
+    #[arg(long)]
+    pub color: bool,
+
+    /// Grid size multiplier, overriding `ascii.scale`
+    #[arg(long, value_name = "FLOAT")]
+    pub scale: Option<f32>,
+
+    /// Grid width in cells for exported ASCII art, overriding `export.width`
+    #[arg(long, value_name = "CELLS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub export_width: Option<u32>,
+
+    /// Grid height in cells for exported ASCII art, overriding `export.height`
+    #[arg(long, value_name = "CELLS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub export_height: Option<u32>,
+
+    /// Record the session as an asciinema cast, overriding `output.record`
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<PathBuf>,
+
+    /// Stream frames to TCP clients on this address, overriding `output.serve`
+    #[arg(long, value_name = "ADDR")]
+    pub serve: Option<String>,
+
+    /// Show this image or video file instead of opening a camera, overriding `camera.input`
+    #[arg(long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Capture from the camera or the screen, overriding `camera.source`
+    #[arg(long, value_enum, value_name = "SOURCE", conflicts_with = "input")]
+    pub source: Option<CaptureSource>,
+
+    /// Grab only this part of the screen, overriding `camera.screen_region`; implies
+    /// `--source screen`
+    #[arg(long, value_name = "X,Y,W,H", conflicts_with = "input")]
+    pub region: Option<ScreenRegion>,
+
+    /// Fetch an image over HTTP and print it as ASCII art instead of starting the TUI
+    #[arg(long, value_name = "URL", conflicts_with = "json")]
+    pub url: Option<String>,
+
+    /// Re-fetch the `--url` image every SECS seconds, overriding `remote.refresh_secs`
+    #[arg(long, value_name = "SECS", requires = "url")]
+    pub refresh_secs: Option<f64>,
+
+    /// Run without the TUI, writing each frame to stdout as a line of JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Run without the TUI, printing each frame to stdout as text that redraws in place
+    #[arg(long, conflicts_with_all = ["json", "url"])]
+    pub headless: bool,
+
+    /// Write the JSON stream to this file instead of stdout
+    #[arg(long, value_name = "PATH", requires = "json")]
+    pub json_output: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Apply the options that take precedence over the config file
+    pub fn apply_overrides(&self, config: &mut Config) {
+        if let Some(backend) = self.backend {
+            config.camera.backend = backend;
+        }
+        if self.include_virtual {
+            config.camera.include_virtual = true;
+        }
+        if self.ascii_only {
+            config.ascii.ascii_only = true;
+        }
+        if let Some(character_set) = self.charset {
+            config.ascii.character_set = character_set;
+        }
+        if self.color {
+            config.ascii.color = true;
+        }
+        if let Some(scale) = self.scale {
+            config.ascii.scale = scale;
+        }
+        if let Some(width) = self.export_width {
+            config.export.width = Some(width);
+        }
+        if let Some(height) = self.export_height {
+            config.export.height = Some(height);
+        }
+        if let Some(path) = &self.input {
+            config.camera.input = Some(path.clone());
+        }
+        if let Some(source) = self.source {
+            config.camera.source = source;
+        }
+        if let Some(region) = self.region {
+            config.camera.source = CaptureSource::Screen;
+            config.camera.screen_region = Some(region.to_string());
+        }
+        if let Some(path) = &self.record {
+            config.output.record = Some(path.clone());
+        }
+        if let Some(address) = &self.serve {
+            config.output.serve = Some(address.clone());
+        }
+        if let Some(secs) = self.refresh_secs {
+            config.remote.refresh_secs = Some(secs);
+        }
+    }
 }
 
 const VERSION_MESSAGE: &str = concat!(
@@ -6,10 +6,13 @@ use ratatui::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, config::Config, tui::Event};
+use crate::{action::Action, config::Config, frame::FrameReader, tui::Event};
 
+pub mod camera_picker;
 pub mod fps;
+pub mod help;
 pub mod home;
+pub mod log_viewer;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
@@ -42,6 +45,19 @@ pub trait Component {
         let _ = config; // to appease clippy
         Ok(())
     }
+    /// Register a read-only handle to the latest frame if necessary.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Shared frame, written only by the component that owns the video.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn register_frame_handler(&mut self, frame: FrameReader) -> Result<()> {
+        let _ = frame; // to appease clippy
+        Ok(())
+    }
     /// Initialize the component with a specified area if necessary.
     ///
     /// # Arguments
@@ -98,6 +114,18 @@ pub trait Component {
         let _ = mouse; // to appease clippy
         Ok(None)
     }
+    /// Whether the component holds keyboard focus.
+    ///
+    /// Focused components form a stack in the order they were added to the app: the last one
+    /// receives every key event, and neither keybindings nor other components see them until
+    /// it closes.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True while the component wants all key events.
+    fn has_focus(&self) -> bool {
+        false
+    }
     /// Update the state of the component based on a received action. (REQUIRED)
     ///
     /// # Arguments
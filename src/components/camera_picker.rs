@@ -0,0 +1,132 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
+
+use super::Component;
+use crate::{action::Action, camera::CameraCapture, config::Config};
+
+/// Popup listing the detected cameras, opened on top of the video and closed once a camera
+/// is picked or Esc is pressed
+#[derive(Debug, Default)]
+pub struct CameraPicker {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    /// (system index, name) pairs
+    cameras: Vec<(u32, String)>,
+    /// Why the list is empty, if querying failed
+    error: Option<String>,
+    selected: usize,
+}
+
+impl CameraPicker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn send(&self, action: Action) -> Result<()> {
+        if let Some(tx) = &self.command_tx {
+            tx.send(action)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for CameraPicker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn init(&mut self, _area: Size) -> Result<()> {
+        match CameraCapture::list_camera_names(
+            self.config.camera.backend,
+            &self.config.camera.camera_filter(),
+        ) {
+            Ok(cameras) => self.cameras = cameras,
+            Err(e) => {
+                error!("Failed to list cameras for the picker: {}", e);
+                self.error = Some(e.to_string());
+            }
+        }
+        // Start on the camera in use
+        let current = self.config.camera.default_camera_index;
+        self.selected = self
+            .cameras
+            .iter()
+            .position(|(index, _)| *index == current)
+            .unwrap_or(0);
+        Ok(())
+    }
+
+    fn has_focus(&self) -> bool {
+        true
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(Some(Action::CloseOverlay)),
+            // Wrap around like Tab/Shift+Tab do
+            KeyCode::Up | KeyCode::Char('k') if !self.cameras.is_empty() => {
+                self.selected = (self.selected + self.cameras.len() - 1) % self.cameras.len();
+            }
+            KeyCode::Down | KeyCode::Char('j') if !self.cameras.is_empty() => {
+                self.selected = (self.selected + 1) % self.cameras.len();
+            }
+            KeyCode::Enter => {
+                if let Some((index, _)) = self.cameras.get(self.selected) {
+                    self.send(Action::SetCamera(*index))?;
+                }
+                return Ok(Some(Action::CloseOverlay));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let height = (self.cameras.len().max(1) as u16 + 2).min(area.height);
+        let [popup] = Layout::vertical([Constraint::Length(height)])
+            .flex(layout::Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(layout::Flex::Center)
+            .areas(popup);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title("Cameras (Up/Down, Enter to switch, Esc to close)");
+
+        frame.render_widget(Clear, popup);
+        if self.cameras.is_empty() {
+            let message = match &self.error {
+                Some(error) => format!("Error listing cameras: {error}"),
+                None => "No cameras found".to_string(),
+            };
+            frame.render_widget(Paragraph::new(message).block(block), popup);
+            return Ok(());
+        }
+        let current = self.config.camera.default_camera_index;
+        let items: Vec<ListItem> = self
+            .cameras
+            .iter()
+            .map(|(index, name)| {
+                let marker = if *index == current { " (current)" } else { "" };
+                ListItem::new(format!("{index}: {name}{marker}"))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Yellow))
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, popup, &mut state);
+        Ok(())
+    }
+}
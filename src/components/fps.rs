@@ -1,27 +1,20 @@
 use std::time::Instant;
 
 use color_eyre::Result;
-use ratatui::{
-    Frame,
-    layout::{Constraint, Layout, Rect},
-    style::{Style, Stylize},
-    text::Span,
-    widgets::Paragraph,
-};
+use ratatui::{Frame, layout::Rect};
+use tokio::sync::mpsc::UnboundedSender;
 
 use super::Component;
 
 use crate::action::Action;
 
-#[derive(Debug, Clone, PartialEq)]
+/// Measures the app's tick rate and reports it once a second as [`Action::FpsUpdate`], for
+/// `Home` to show in the status bar next to its own frame rates
+#[derive(Debug, Clone)]
 pub struct FpsCounter {
+    command_tx: Option<UnboundedSender<Action>>,
     last_tick_update: Instant,
     tick_count: u32,
-    ticks_per_second: f64,
-
-    last_frame_update: Instant,
-    frame_count: u32,
-    frames_per_second: f64,
 }
 
 impl Default for FpsCounter {
@@ -33,12 +26,9 @@ impl Default for FpsCounter {
 impl FpsCounter {
     pub fn new() -> Self {
         Self {
+            command_tx: None,
             last_tick_update: Instant::now(),
             tick_count: 0,
-            ticks_per_second: 0.0,
-            last_frame_update: Instant::now(),
-            frame_count: 0,
-            frames_per_second: 0.0,
         }
     }
 
@@ -47,45 +37,32 @@ impl FpsCounter {
         let now = Instant::now();
         let elapsed = (now - self.last_tick_update).as_secs_f64();
         if elapsed >= 1.0 {
-            self.ticks_per_second = self.tick_count as f64 / elapsed;
+            let ticks_per_second = self.tick_count as f64 / elapsed;
             self.last_tick_update = now;
             self.tick_count = 0;
+            if let Some(tx) = &self.command_tx {
+                tx.send(Action::FpsUpdate(ticks_per_second))?;
+            }
         }
         Ok(())
     }
+}
 
-    fn render_tick(&mut self) -> Result<()> {
-        self.frame_count += 1;
-        let now = Instant::now();
-        let elapsed = (now - self.last_frame_update).as_secs_f64();
-        if elapsed >= 1.0 {
-            self.frames_per_second = self.frame_count as f64 / elapsed;
-            self.last_frame_update = now;
-            self.frame_count = 0;
-        }
+impl Component for FpsCounter {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
         Ok(())
     }
-}
 
-impl Component for FpsCounter {
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        match action {
-            Action::Tick => self.app_tick()?,
-            Action::Render => self.render_tick()?,
-            _ => {}
-        };
+        if action == Action::Tick {
+            self.app_tick()?;
+        }
         Ok(None)
     }
 
-    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let [top, _] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
-        let message = format!(
-            "{:.2} ticks/sec, {:.2} FPS",
-            self.ticks_per_second, self.frames_per_second
-        );
-        let span = Span::styled(message, Style::new().dim());
-        let paragraph = Paragraph::new(span).right_aligned();
-        frame.render_widget(paragraph, top);
+    /// Nothing to draw, the rate shows in `Home`'s status bar
+    fn draw(&mut self, _frame: &mut Frame, _area: Rect) -> Result<()> {
         Ok(())
     }
 }
@@ -0,0 +1,197 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use super::Component;
+use crate::{
+    action::Action,
+    app::Mode,
+    config::{Config, key_event_to_string},
+};
+
+/// Space between the key and action columns, and between entries side by side
+const GAP: usize = 2;
+
+/// Rows moved by PageUp/PageDown
+const PAGE_ROWS: usize = 10;
+
+/// Popup listing the keybindings of the current mode, built from the loaded keymap so it
+/// matches the config; scrolled with the arrow and page keys when it doesn't fit, and
+/// closed by any other key or a second `Help`
+#[derive(Debug, Default)]
+pub struct Help {
+    config: Config,
+    mode: Mode,
+    visible: bool,
+    /// Rows scrolled down from the top
+    scroll: usize,
+}
+
+impl Help {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// (keys, action) pairs for the current mode, sorted by action so alternative keys for
+    /// the same action sit together
+    fn entries(&self) -> Vec<(String, String)> {
+        let Some(keymap) = self.config.keybindings.get(&self.mode) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<(String, String)> = keymap
+            .iter()
+            .map(|(keys, action)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| format!("<{}>", key_event_to_string(key)))
+                    .collect();
+                (keys, format!("{action:?}"))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        entries
+    }
+}
+
+impl Component for Help {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    /// Takes all keys while open, so the keymap doesn't act on them
+    fn has_focus(&self) -> bool {
+        self.visible
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        // Scrolling past the end is clamped when drawing
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE_ROWS),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(PAGE_ROWS),
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = usize::MAX,
+            _ => self.visible = false,
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Help => {
+                self.visible = !self.visible;
+                self.scroll = 0;
+            }
+            Action::SetMode(mode) => self.mode = mode,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+
+        let entries = self.entries();
+        let key_width = entries
+            .iter()
+            .map(|(keys, _)| keys.len())
+            .max()
+            .unwrap_or(0);
+        let entry_width = entries
+            .iter()
+            .map(|(_, action)| key_width + GAP + action.len() + GAP)
+            .max()
+            .unwrap_or(1);
+        // As many columns as fit side by side, filled top to bottom
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let columns = (inner_width / entry_width).clamp(1, entries.len().max(1));
+        let rows = entries.len().div_ceil(columns).max(1);
+
+        let height = (rows as u16 + 2).min(area.height);
+        let width = ((columns * entry_width) as u16 + 2).min(area.width);
+        let [popup] = Layout::vertical([Constraint::Length(height)])
+            .flex(layout::Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Length(width)])
+            .flex(layout::Flex::Center)
+            .areas(popup);
+        let visible_rows = height.saturating_sub(2) as usize;
+        self.scroll = self.scroll.min(rows.saturating_sub(visible_rows));
+        let title = if rows > visible_rows {
+            format!(
+                "Keybindings: {:?} (rows {}-{} of {}, Up/Down/PgUp/PgDn to scroll, any other key to close)",
+                self.mode,
+                self.scroll + 1,
+                self.scroll + visible_rows,
+                rows
+            )
+        } else {
+            format!("Keybindings: {:?} (any key to close)", self.mode)
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(title);
+
+        frame.render_widget(Clear, popup);
+        let inner = block.inner(popup);
+        frame.render_widget(block, popup);
+        if entries.is_empty() {
+            frame.render_widget(Paragraph::new("No keybindings for this mode"), inner);
+            return Ok(());
+        }
+        let column_areas =
+            Layout::horizontal(vec![Constraint::Length(entry_width as u16); columns]).split(inner);
+        for (chunk, column) in entries.chunks(rows).zip(column_areas.iter()) {
+            let items: Vec<ListItem> = chunk
+                .iter()
+                .skip(self.scroll)
+                .map(|(keys, action)| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{keys:<width$}", width = key_width + GAP),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        Span::raw(action.as_str()),
+                    ]))
+                })
+                .collect();
+            frame.render_widget(List::new(items), *column);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_list_every_binding_sorted_by_action() {
+        let config: Config = json5::from_str(include_str!("../../.config/config.json5")).unwrap();
+        let bindings = config.keybindings[&Mode::Home].len();
+        let mut help = Help::new();
+        help.register_config_handler(config).unwrap();
+
+        let entries = help.entries();
+        assert_eq!(entries.len(), bindings);
+        assert!(
+            entries
+                .windows(2)
+                .all(|pair| (&pair[0].1, &pair[0].0) <= (&pair[1].1, &pair[1].0))
+        );
+        assert!(
+            entries
+                .iter()
+                .any(|(keys, action)| keys == "<q>" && action == "Quit")
+        );
+
+        // A mode without bindings lists nothing
+        help.update(Action::SetMode(Mode::Prompt)).unwrap();
+        assert!(help.entries().is_empty());
+    }
+}
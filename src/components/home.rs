@@ -1,55 +1,349 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
 use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::{layout::Size, prelude::*, widgets::*};
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use super::Component;
 use crate::{
     action::Action,
-    ascii::{AsciiConverter, ColoredChar},
+    app::Mode,
+    ascii::{
+        AsciiConverter, AutoContrast, CharacterSet, ColorMode, ColoredChar, FilterKind, GridBounds,
+        LETTERBOX_COLOR, RenderMode, Rotation, ScaleMode, color_name, frame_to_text,
+        to_ansi_string,
+    },
     camera::CameraCapture,
     config::Config,
+    effects::AnimatedEffect,
+    frame::{FrameWriter, SharedGrid, shared_frame},
+    preset::Preset,
+    profiles::Profile,
+    quality::QUALITY_SCALES,
+    render_image::rasterize_on,
+    sink::{CastRecorder, FrameSink, SinkSet, TcpBroadcaster, TextFileSink},
+    status::{StatusField, StatusTemplate},
+    terminal::ColorSupport,
+    value_entry::ValueField,
+    worker::{ConversionJob, ConversionWorker, ConvertedFrame, FrameLayout},
+    yuyv,
 };
 
 pub struct Home {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
     ascii_converter: AsciiConverter,
-    current_frame: Vec<Vec<ColoredChar>>,
+    /// The latest converted frame, swapped whole for each new one
+    ///
+    /// The display, the shared frame and the sinks all hold this same `Arc` instead of copies,
+    /// and a reader never sees a frame half replaced, including one the conversion worker
+    /// just finished.
+    current_frame: SharedGrid,
+    /// Converts single-pane frames off the event loop, started with the first frame
+    worker: Option<ConversionWorker>,
     camera_active: bool,
+    /// The camera device was closed and the last frame is frozen on screen
+    camera_released: bool,
+    /// The camera stopped delivering frames and was let go, the last frame stays on screen
+    camera_disconnected: bool,
+    test_pattern_active: bool,
+    /// Path of the image or video shown instead of the camera
+    input_file: Option<String>,
+    input_paused: bool,
+    /// The camera is streaming but its frames are ignored, freezing the picture
+    capture_paused: bool,
+    status_template: StatusTemplate,
+    /// Where each field was drawn in the last status bar, for routing the scroll wheel
+    status_regions: Vec<(StatusField, Rect)>,
+    /// Frames converted per second
+    fps: FrameRate,
+    /// Frames the camera delivers per second
+    capture_fps: FrameRate,
+    /// Frames drawn to the terminal per second
+    render_fps: FrameRate,
+    /// App ticks per second, as last reported by the FPS counter
+    tick_rate: f64,
+    /// Size of the last source frame
+    source_resolution: Option<(u32, u32)>,
+    /// Seconds between demo steps while demo mode is running
+    demo_interval: Option<u64>,
     camera_error: Option<String>,
     available_cameras: Vec<(u32, String)>,
     status_message: String,
     last_frame_processed: std::time::Instant,
-    pending_frame: Option<(Vec<u8>, u32, u32)>,
+    /// Latest frame not converted yet, with when it arrived
+    pending_frame: Option<(FrameLayout, Vec<u8>, u32, u32, Instant)>,
+    split_view: bool,
+    split_compare: SplitCompare,
+    split_frame: Vec<Vec<ColoredChar>>,
+    /// Converters for the two split panes, kept across frames so trails, smoothing and grain
+    /// carry on in each pane
+    split_panes: Option<(AsciiConverter, AsciiConverter)>,
+    /// Converter for the channel view, kept across frames like the split panes
+    channel_pane: Option<AsciiConverter>,
+    /// The live settings may have changed since the pane converters last took them
+    panes_stale: bool,
+    /// Settings replaced by peak detail mode, restored when it's turned off
+    peak_detail: Option<PeakRestore>,
+    /// Settings replaced by power saver mode, restored when it's turned off
+    power_saver: Option<PowerRestore>,
+    /// Scale and detail level while automatic quality is on
+    auto_quality: Option<AutoQuality>,
+    /// Terminal size in cells, from the last resize
+    terminal_size: (u16, u16),
+    /// Show one pane per color channel instead of the single image
+    channel_view: bool,
+    /// Red, green and blue grids for the channel view
+    channel_frames: [Vec<Vec<ColoredChar>>; 3],
+    effect: AnimatedEffect,
+    effect_clock: Instant,
+    /// Grids actually drawn, derived from the converted frames by the active effect
+    display_frame: Vec<Vec<ColoredChar>>,
+    display_split_frame: Vec<Vec<ColoredChar>>,
+    display_channel_frames: [Vec<Vec<ColoredChar>>; 3],
+    /// Set whenever the converted frames change so static output is only re-derived once
+    frame_dirty: bool,
+    color_support: ColorSupport,
+    preset: Preset,
+    /// Name being typed for a profile save, if the prompt is open
+    profile_prompt: Option<ProfilePrompt>,
+    /// Exact value being typed for a setting, if the prompt is open
+    value_prompt: Option<ValuePrompt>,
+    /// Clamping note for the last entered value, shown once it's applied
+    value_note: Option<String>,
+    current_profile: Option<String>,
+    /// Keys typed so far towards a multi-key binding
+    pending_chord: String,
+    /// Publishes every converted frame for other components
+    frame_writer: FrameWriter,
+    /// Recording and streaming outputs fed alongside the screen
+    sinks: SinkSet,
+    /// Name of the sink writing text frames to a file, while one runs
+    text_stream: Option<String>,
+    /// Grid size last reported to the app, so changes are only announced once
+    reported_grid: Option<(u32, u32)>,
+    /// Whether luma-only capture was last reported as usable
+    reported_luma: Option<bool>,
+    /// Settings last reported to the app for saving on quit
+    reported_settings: Option<(CharacterSet, bool, f32)>,
+    /// The truecolor mismatch warning is only shown once per session
+    truecolor_warned: bool,
+}
+
+/// Which setting the right half of the split view changes relative to the live settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitCompare {
+    CharacterSet,
+    Color,
+    /// The next cell mode, e.g. characters against half blocks
+    CellMode,
+}
+
+impl SplitCompare {
+    fn next(&self) -> SplitCompare {
+        match self {
+            SplitCompare::CharacterSet => SplitCompare::Color,
+            SplitCompare::Color => SplitCompare::CellMode,
+            SplitCompare::CellMode => SplitCompare::CharacterSet,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SplitCompare::CharacterSet => "character set",
+            SplitCompare::Color => "color mode",
+            SplitCompare::CellMode => "cell mode",
+        }
+    }
+}
+
+/// State of the profile name prompt
+#[derive(Debug, Default)]
+struct ProfilePrompt {
+    name: String,
+    /// The name already exists and the next Enter overwrites it
+    confirm_overwrite: bool,
+}
+
+/// Converter settings peak detail mode overrides
+#[derive(Debug, Clone, Copy)]
+struct PeakRestore {
+    scale_factor: f32,
+    scale_mode: ScaleMode,
+    filter: FilterKind,
+    grid_bounds: GridBounds,
 }
 
+/// Settings power saver mode turns down
+#[derive(Debug, Clone, Copy)]
+struct PowerRestore {
+    scale_factor: f32,
+    filter: FilterKind,
+    edge_overlay: bool,
+    phosphor: bool,
+    auto_contrast: AutoContrast,
+    perceptual_ramp: bool,
+    effect: AnimatedEffect,
+    split_view: bool,
+    channel_view: bool,
+}
+
+/// Automatic quality lowers the user's scale by a level's factor
+#[derive(Debug, Clone, Copy)]
+struct AutoQuality {
+    /// Scale at full detail, restored when automatic quality is turned off
+    base_scale: f32,
+    level: usize,
+}
+
+/// Events per second, counted over windows of at least a second
+#[derive(Debug, Clone, Copy)]
+struct FrameRate {
+    count: u32,
+    window_start: Instant,
+    per_second: f64,
+}
+
+impl FrameRate {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            window_start: Instant::now(),
+            per_second: 0.0,
+        }
+    }
+
+    fn count(&mut self) {
+        self.count += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.per_second = self.count as f64 / elapsed.as_secs_f64();
+            self.count = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    /// The last window's rate, or zero once nothing has been counted for a while
+    fn per_second(&self) -> f64 {
+        if self.window_start.elapsed() > Duration::from_secs(2) {
+            0.0
+        } else {
+            self.per_second
+        }
+    }
+}
+
+/// State of the numeric entry prompt
+#[derive(Debug)]
+struct ValuePrompt {
+    field: ValueField,
+    text: String,
+}
+
+/// Shortest time between frame conversions, so converting never starves the UI
+const CONVERSION_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+/// Capture pixels across each cell in photo renders, enough for the filters to smooth over;
+/// rows follow the cell aspect
+const PHOTO_CELL_PIXELS: u32 = 4;
+/// Conversion interval in power saver mode
+const POWER_SAVER_CONVERSION_INTERVAL: std::time::Duration = std::time::Duration::from_millis(150);
+/// Luma levels each brightness key press shifts by
+const BRIGHTNESS_STEP: i16 = 8;
+
 impl Default for Home {
     fn default() -> Self {
-        Self::new()
+        Self::new(shared_frame().0)
     }
 }
 
 impl Home {
-    pub fn new() -> Self {
+    pub fn new(frame_writer: FrameWriter) -> Self {
         let ascii_converter = AsciiConverter::new_dense(80, 24);
         Self {
             command_tx: None,
             config: Config::default(),
             ascii_converter,
-            current_frame: Vec::new(),
+            current_frame: SharedGrid::default(),
+            worker: None,
             camera_active: false,
+            camera_released: false,
+            camera_disconnected: false,
+            test_pattern_active: false,
+            input_file: None,
+            input_paused: false,
+            capture_paused: false,
+            status_template: StatusTemplate::default(),
+            status_regions: Vec::new(),
+            fps: FrameRate::new(),
+            capture_fps: FrameRate::new(),
+            render_fps: FrameRate::new(),
+            tick_rate: 0.0,
+            source_resolution: None,
+            demo_interval: None,
             camera_error: None,
             available_cameras: Vec::new(),
             status_message: "Press SPACE to start camera".to_string(),
             last_frame_processed: std::time::Instant::now(),
             pending_frame: None,
+            split_view: false,
+            split_compare: SplitCompare::CharacterSet,
+            split_frame: Vec::new(),
+            split_panes: None,
+            channel_pane: None,
+            panes_stale: true,
+            peak_detail: None,
+            power_saver: None,
+            auto_quality: None,
+            terminal_size: (0, 0),
+            channel_view: false,
+            channel_frames: Default::default(),
+            effect: AnimatedEffect::default(),
+            effect_clock: Instant::now(),
+            display_frame: Vec::new(),
+            display_split_frame: Vec::new(),
+            display_channel_frames: Default::default(),
+            frame_dirty: false,
+            color_support: ColorSupport::TrueColor,
+            preset: Preset::default(),
+            profile_prompt: None,
+            value_prompt: None,
+            value_note: None,
+            current_profile: None,
+            pending_chord: String::new(),
+            frame_writer,
+            sinks: SinkSet::default(),
+            text_stream: None,
+            reported_grid: None,
+            reported_luma: None,
+            reported_settings: None,
+            truecolor_warned: false,
+        }
+    }
+
+    /// Re-list the cameras, keeping the old list if listing fails
+    fn refresh_camera_list(&mut self) {
+        match CameraCapture::list_camera_names(
+            self.config.camera.backend,
+            &self.config.camera.camera_filter(),
+        ) {
+            Ok(cameras) => self.available_cameras = cameras,
+            Err(e) => warn!("Failed to refresh the camera list: {e}"),
         }
     }
 
     fn initialize_camera_list(&mut self) {
         info!("Initializing camera list...");
-        match CameraCapture::list_cameras() {
+        match CameraCapture::list_camera_names(
+            self.config.camera.backend,
+            &self.config.camera.camera_filter(),
+        ) {
             Ok(cameras) => {
                 self.available_cameras = cameras.clone();
                 if !self.available_cameras.is_empty() {
@@ -66,7 +360,8 @@ impl Home {
                     );
                     info!("Camera list initialized: {}", camera_info);
                 } else {
-                    self.status_message = "No cameras found!".to_string();
+                    self.status_message =
+                        "No cameras found! Press P for a test pattern.".to_string();
                     info!("No cameras found");
                 }
             }
@@ -80,18 +375,897 @@ impl Home {
 
     /// Process pending frame if available and enough time has passed
     fn process_pending_frame(&mut self) {
-        if let Some((frame_data, width, height)) = self.pending_frame.take() {
+        if let Some((layout, frame_data, width, height, captured)) = self.pending_frame.take() {
             let now = std::time::Instant::now();
-            if now.duration_since(self.last_frame_processed) >= std::time::Duration::from_millis(50)
-            {
-                self.current_frame =
-                    self.ascii_converter
-                        .convert_rgb_frame_direct(&frame_data, width, height);
+            if now.duration_since(self.last_frame_processed) >= self.conversion_interval() {
+                self.convert_frame(layout, frame_data, width, height, captured);
                 self.last_frame_processed = now;
                 self.camera_error = None;
             } else {
                 // Put frame back if not ready to process yet
-                self.pending_frame = Some((frame_data, width, height));
+                self.pending_frame = Some((layout, frame_data, width, height, captured));
+            }
+        }
+    }
+
+    /// Convert a new source frame right away, or hold it until the conversion interval passes
+    fn receive_frame(
+        &mut self,
+        layout: FrameLayout,
+        frame_data: Vec<u8>,
+        width: u32,
+        height: u32,
+        captured: Instant,
+    ) {
+        debug!("Received camera frame: {}x{}", width, height);
+        if !self.camera_active && !self.test_pattern_active && self.input_file.is_none() {
+            return;
+        }
+        // Frames a burst or photo needs while paused still arrive, but the picture stays
+        if self.capture_paused {
+            return;
+        }
+        // Use frame throttling to prevent UI blocking
+        let now = std::time::Instant::now();
+        if now.duration_since(self.last_frame_processed) >= self.conversion_interval() {
+            // Process frame immediately if enough time has passed
+            self.convert_frame(layout, frame_data, width, height, captured);
+            self.last_frame_processed = now;
+            self.camera_error = None;
+        } else {
+            // Store frame for later processing to avoid blocking
+            self.pending_frame = Some((layout, frame_data, width, height, captured));
+        }
+    }
+
+    /// Whether frames can be converted from luma alone, without decoding them to RGB
+    fn luma_frames_usable(&self) -> bool {
+        !self.channel_view && !self.split_view && self.ascii_converter.accepts_luma()
+    }
+
+    /// Convert a camera frame for display, once per pane
+    ///
+    /// A single pane is converted on the worker when `ui.convert_in_background` is on, and
+    /// shows once it comes back as [`Action::AsciiFrameReady`].
+    fn convert_frame(
+        &mut self,
+        layout: FrameLayout,
+        frame_data: Vec<u8>,
+        width: u32,
+        height: u32,
+        captured: Instant,
+    ) {
+        if !self.channel_view
+            && !self.split_view
+            && self.start_conversion_worker()
+            && let Some(worker) = &self.worker
+        {
+            let job = ConversionJob {
+                converter: self.ascii_converter.clone(),
+                layout,
+                frame: frame_data,
+                width,
+                height,
+                captured,
+            };
+            if worker.submit(job) {
+                debug!("Dropped a frame still waiting for the conversion worker");
+            }
+            return;
+        }
+        if layout == FrameLayout::Yuyv {
+            if self.luma_frames_usable() {
+                self.current_frame = Arc::new(self.ascii_converter.convert_yuyv_frame(
+                    &frame_data,
+                    width,
+                    height,
+                ));
+                self.finish_frame(&[], width, height, captured);
+            } else {
+                // Settings changed while the frame was on its way
+                let rgb = yuyv::to_rgb(&frame_data);
+                self.convert_frame(FrameLayout::Rgb, rgb, width, height, captured);
+            }
+            return;
+        }
+        let frame_data = frame_data.as_slice();
+        self.sync_panes();
+        if self.channel_view
+            && let Some(converter) = self.channel_pane.as_mut()
+        {
+            self.channel_frames = converter.convert_rgb_channels(frame_data, width, height);
+            self.current_frame = Arc::new(self.channel_frames[0].clone());
+        } else if self.split_view
+            && let Some((left, right)) = self.split_panes.as_mut()
+        {
+            // Both halves come from the same source buffer so they stay in sync
+            self.current_frame = Arc::new(left.convert_rgb_frame_direct(frame_data, width, height));
+            self.split_frame = right.convert_rgb_frame_direct(frame_data, width, height);
+        } else {
+            self.current_frame = Arc::new(
+                self.ascii_converter
+                    .convert_rgb_frame_direct(frame_data, width, height),
+            );
+        }
+        self.finish_frame(frame_data, width, height, captured);
+    }
+
+    /// Start the conversion worker if it isn't yet; false when frames are converted here
+    fn start_conversion_worker(&mut self) -> bool {
+        if !self.config.ui.convert_in_background {
+            self.worker = None;
+            return false;
+        }
+        if self.worker.is_none()
+            && let Some(tx) = self.command_tx.clone()
+        {
+            self.worker = Some(ConversionWorker::spawn(tx));
+        }
+        // Without its thread frames are converted here instead
+        self.worker
+            .as_ref()
+            .is_some_and(ConversionWorker::is_running)
+    }
+
+    /// Show a frame the worker converted, unless the picture moved on while it was converting
+    fn show_converted_frame(&mut self, frame: ConvertedFrame) {
+        let source_active =
+            self.camera_active || self.test_pattern_active || self.input_file.is_some();
+        if !source_active || self.capture_paused || self.channel_view || self.split_view {
+            debug!("Dropped a converted frame the display no longer wants");
+            return;
+        }
+        self.current_frame = frame.grid;
+        self.finish_frame(&frame.rgb, frame.width, frame.height, frame.captured);
+    }
+
+    /// Hand a freshly converted frame to the display, the shared frame and the outputs
+    fn finish_frame(&mut self, rgb: &[u8], width: u32, height: u32, captured: Instant) {
+        self.frame_dirty = true;
+        self.frame_writer
+            .publish(rgb, width, height, &self.current_frame);
+        self.sinks.dispatch(&self.current_frame, captured);
+        self.source_resolution = Some((width, height));
+        self.count_frame();
+    }
+
+    /// Open the recording and streaming outputs requested in the config
+    fn start_sinks(&mut self) {
+        let mut failures = Vec::new();
+        if let Some(path) = self.config.output.record.clone() {
+            match CastRecorder::create(&path) {
+                Ok(recorder) => self.sinks.add(Box::new(recorder)),
+                Err(e) => {
+                    error!("Failed to start recording to {}: {}", path.display(), e);
+                    failures.push(format!("recording to {}: {e}", path.display()));
+                }
+            }
+        }
+        if let Some(address) = self.config.output.serve.clone() {
+            match TcpBroadcaster::bind(&address) {
+                Ok(broadcaster) => self.sinks.add(Box::new(broadcaster)),
+                Err(e) => {
+                    error!("Failed to stream on {}: {}", address, e);
+                    failures.push(format!("stream on {address}: {e}"));
+                }
+            }
+        }
+        if let Some(path) = self.config.output.text_file.clone()
+            && let Err(e) = self.start_text_stream(&path)
+        {
+            failures.push(format!("text frames to {}: {e}", path.display()));
+        }
+        if !failures.is_empty() {
+            self.status_message = format!("Output failed: {}", failures.join(", "));
+        }
+    }
+
+    /// Start appending frames as text to `path`, taken as relative to the data directory
+    fn start_text_stream(&mut self, path: &Path) -> Result<PathBuf> {
+        let path = self.config.config.data_dir.join(path);
+        let sink = TextFileSink::open(&path).inspect_err(|e| {
+            error!("Failed to write text frames to {}: {}", path.display(), e);
+        })?;
+        self.text_stream = Some(sink.name());
+        self.sinks.add(Box::new(sink));
+        Ok(path)
+    }
+
+    /// Stop writing text frames, or start writing them to `path`
+    fn toggle_text_stream(&mut self, path: &Path) {
+        // A sink that failed on its own is gone already, so this starts a new one
+        if let Some(name) = self.text_stream.take()
+            && self.sinks.remove(&name)
+        {
+            self.status_message = "Stopped writing frames".to_string();
+            return;
+        }
+        self.status_message = match self.start_text_stream(path) {
+            Ok(path) => format!("Writing frames to {}", path.display()),
+            Err(e) => format!("Failed to write frames: {e}"),
+        };
+    }
+
+    /// Track converted frames per second for the status bar
+    fn count_frame(&mut self) {
+        self.fps.count();
+    }
+
+    /// Tell the app when the grid size changed so it can adapt the capture resolution
+    ///
+    /// The size is given in the camera's orientation, so a quarter turn asks for a capture
+    /// that is tall where the grid is wide.
+    fn report_grid_size(&mut self) -> Result<()> {
+        let (width, height) = self.ascii_converter.grid_dimensions();
+        let grid = self.ascii_converter.rotation().rotated_size(width, height);
+        if self.reported_grid == Some(grid) {
+            return Ok(());
+        }
+        self.reported_grid = Some(grid);
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::GridResized(grid.0, grid.1))?;
+        }
+        Ok(())
+    }
+
+    /// Tell the app when frames start or stop being convertible from luma alone, so the
+    /// camera knows whether to decode them
+    fn report_luma_capture(&mut self) -> Result<()> {
+        let usable = self.luma_frames_usable();
+        if self.reported_luma == Some(usable) {
+            return Ok(());
+        }
+        self.reported_luma = Some(usable);
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::LumaCapture(usable))?;
+        }
+        Ok(())
+    }
+
+    /// Tell the app when the settings worth keeping for the next launch changed
+    ///
+    /// The scale is the user's own, not a temporary one from peak detail, power saver or
+    /// automatic quality.
+    fn report_settings(&mut self) -> Result<()> {
+        let scale = if let Some(restore) = &self.peak_detail {
+            restore.scale_factor
+        } else if let Some(restore) = &self.power_saver {
+            restore.scale_factor
+        } else if let Some(auto) = &self.auto_quality {
+            auto.base_scale
+        } else {
+            self.ascii_converter.scale_factor()
+        };
+        let settings = (
+            self.ascii_converter.character_set(),
+            self.ascii_converter.color_enabled(),
+            scale,
+        );
+        if self.reported_settings == Some(settings) {
+            return Ok(());
+        }
+        self.reported_settings = Some(settings);
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::SettingsChanged(settings.0, settings.1, settings.2))?;
+        }
+        Ok(())
+    }
+
+    /// Note in the status bar when the grid bounds override the terminal size
+    fn report_clamping(&mut self) {
+        if self.ascii_converter.is_clamped() {
+            let (width, height) = self.ascii_converter.grid_dimensions();
+            self.status_message = format!(
+                "{} (grid clamped to {width}x{height} by min/max bounds)",
+                self.status_message
+            );
+        }
+    }
+
+    fn show_profile_prompt(&mut self) {
+        if let Some(prompt) = &self.profile_prompt {
+            self.status_message = format!(
+                "Profile name: {}_ (Enter to save, Esc to cancel)",
+                prompt.name
+            );
+        }
+    }
+
+    fn show_value_prompt(&mut self) {
+        if let Some(prompt) = &self.value_prompt {
+            self.status_message = format!(
+                "{} ({}): {}_ (Enter to set, Esc to cancel)",
+                prompt.field.name(),
+                prompt.field.hint(),
+                prompt.text
+            );
+        }
+    }
+
+    /// Validate the typed value and send the action setting it, keeping the prompt open on
+    /// invalid input
+    fn submit_value_prompt(&mut self) -> Result<Option<Action>> {
+        let Some(prompt) = self.value_prompt.as_ref() else {
+            return Ok(None);
+        };
+        match prompt.field.parse(&prompt.text) {
+            Ok((action, note)) => {
+                self.value_prompt = None;
+                self.value_note = note;
+                if let Some(tx) = &self.command_tx {
+                    tx.send(action)?;
+                }
+                Ok(Some(Action::SetMode(Mode::Home)))
+            }
+            Err(e) => {
+                self.status_message = format!(
+                    "Invalid {}: {e}. Fix it or press Esc to cancel",
+                    prompt.field.name().to_lowercase()
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    fn report_smoothing(&mut self) {
+        let smoothing = self.ascii_converter.smoothing();
+        self.status_message = if smoothing == 0.0 {
+            "Smoothing: OFF".to_string()
+        } else {
+            format!("Smoothing: {smoothing:.2}")
+        };
+    }
+
+    fn report_cell_aspect(&mut self) {
+        self.frame_dirty = true;
+        self.status_message = format!("Cell aspect: {:.1}", self.ascii_converter.cell_aspect());
+        if self.ascii_converter.scale_mode() == ScaleMode::Stretch
+            && self.ascii_converter.detail_level().is_none()
+        {
+            self.status_message.push_str(" (applies in Fit)");
+        }
+    }
+
+    fn report_grain(&mut self) {
+        let grain = self.ascii_converter.grain_amount();
+        self.status_message = if grain == 0.0 {
+            "Grain: OFF".to_string()
+        } else {
+            format!("Grain: {grain:.2}")
+        };
+    }
+
+    /// Source size the detail level is relative to, the configured capture size before the
+    /// first frame
+    fn detail_source(&self) -> (u32, u32) {
+        self.source_resolution
+            .unwrap_or((self.config.camera.width, self.config.camera.height))
+    }
+
+    fn report_detail_level(&mut self) {
+        self.status_message = match self.ascii_converter.detail_level() {
+            Some(level) => format!("Detail: {level:.3} cells per source pixel"),
+            None => "Detail: follows terminal size".to_string(),
+        };
+    }
+
+    /// Status message for an applied value, with the clamping note if there is one
+    fn report_value(&mut self, message: String) {
+        self.status_message = match self.value_note.take() {
+            Some(note) => format!("{message} ({note})"),
+            None => message,
+        };
+    }
+
+    /// Number keys 1-9 pick the matching entry of the camera list, unless bound to
+    /// something else
+    fn quick_select_camera(&mut self, key: KeyEvent) -> Option<Action> {
+        let KeyCode::Char(digit @ '1'..='9') = key.code else {
+            return None;
+        };
+        if !key.modifiers.is_empty()
+            || self
+                .config
+                .keybindings
+                .get(&Mode::Home)
+                .is_some_and(|keymap| keymap.contains_key(&vec![key]))
+        {
+            return None;
+        }
+        let position = digit as usize - '1' as usize;
+        let (index, _) = self.available_cameras.get(position)?;
+        Some(Action::SetCamera(*index))
+    }
+
+    /// Switch to the detected camera `step` places from the current one, wrapping around
+    fn step_camera(&mut self, step: isize) -> Option<Action> {
+        let count = self.available_cameras.len();
+        if count < 2 {
+            self.status_message = match count {
+                0 => "No cameras found! Press P for a test pattern.".to_string(),
+                _ => "Only one camera found".to_string(),
+            };
+            return None;
+        }
+        let current = self.config.camera.default_camera_index;
+        // A current camera missing from the list starts from the first one
+        let position = self
+            .available_cameras
+            .iter()
+            .position(|(index, _)| *index == current)
+            .map_or(0, |position| {
+                (position as isize + step).rem_euclid(count as isize)
+            });
+        let (index, _) = self.available_cameras[position as usize];
+        Some(Action::SetCamera(index))
+    }
+
+    /// Save the current settings under the prompted name, asking before overwriting
+    fn submit_profile_prompt(&mut self) -> Result<Option<Action>> {
+        let Some(prompt) = self.profile_prompt.as_mut() else {
+            return Ok(None);
+        };
+        let name = prompt.name.trim().to_string();
+        if name.is_empty() {
+            self.status_message = "Profile name can't be empty".to_string();
+            return Ok(None);
+        }
+        if self.config.profiles.contains(&name) && !prompt.confirm_overwrite {
+            prompt.confirm_overwrite = true;
+            self.status_message =
+                format!("Profile '{name}' exists. Press Enter again to overwrite, Esc to cancel");
+            return Ok(None);
+        }
+
+        self.profile_prompt = None;
+        self.config
+            .profiles
+            .insert(name.clone(), Profile::capture(&self.ascii_converter));
+        match self.config.profiles.save(&self.config.config.config_dir) {
+            Ok(()) => {
+                info!("Saved profile '{}'", name);
+                self.status_message = format!("Saved profile '{name}'");
+            }
+            Err(e) => {
+                error!("Failed to save profiles: {}", e);
+                self.status_message = format!("Failed to save profile '{name}': {e}");
+            }
+        }
+        self.current_profile = Some(name);
+        Ok(Some(Action::SetMode(Mode::Home)))
+    }
+
+    /// Warn once when colored output is on but the terminal doesn't advertise truecolor
+    fn warn_truecolor_mismatch(&mut self) {
+        if self.truecolor_warned
+            || self.ascii_converter.color_mode() != ColorMode::Truecolor
+            || self.color_support == ColorSupport::TrueColor
+        {
+            return;
+        }
+        self.truecolor_warned = true;
+        warn!(
+            "Color enabled but terminal only advertises {}",
+            self.color_support.name()
+        );
+        let fewer_colors = match self
+            .config
+            .keybindings
+            .key_for(Mode::Home, &Action::NextColorMode)
+        {
+            Some(key) => format!("Press {key} for fewer colors, or set"),
+            None => "Set".to_string(),
+        };
+        self.status_message = format!(
+            "Warning: terminal advertises {} only (COLORTERM not set), colors may look wrong. \
+             {fewer_colors} COLORTERM=truecolor if supported.",
+            self.color_support.name()
+        );
+    }
+
+    /// Re-derive the drawn grids from the converted frames when they changed or are animated
+    fn refresh_display_frames(&mut self) {
+        if !self.frame_dirty && !self.effect.is_animated() {
+            return;
+        }
+        let t = self.effect_clock.elapsed().as_secs_f32();
+        self.display_frame = self.effect.apply(&self.current_frame, t);
+        self.display_split_frame = self.effect.apply(&self.split_frame, t);
+        self.display_channel_frames = self
+            .channel_frames
+            .each_ref()
+            .map(|grid| self.effect.apply(grid, t));
+        // Last, so effects still work on the full colors
+        self.ascii_converter
+            .quantize_colors(&mut self.display_frame);
+        self.ascii_converter
+            .quantize_colors(&mut self.display_split_frame);
+        for grid in &mut self.display_channel_frames {
+            self.ascii_converter.quantize_colors(grid);
+        }
+        self.frame_dirty = false;
+    }
+
+    /// Bring the pane converters of the view in use up to date with the live settings after
+    /// they changed, carrying over each pane's history, and drop those of views not in use
+    fn sync_panes(&mut self) {
+        let stale = std::mem::take(&mut self.panes_stale);
+        if !self.split_view {
+            self.split_panes = None;
+        } else if stale || self.split_panes.is_none() {
+            let (mut left, mut right) = self.split_converters();
+            if let Some((mut previous_left, mut previous_right)) = self.split_panes.take() {
+                left.take_frame_state(&mut previous_left);
+                right.take_frame_state(&mut previous_right);
+            }
+            self.split_panes = Some((left, right));
+        }
+        if !self.channel_view {
+            self.channel_pane = None;
+        } else if stale || self.channel_pane.is_none() {
+            let mut converter = self.channel_converter();
+            if let Some(mut previous) = self.channel_pane.take() {
+                converter.take_frame_state(&mut previous);
+            }
+            self.channel_pane = Some(converter);
+        }
+    }
+
+    /// Settings for the two split panes: the live settings and the compared variant
+    fn split_converters(&self) -> (AsciiConverter, AsciiConverter) {
+        let mut left = self.ascii_converter.clone();
+        // Each pane has its own border, so it gets a little less than half the width
+        left.resize((left.width() / 2).saturating_sub(1).max(1), left.height());
+        let mut right = left.clone();
+        match self.split_compare {
+            SplitCompare::CharacterSet => right.next_character_set(),
+            SplitCompare::Color => right.toggle_color(),
+            SplitCompare::CellMode => right.set_cell_mode(right.cell_mode().next()),
+        }
+        (left, right)
+    }
+
+    /// Size the grid for the terminal: exactly the video pane's inner area in peak detail
+    /// mode, otherwise with some room to spare
+    fn fit_to_terminal(&mut self) {
+        let (width, height) = self.terminal_size;
+        if self.peak_detail.is_some() {
+            // Borders around the video, plus the status bar and controls below it
+            let inner_width = width.saturating_sub(2) as u32;
+            let inner_height = height.saturating_sub(2 + 3 + 2) as u32;
+            self.ascii_converter.set_grid_bounds(GridBounds {
+                min_width: 1,
+                min_height: 1,
+                max_width: inner_width.max(1),
+                max_height: inner_height.max(1),
+            });
+            self.ascii_converter.resize(inner_width, inner_height);
+        } else {
+            let ascii_width = width.saturating_sub(4) as u32;
+            let ascii_height = height.saturating_sub(6) as u32;
+            self.ascii_converter.resize(ascii_width, ascii_height);
+        }
+    }
+
+    /// Switch to one character per drawable cell with aspect-correct fit and the smoothest
+    /// filter, or back to the settings from before
+    fn toggle_peak_detail(&mut self) {
+        match self.peak_detail.take() {
+            Some(restore) => {
+                self.ascii_converter.set_scale_factor(restore.scale_factor);
+                self.ascii_converter.set_scale_mode(restore.scale_mode);
+                self.ascii_converter.set_filter(restore.filter);
+                self.ascii_converter.set_grid_bounds(restore.grid_bounds);
+                self.status_message = "Peak detail: OFF".to_string();
+            }
+            None => {
+                self.peak_detail = Some(PeakRestore {
+                    scale_factor: self.ascii_converter.scale_factor(),
+                    scale_mode: self.ascii_converter.scale_mode(),
+                    filter: self.ascii_converter.filter(),
+                    grid_bounds: self.ascii_converter.grid_bounds(),
+                });
+                self.ascii_converter.set_scale_factor(1.0);
+                self.ascii_converter.set_scale_mode(ScaleMode::Fit);
+                self.ascii_converter.set_filter(FilterKind::Gaussian);
+                self.status_message = "Peak detail".to_string();
+            }
+        }
+        self.fit_to_terminal();
+        self.frame_dirty = true;
+    }
+
+    /// Shortest time between frame conversions, longer in power saver mode
+    fn conversion_interval(&self) -> std::time::Duration {
+        if self.power_saver.is_some() {
+            POWER_SAVER_CONVERSION_INTERVAL
+        } else {
+            CONVERSION_INTERVAL
+        }
+    }
+
+    /// Halve the grid and switch off the costly processing, or bring back the settings
+    /// from before
+    fn toggle_power_saver(&mut self) {
+        match self.power_saver.take() {
+            Some(restore) => {
+                self.ascii_converter.set_scale_factor(restore.scale_factor);
+                self.ascii_converter.set_filter(restore.filter);
+                if restore.edge_overlay != self.ascii_converter.edge_overlay() {
+                    self.ascii_converter.toggle_edge_overlay();
+                }
+                if restore.phosphor != self.ascii_converter.phosphor() {
+                    self.ascii_converter.toggle_phosphor();
+                }
+                self.ascii_converter
+                    .set_auto_contrast(restore.auto_contrast);
+                self.ascii_converter
+                    .set_perceptual_ramp(restore.perceptual_ramp);
+                self.effect = restore.effect;
+                self.split_view = restore.split_view;
+                self.channel_view = restore.channel_view;
+                self.status_message = "Power saver: OFF".to_string();
+            }
+            None => {
+                self.leave_peak_detail();
+                self.power_saver = Some(PowerRestore {
+                    scale_factor: self.ascii_converter.scale_factor(),
+                    filter: self.ascii_converter.filter(),
+                    edge_overlay: self.ascii_converter.edge_overlay(),
+                    phosphor: self.ascii_converter.phosphor(),
+                    auto_contrast: self.ascii_converter.auto_contrast(),
+                    perceptual_ramp: self.ascii_converter.perceptual_ramp(),
+                    effect: self.effect,
+                    split_view: self.split_view,
+                    channel_view: self.channel_view,
+                });
+                self.ascii_converter
+                    .set_scale_factor(self.ascii_converter.scale_factor() * 0.5);
+                self.ascii_converter.set_filter(FilterKind::Nearest);
+                if self.ascii_converter.edge_overlay() {
+                    self.ascii_converter.toggle_edge_overlay();
+                }
+                if self.ascii_converter.phosphor() {
+                    self.ascii_converter.toggle_phosphor();
+                }
+                self.ascii_converter.set_auto_contrast(AutoContrast::Off);
+                self.ascii_converter.set_perceptual_ramp(false);
+                self.effect = AnimatedEffect::None;
+                self.split_view = false;
+                self.channel_view = false;
+                self.status_message = "Power saver: ON".to_string();
+            }
+        }
+        self.fit_to_terminal();
+        self.frame_dirty = true;
+    }
+
+    /// Take a scale set by hand, or by power saver, as the new full-detail scale for
+    /// automatic quality, so the current level keeps applying on top of it
+    fn rebase_auto_quality(&mut self) {
+        if let Some(auto) = self.auto_quality.as_mut() {
+            auto.base_scale = self.ascii_converter.scale_factor() / QUALITY_SCALES[auto.level];
+        }
+    }
+
+    /// Manual changes to a setting peak detail mode manages end the mode, keeping the rest
+    fn leave_peak_detail(&mut self) {
+        if let Some(restore) = self.peak_detail.take() {
+            self.ascii_converter.set_grid_bounds(restore.grid_bounds);
+            self.fit_to_terminal();
+        }
+    }
+
+    /// Settings for the channel view, sized so three bordered panes fit side by side
+    fn channel_converter(&self) -> AsciiConverter {
+        let mut converter = self.ascii_converter.clone();
+        converter.resize(
+            (converter.width() / 3).saturating_sub(1).max(1),
+            converter.height(),
+        );
+        converter
+    }
+
+    /// Title describing what a split pane shows
+    fn split_title(converter: &AsciiConverter, compare: SplitCompare) -> String {
+        match compare {
+            SplitCompare::CharacterSet => charset_name(converter),
+            SplitCompare::Color if converter.color_enabled() => "Color".to_string(),
+            SplitCompare::Color => "Grayscale".to_string(),
+            SplitCompare::CellMode => cell_mode_name(converter),
+        }
+    }
+
+    /// Apply the converter settings from the config file
+    fn apply_config_settings(&mut self) {
+        let ascii = &self.config.ascii;
+        self.ascii_converter.set_character_set(ascii.character_set);
+        self.ascii_converter.set_color_mode(ascii.color_mode);
+        self.ascii_converter.set_color_enabled(ascii.color);
+        self.ascii_converter.set_tint(ascii.tint());
+        self.ascii_converter.set_background(ascii.background());
+        self.ascii_converter.set_scale_factor(ascii.scale);
+        self.ascii_converter.set_tone_curve(ascii.tone_curve);
+        self.ascii_converter.set_scale_mode(ascii.scale_mode);
+        self.ascii_converter.set_rotation(ascii.rotation);
+        self.ascii_converter.set_cell_aspect(ascii.cell_aspect);
+        self.ascii_converter.set_gamma(ascii.gamma);
+        self.ascii_converter.set_contrast(ascii.contrast);
+        self.ascii_converter
+            .set_brightness_offset(ascii.brightness_offset);
+        self.ascii_converter.set_filter(ascii.filter);
+        self.ascii_converter
+            .set_gaussian_sigma(ascii.gaussian_sigma);
+        self.ascii_converter
+            .set_perceptual_ramp(ascii.perceptual_ramp);
+        self.ascii_converter.set_auto_contrast(ascii.auto_contrast);
+        self.ascii_converter.set_clahe_tiles(ascii.clahe_tiles);
+        self.ascii_converter
+            .set_clahe_clip_limit(ascii.clahe_clip_limit);
+        self.ascii_converter
+            .set_phosphor_decay(ascii.phosphor_decay);
+        self.ascii_converter.set_smoothing(ascii.smoothing);
+        self.ascii_converter.set_grain_amount(ascii.grain_amount);
+        self.ascii_converter
+            .set_grain_animated(ascii.grain_animated);
+        self.ascii_converter.set_ascii_only(ascii.ascii_only);
+        self.ascii_converter.set_linearize(ascii.linearize);
+        self.ascii_converter.set_invert(ascii.invert);
+        self.ascii_converter.set_edge_detect(ascii.edge_detect);
+        self.ascii_converter.set_detail_level(ascii.detail_level);
+        self.ascii_converter
+            .set_custom_ramp(ascii.custom_ramp.as_deref());
+        if ascii.calibrate_ramp {
+            self.ascii_converter.calibrate_ramp();
+        }
+        self.ascii_converter
+            .set_index_rounding(ascii.index_rounding);
+        self.ascii_converter.set_cell_mode(ascii.cell_mode);
+        self.ascii_converter.set_sextants_supported(ascii.sextants);
+        self.ascii_converter.set_grid_bounds(ascii.grid_bounds());
+    }
+
+    /// Back to the startup settings, leaving the frame source alone
+    fn reset_settings(&mut self) {
+        self.ascii_converter.reset();
+        self.apply_config_settings();
+        self.peak_detail = None;
+        self.power_saver = None;
+        self.auto_quality = None;
+        self.fit_to_terminal();
+        self.effect = AnimatedEffect::default();
+        self.preset = Preset::default();
+        self.current_profile = None;
+        self.split_view = false;
+        self.split_compare = SplitCompare::CharacterSet;
+        self.split_frame.clear();
+        self.channel_view = false;
+        self.frame_dirty = true;
+        self.status_message = "Settings reset".to_string();
+        self.report_clamping();
+    }
+
+    /// Converter for exports: the live settings at `export.width`/`export.height` when set,
+    /// so exported art doesn't depend on the terminal size
+    fn export_converter(&self) -> AsciiConverter {
+        let export = &self.config.export;
+        if export.width.is_none() && export.height.is_none() {
+            return self.ascii_converter.clone();
+        }
+        let (grid_width, grid_height) = self.ascii_converter.grid_dimensions();
+        self.ascii_converter.with_grid_size(
+            export.width.unwrap_or(grid_width),
+            export.height.unwrap_or(grid_height),
+        )
+    }
+
+    /// Converter for a photo: the export size when set, otherwise one cell per
+    /// `PHOTO_CELL_PIXELS` of the capture, so the render keeps the photo's detail
+    fn photo_converter(&self, width: u32, height: u32) -> AsciiConverter {
+        let export = &self.config.export;
+        if export.width.is_some() || export.height.is_some() {
+            return self.export_converter();
+        }
+        let cell_width = PHOTO_CELL_PIXELS;
+        let cell_height = (cell_width as f32 * self.ascii_converter.cell_aspect()).round() as u32;
+        self.ascii_converter
+            .with_grid_size((width / cell_width).max(1), (height / cell_height).max(1))
+    }
+
+    /// Write the ASCII render of a captured frame next to its raw capture
+    fn save_render(
+        mut converter: AsciiConverter,
+        stem: &Path,
+        frame_data: &[u8],
+        width: u32,
+        height: u32,
+    ) {
+        let render = converter.convert_rgb_frame_direct(frame_data, width, height);
+        let text = render
+            .iter()
+            .map(|line| line.iter().map(|c| c.ch).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let path = stem.with_extension("txt");
+        if let Err(e) = std::fs::write(&path, text) {
+            error!("Failed to save ASCII render {:?}: {}", path, e);
+        }
+    }
+
+    /// Write the frame on screen with its colors as 24-bit ANSI escapes, for `cat` in a
+    /// truecolor terminal
+    fn save_frame_ansi(&mut self) {
+        if self.current_frame.is_empty() {
+            self.status_message = "No frame to save yet".to_string();
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let directory = self.config.config.data_dir.join("frames");
+        let path = directory.join(format!("frame-{timestamp}.ans"));
+        let mut text = to_ansi_string(&self.current_frame);
+        text.push('\n');
+        match std::fs::create_dir_all(&directory).and_then(|()| std::fs::write(&path, text)) {
+            Ok(()) => {
+                info!("Saved ANSI frame to {:?}", path);
+                self.status_message = format!("Frame saved to {}", path.display());
+            }
+            Err(e) => {
+                error!("Failed to save ANSI frame {:?}: {}", path, e);
+                self.status_message = format!("Failed to save frame: {e}");
+            }
+        }
+    }
+
+    fn report_zoom(&mut self) {
+        self.frame_dirty = true;
+        self.status_message = match self.ascii_converter.roi() {
+            Some(_) => format!("Zoom: {:.1}x", self.ascii_converter.zoom()),
+            None => "Zoom: OFF".to_string(),
+        };
+    }
+
+    /// Move the zoomed region by pan steps
+    fn pan(&mut self, dx: f32, dy: f32) {
+        if self.ascii_converter.roi().is_none() {
+            self.status_message = "Zoom in first to pan".to_string();
+            return;
+        }
+        self.ascii_converter.pan(dx, dy);
+        self.report_zoom();
+    }
+
+    /// Draw the frame on screen into a PNG with the bundled font, at `export.glyph_px`
+    fn save_snapshot_png(&mut self) {
+        if self.current_frame.is_empty() {
+            self.status_message = "No frame to save yet".to_string();
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let directory = self.config.config.data_dir.join("frames");
+        let path = directory.join(format!("frame-{timestamp}.png"));
+        let export = &self.config.export;
+        let background = export
+            .background()
+            .or(self.ascii_converter.background().map(|(r, g, b)| [r, g, b]))
+            .unwrap_or([0, 0, 0]);
+        let image = rasterize_on(&self.current_frame, export.glyph_px, background);
+        let saved = std::fs::create_dir_all(&directory)
+            .map_err(image::ImageError::IoError)
+            .and_then(|()| image.save(&path));
+        match saved {
+            Ok(()) => {
+                info!("Saved PNG snapshot to {:?}", path);
+                self.status_message = format!("Snapshot saved to {}", path.display());
+            }
+            Err(e) => {
+                error!("Failed to save PNG snapshot {:?}: {}", path, e);
+                self.status_message = format!("Failed to save snapshot: {e}");
             }
         }
     }
@@ -104,52 +1278,124 @@ impl Component for Home {
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.status_template = StatusTemplate::parse(&config.ui.status_format);
         self.config = config;
+        self.apply_config_settings();
+        self.panes_stale = true;
         Ok(())
     }
 
     fn init(&mut self, area: Size) -> Result<()> {
         // Initialize ASCII converter with terminal dimensions
         // Leave space for UI elements (controls, status)
-        let ascii_width = area.width.saturating_sub(4) as u32;
-        let ascii_height = area.height.saturating_sub(6) as u32;
-        self.ascii_converter.resize(ascii_width, ascii_height);
+        self.terminal_size = (area.width, area.height);
+        self.fit_to_terminal();
+        self.panes_stale = true;
+
+        self.color_support = ColorSupport::detect();
+        info!("Terminal color support: {}", self.color_support.name());
 
         // Initialize camera list
         self.initialize_camera_list();
+        self.start_sinks();
+        self.report_grid_size()?;
         Ok(())
     }
 
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(prompt) = self.value_prompt.as_mut() {
+            match key.code {
+                KeyCode::Esc => {
+                    self.value_prompt = None;
+                    self.status_message = "Value entry cancelled".to_string();
+                    return Ok(Some(Action::SetMode(Mode::Home)));
+                }
+                KeyCode::Enter => return self.submit_value_prompt(),
+                KeyCode::Backspace => {
+                    prompt.text.pop();
+                }
+                KeyCode::Char(c) if prompt.field.accepts(c.to_ascii_lowercase()) => {
+                    prompt.text.push(c.to_ascii_lowercase());
+                }
+                _ => {}
+            }
+            self.show_value_prompt();
+            return Ok(None);
+        }
+        let Some(prompt) = self.profile_prompt.as_mut() else {
+            return Ok(self.quick_select_camera(key));
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.profile_prompt = None;
+                self.status_message = "Profile save cancelled".to_string();
+                return Ok(Some(Action::SetMode(Mode::Home)));
+            }
+            KeyCode::Enter => return self.submit_profile_prompt(),
+            KeyCode::Backspace => {
+                prompt.name.pop();
+                prompt.confirm_overwrite = false;
+            }
+            KeyCode::Char(c) if !c.is_control() => {
+                prompt.name.push(c);
+                prompt.confirm_overwrite = false;
+            }
+            _ => {}
+        }
+        self.show_profile_prompt();
+        Ok(None)
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        let up = match mouse.kind {
+            MouseEventKind::ScrollUp => true,
+            MouseEventKind::ScrollDown => false,
+            _ => return Ok(None),
+        };
+        if self.value_prompt.is_some() || self.profile_prompt.is_some() {
+            return Ok(None);
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        let field = self
+            .status_regions
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            .map(|(field, _)| *field);
+        Ok(match field.and_then(|field| self.wheel_action(field, up)) {
+            Some(action) => Some(action),
+            None if up => self.config.ui.wheel_up.clone(),
+            None => self.config.ui.wheel_down.clone(),
+        })
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        // Anything but frames and timing may change settings the panes need to follow
+        if !matches!(
+            action,
+            Action::Tick
+                | Action::Render
+                | Action::FrameCaptured
+                | Action::FpsUpdate(_)
+                | Action::CameraFrame(..)
+                | Action::CameraYuyvFrame(..)
+                | Action::AsciiFrameReady(_)
+        ) {
+            self.panes_stale = true;
+        }
         match action {
             Action::Tick => {
                 // Process pending frame if enough time has passed
                 self.process_pending_frame();
             }
-            Action::Render => {
-                // Nothing special on render
+            Action::Render => self.render_fps.count(),
+            Action::FrameCaptured => self.capture_fps.count(),
+            Action::FpsUpdate(rate) => self.tick_rate = rate,
+            Action::AsciiFrameReady(frame) => self.show_converted_frame(frame),
+            Action::CameraFrame(frame_data, width, height, captured) => {
+                self.receive_frame(FrameLayout::Rgb, frame_data, width, height, captured)
             }
-            Action::CameraFrame(frame_data, width, height) => {
-                debug!("Received camera frame: {}x{}", width, height);
-                if self.camera_active {
-                    // Use frame throttling to prevent UI blocking
-                    let now = std::time::Instant::now();
-                    if now.duration_since(self.last_frame_processed)
-                        >= std::time::Duration::from_millis(50)
-                    {
-                        // Process frame immediately if enough time has passed
-                        self.current_frame = self.ascii_converter.convert_rgb_frame_direct(
-                            &frame_data,
-                            width,
-                            height,
-                        );
-                        self.last_frame_processed = now;
-                        self.camera_error = None;
-                    } else {
-                        // Store frame for later processing to avoid blocking
-                        self.pending_frame = Some((frame_data, width, height));
-                    }
-                }
+            Action::CameraYuyvFrame(frame_data, width, height, captured) => {
+                self.receive_frame(FrameLayout::Yuyv, frame_data, width, height, captured)
             }
             Action::CameraError(ref error) => {
                 error!("Camera error received: {}", error);
@@ -158,14 +1404,36 @@ impl Component for Home {
             }
             Action::StartCamera => {
                 info!("StartCamera action received");
+                self.ascii_converter.clear_history();
                 self.camera_active = true;
+                self.camera_released = false;
+                self.camera_disconnected = false;
+                self.capture_paused = false;
                 self.camera_error = None;
                 self.status_message = "Camera active".to_string();
             }
+            Action::CameraDisconnected => {
+                self.camera_active = false;
+                self.camera_disconnected = true;
+                // The failures leading up to this are explained by it
+                self.camera_error = None;
+                self.pending_frame = None;
+                self.status_message = "Camera disconnected — press SPACE to reconnect".to_string();
+            }
+            Action::CameraReleased => {
+                self.camera_active = false;
+                self.camera_released = true;
+                self.pending_frame = None;
+                self.status_message =
+                    "Camera released, showing the last frame. Press SPACE to reopen.".to_string();
+            }
             Action::StopCamera => {
                 info!("StopCamera action received");
                 self.camera_active = false;
-                self.current_frame.clear();
+                self.capture_paused = false;
+                self.current_frame = SharedGrid::default();
+                self.frame_writer.clear();
+                self.frame_dirty = true;
                 self.pending_frame = None;
                 self.status_message = format!(
                     "Camera stopped. Found {} camera(s). Press SPACE to restart.",
@@ -185,21 +1453,89 @@ impl Component for Home {
                     self.status_message = "Starting camera...".to_string();
                 }
             }
+            Action::DemoStarted(interval) => {
+                self.demo_interval = Some(interval);
+                self.status_message = format!("Demo mode: every {interval}s");
+            }
+            Action::DemoStopped => {
+                self.demo_interval = None;
+                self.status_message = "Demo mode: Off".to_string();
+            }
+            Action::TestPatternStarted => {
+                self.ascii_converter.clear_history();
+                self.camera_released = false;
+                self.capture_paused = false;
+                self.test_pattern_active = true;
+                self.camera_error = None;
+                self.status_message = "Test pattern active. Press P to stop.".to_string();
+            }
+            Action::TestPatternStopped => {
+                self.test_pattern_active = false;
+                self.current_frame = SharedGrid::default();
+                self.frame_writer.clear();
+                self.split_frame.clear();
+                self.frame_dirty = true;
+                self.pending_frame = None;
+                self.status_message = "Test pattern stopped".to_string();
+            }
+            Action::InputFileStarted(path) => {
+                self.ascii_converter.clear_history();
+                self.status_message = format!("Showing {path}");
+                self.input_file = Some(path);
+            }
+            Action::CapturePaused(paused) => {
+                self.capture_paused = paused;
+                self.status_message = if paused {
+                    match self
+                        .config
+                        .keybindings
+                        .key_for(Mode::Home, &Action::TogglePause)
+                    {
+                        Some(key) => {
+                            format!("Paused, the camera keeps streaming. Press {key} to resume.")
+                        }
+                        None => "Paused, the camera keeps streaming.".to_string(),
+                    }
+                } else {
+                    "Camera active".to_string()
+                };
+            }
+            Action::InputFilePaused(paused) => {
+                self.input_paused = paused;
+                self.status_message = if paused {
+                    "Video paused. Press Space to resume.".to_string()
+                } else {
+                    "Video playing".to_string()
+                };
+            }
             Action::NextCharacterSet => {
                 let current = self.ascii_converter.character_set();
                 self.ascii_converter.set_character_set(current.next());
-                self.status_message = format!(
-                    "Character set: {}",
-                    self.ascii_converter.character_set().name()
-                );
+                self.status_message =
+                    format!("Character set: {}", charset_name(&self.ascii_converter));
+            }
+            Action::NextRenderMode | Action::PreviousRenderMode => {
+                let current = self.ascii_converter.render_mode();
+                self.ascii_converter
+                    .set_render_mode(if action == Action::NextRenderMode {
+                        current.next()
+                    } else {
+                        current.previous()
+                    });
+                self.frame_dirty = true;
+                self.status_message =
+                    format!("Render mode: {}", render_mode_name(&self.ascii_converter));
+            }
+            Action::NextCellMode => {
+                let next = self.ascii_converter.cell_mode().next();
+                self.ascii_converter.set_cell_mode(next);
+                self.status_message = format!("Cells: {}", cell_mode_name(&self.ascii_converter));
             }
             Action::PreviousCharacterSet => {
                 let current = self.ascii_converter.character_set();
                 self.ascii_converter.set_character_set(current.previous());
-                self.status_message = format!(
-                    "Character set: {}",
-                    self.ascii_converter.character_set().name()
-                );
+                self.status_message =
+                    format!("Character set: {}", charset_name(&self.ascii_converter));
             }
             Action::ToggleColor => {
                 self.ascii_converter.toggle_color();
@@ -211,27 +1547,469 @@ impl Component for Home {
                         "OFF"
                     }
                 );
+                self.warn_truecolor_mismatch();
+            }
+            Action::NextColorMode => {
+                self.ascii_converter.next_color_mode();
+                self.frame_dirty = true;
+                self.status_message =
+                    format!("Color mode: {}", self.ascii_converter.color_mode().name());
+                self.warn_truecolor_mismatch();
+            }
+            Action::NextTint => {
+                self.ascii_converter.next_tint();
+                self.frame_dirty = true;
+                self.status_message = match self.ascii_converter.tint() {
+                    Some(color) => format!("Tint: {}", color_name(color)),
+                    None => "Tint: OFF".to_string(),
+                };
+            }
+            Action::ToggleInvert => {
+                self.ascii_converter.toggle_invert();
+                self.frame_dirty = true;
+                self.status_message = format!(
+                    "Invert: {}",
+                    if self.ascii_converter.invert() {
+                        "ON"
+                    } else {
+                        "OFF"
+                    }
+                );
+            }
+            Action::TogglePowerSaver => {
+                self.toggle_power_saver();
+                self.rebase_auto_quality();
+                self.report_clamping();
+            }
+            Action::AutoQualityStarted(target) => {
+                self.auto_quality = Some(AutoQuality {
+                    base_scale: self.ascii_converter.scale_factor(),
+                    level: 0,
+                });
+                self.status_message = format!("Auto quality: holding {target} fps");
+            }
+            Action::AutoQualityStopped => {
+                if let Some(auto) = self.auto_quality.take() {
+                    self.ascii_converter.set_scale_factor(auto.base_scale);
+                }
+                self.status_message = "Auto quality: OFF".to_string();
+            }
+            Action::SetQualityLevel(level) => {
+                if let Some(auto) = self.auto_quality.as_mut() {
+                    auto.level = (level as usize).min(QUALITY_SCALES.len() - 1);
+                    let scale = auto.base_scale * QUALITY_SCALES[auto.level];
+                    self.leave_peak_detail();
+                    self.ascii_converter.set_scale_factor(scale);
+                    let (width, height) = self.ascii_converter.grid_dimensions();
+                    self.status_message = format!("Auto quality: grid {width}x{height}");
+                }
+            }
+            Action::TogglePeakDetail => {
+                self.toggle_peak_detail();
+                self.report_clamping();
             }
             Action::IncreaseScale => {
+                self.leave_peak_detail();
                 self.ascii_converter.increase_scale();
+                self.rebase_auto_quality();
                 self.status_message = format!("Scale: {:.1}x", self.ascii_converter.scale_factor());
+                self.report_clamping();
             }
             Action::DecreaseScale => {
+                self.leave_peak_detail();
                 self.ascii_converter.decrease_scale();
+                self.rebase_auto_quality();
                 self.status_message = format!("Scale: {:.1}x", self.ascii_converter.scale_factor());
+                self.report_clamping();
+            }
+            Action::SetScale(scale) => {
+                self.leave_peak_detail();
+                self.ascii_converter.set_scale_factor(scale);
+                self.rebase_auto_quality();
+                self.report_value(format!(
+                    "Scale: {:.2}x",
+                    self.ascii_converter.scale_factor()
+                ));
+                self.report_clamping();
+            }
+            Action::SetGamma(gamma) => {
+                self.ascii_converter.set_gamma(gamma);
+                self.frame_dirty = true;
+                self.report_value(format!("Gamma: {:.2}", self.ascii_converter.gamma()));
+            }
+            Action::IncreaseGamma => {
+                self.ascii_converter.increase_gamma();
+                self.frame_dirty = true;
+                self.status_message = format!("Gamma: {:.1}", self.ascii_converter.gamma());
+            }
+            Action::DecreaseGamma => {
+                self.ascii_converter.decrease_gamma();
+                self.frame_dirty = true;
+                self.status_message = format!("Gamma: {:.1}", self.ascii_converter.gamma());
+            }
+            Action::IncreaseContrast => {
+                self.ascii_converter.increase_contrast();
+                self.frame_dirty = true;
+                self.status_message = format!("Contrast: {:.1}", self.ascii_converter.contrast());
+            }
+            Action::DecreaseContrast => {
+                self.ascii_converter.decrease_contrast();
+                self.frame_dirty = true;
+                self.status_message = format!("Contrast: {:.1}", self.ascii_converter.contrast());
+            }
+            Action::IncreaseBrightness => {
+                self.ascii_converter.adjust_brightness(BRIGHTNESS_STEP);
+                self.frame_dirty = true;
+                self.status_message =
+                    format!("Brightness: {:+}", self.ascii_converter.brightness_offset());
+            }
+            Action::DecreaseBrightness => {
+                self.ascii_converter.adjust_brightness(-BRIGHTNESS_STEP);
+                self.frame_dirty = true;
+                self.status_message =
+                    format!("Brightness: {:+}", self.ascii_converter.brightness_offset());
+            }
+            Action::SetTargetFps(fps) => self.report_value(format!("Target FPS: {fps}")),
+            Action::ExposureChanged(value) => {
+                self.status_message = format!("Camera exposure: {value}");
+            }
+            Action::NextCamera => return Ok(self.step_camera(1)),
+            Action::PreviousCamera => return Ok(self.step_camera(-1)),
+            Action::SetCamera(index) => {
+                // A camera plugged in since the list was taken is missing from it; an index
+                // still missing after a refresh is reported by the app
+                if !self.available_cameras.iter().any(|(i, _)| *i == index) {
+                    self.refresh_camera_list();
+                }
+                if let Some((_, name)) = self.available_cameras.iter().find(|(i, _)| *i == index) {
+                    // Trails from the last camera would bleed into the next one's picture
+                    self.ascii_converter.clear_history();
+                    self.config.camera.default_camera_index = index;
+                    self.status_message = format!("Camera: {name} (ID {index})");
+                }
+            }
+            Action::Error(ref message) => self.status_message = message.clone(),
+            Action::SetResolution(width, height) => {
+                self.report_value(format!("Requested resolution: {width}x{height}"))
+            }
+            Action::EnterScale
+            | Action::EnterGamma
+            | Action::EnterFps
+            | Action::EnterResolution => {
+                let field = match action {
+                    Action::EnterScale => ValueField::Scale,
+                    Action::EnterGamma => ValueField::Gamma,
+                    Action::EnterFps => ValueField::Fps,
+                    _ => ValueField::Resolution,
+                };
+                self.value_prompt = Some(ValuePrompt {
+                    field,
+                    text: String::new(),
+                });
+                self.show_value_prompt();
+                return Ok(Some(Action::SetMode(Mode::Prompt)));
+            }
+            Action::NextToneCurve => {
+                self.ascii_converter.next_tone_curve();
+                self.status_message =
+                    format!("Tone curve: {}", self.ascii_converter.tone_curve().name());
+            }
+            Action::NextScaleMode => {
+                self.leave_peak_detail();
+                self.ascii_converter.next_scale_mode();
+                self.status_message =
+                    format!("Scale mode: {}", self.ascii_converter.scale_mode().name());
+            }
+            Action::RotateCw => {
+                self.ascii_converter.rotate_cw();
+                self.frame_dirty = true;
+                self.status_message =
+                    format!("Rotation: {}", self.ascii_converter.rotation().name());
+            }
+            Action::ZoomIn => {
+                self.ascii_converter.zoom_in();
+                self.report_zoom();
+            }
+            Action::ZoomOut => {
+                self.ascii_converter.zoom_out();
+                self.report_zoom();
+            }
+            Action::PanLeft => self.pan(-1.0, 0.0),
+            Action::PanRight => self.pan(1.0, 0.0),
+            Action::PanUp => self.pan(0.0, -1.0),
+            Action::PanDown => self.pan(0.0, 1.0),
+            Action::IncreaseCellAspect => {
+                self.ascii_converter.increase_cell_aspect();
+                self.report_cell_aspect();
+            }
+            Action::DecreaseCellAspect => {
+                self.ascii_converter.decrease_cell_aspect();
+                self.report_cell_aspect();
+            }
+            Action::TogglePerceptualRamp => {
+                self.ascii_converter.toggle_perceptual_ramp();
+                self.status_message = format!(
+                    "Perceptual ramp: {}",
+                    if self.ascii_converter.perceptual_ramp() {
+                        "On"
+                    } else {
+                        "Off"
+                    }
+                );
             }
+            Action::NextAutoContrast => {
+                self.ascii_converter.next_auto_contrast();
+                self.status_message = format!(
+                    "Auto contrast: {}",
+                    self.ascii_converter.auto_contrast().name()
+                );
+            }
+            Action::NextFilter => {
+                self.leave_peak_detail();
+                self.ascii_converter.next_filter();
+                self.status_message = format!("Filter: {}", self.ascii_converter.filter().name());
+            }
+            Action::NextIndexRounding => {
+                let rounding = self.ascii_converter.index_rounding().next();
+                self.ascii_converter.set_index_rounding(rounding);
+                self.frame_dirty = true;
+                self.status_message = format!("Rounding: {}", rounding.name());
+            }
+            Action::ToggleEdgeOverlay => {
+                self.ascii_converter.toggle_edge_overlay();
+                self.status_message = format!(
+                    "Edge overlay: {}",
+                    if self.ascii_converter.edge_overlay() {
+                        "ON"
+                    } else {
+                        "OFF"
+                    }
+                );
+            }
+            Action::ToggleEdgeDetect => {
+                self.ascii_converter.toggle_edge_detect();
+                self.frame_dirty = true;
+                self.status_message = format!(
+                    "Edge detect: {}",
+                    if self.ascii_converter.edge_detect() {
+                        "ON"
+                    } else {
+                        "OFF"
+                    }
+                );
+            }
+            Action::TogglePhosphor => {
+                self.ascii_converter.toggle_phosphor();
+                self.status_message = format!(
+                    "Phosphor trail: {}",
+                    if self.ascii_converter.phosphor() {
+                        "On"
+                    } else {
+                        "Off"
+                    }
+                );
+            }
+            Action::ToggleDetailLevel => {
+                let level = match self.ascii_converter.detail_level() {
+                    Some(_) => None,
+                    None => {
+                        let (width, height) = self.detail_source();
+                        Some(self.ascii_converter.current_detail_level(width, height))
+                    }
+                };
+                self.ascii_converter.set_detail_level(level);
+                self.report_detail_level();
+                self.frame_dirty = true;
+            }
+            Action::IncreaseDetail => {
+                let (width, height) = self.detail_source();
+                self.ascii_converter.increase_detail_level(width, height);
+                self.report_detail_level();
+                self.frame_dirty = true;
+            }
+            Action::DecreaseDetail => {
+                let (width, height) = self.detail_source();
+                self.ascii_converter.decrease_detail_level(width, height);
+                self.report_detail_level();
+                self.frame_dirty = true;
+            }
+            Action::ToggleSmoothing => {
+                self.ascii_converter.toggle_smoothing();
+                self.report_smoothing();
+            }
+            Action::IncreaseSmoothing => {
+                self.ascii_converter.increase_smoothing();
+                self.report_smoothing();
+            }
+            Action::DecreaseSmoothing => {
+                self.ascii_converter.decrease_smoothing();
+                self.report_smoothing();
+            }
+            Action::IncreaseGrain => {
+                self.ascii_converter.increase_grain();
+                self.report_grain();
+            }
+            Action::DecreaseGrain => {
+                self.ascii_converter.decrease_grain();
+                self.report_grain();
+            }
+            Action::ToggleGrainAnimation => {
+                let animated = !self.ascii_converter.grain_animated();
+                self.ascii_converter.set_grain_animated(animated);
+                self.status_message =
+                    format!("Grain: {}", if animated { "Animated" } else { "Static" });
+            }
+            Action::IncreasePhosphorDecay => {
+                self.ascii_converter.increase_phosphor_decay();
+                self.status_message = format!(
+                    "Phosphor decay: {:.2}",
+                    self.ascii_converter.phosphor_decay()
+                );
+            }
+            Action::DecreasePhosphorDecay => {
+                self.ascii_converter.decrease_phosphor_decay();
+                self.status_message = format!(
+                    "Phosphor decay: {:.2}",
+                    self.ascii_converter.phosphor_decay()
+                );
+            }
+            Action::IncreaseEdgeStrength => {
+                self.ascii_converter.increase_edge_strength();
+                self.status_message =
+                    format!("Edge strength: {:.1}", self.ascii_converter.edge_strength());
+            }
+            Action::DecreaseEdgeStrength => {
+                self.ascii_converter.decrease_edge_strength();
+                self.status_message =
+                    format!("Edge strength: {:.1}", self.ascii_converter.edge_strength());
+            }
+            Action::ResetSettings => self.reset_settings(),
+            Action::CyclePreset => {
+                self.preset = self.preset.next();
+                self.preset.apply(&mut self.ascii_converter);
+                self.status_message = format!("Preset: {}", self.preset.name());
+                self.warn_truecolor_mismatch();
+            }
+            Action::PendingChord(ref keys) => self.pending_chord = keys.clone(),
+            Action::SetMode(_) => self.pending_chord.clear(),
+            Action::SaveProfile => {
+                self.profile_prompt = Some(ProfilePrompt::default());
+                self.show_profile_prompt();
+                return Ok(Some(Action::SetMode(Mode::Prompt)));
+            }
+            Action::NextProfile => {
+                let next = self
+                    .config
+                    .profiles
+                    .next_after(self.current_profile.as_deref())
+                    .map(|(name, profile)| (name.clone(), profile.clone()));
+                match next {
+                    Some((name, profile)) => {
+                        profile.apply(&mut self.ascii_converter);
+                        self.status_message = format!("Profile: {name}");
+                        self.current_profile = Some(name);
+                        self.warn_truecolor_mismatch();
+                    }
+                    None => {
+                        self.status_message =
+                            "No saved profiles. Press Ctrl+S to save one.".to_string();
+                    }
+                }
+            }
+            Action::NextEffect => {
+                self.effect = self.effect.next();
+                self.frame_dirty = true;
+                self.status_message = format!("Effect: {}", self.effect.name());
+            }
+            Action::ToggleChannelView => {
+                self.channel_view = !self.channel_view;
+                // The two layouts don't combine
+                self.split_view = false;
+                self.frame_dirty = true;
+                self.status_message = format!(
+                    "Channel view: {}",
+                    if self.channel_view {
+                        "R | G | B"
+                    } else {
+                        "OFF"
+                    }
+                );
+            }
+            Action::ToggleSplitView => {
+                self.split_view = !self.split_view;
+                self.channel_view = false;
+                self.split_frame.clear();
+                self.frame_dirty = true;
+                self.status_message = if self.split_view {
+                    format!("Split view: comparing {}", self.split_compare.name())
+                } else {
+                    "Split view: OFF".to_string()
+                };
+            }
+            Action::CycleSplitComparison => {
+                self.split_compare = self.split_compare.next();
+                self.split_frame.clear();
+                self.frame_dirty = true;
+                self.status_message =
+                    format!("Split view: comparing {}", self.split_compare.name());
+            }
+            Action::Burst if !self.camera_active => {
+                self.status_message = "Start the camera before capturing a burst".to_string();
+            }
+            Action::BurstFrame(stem, frame_data, width, height) => {
+                Self::save_render(self.export_converter(), &stem, &frame_data, width, height);
+            }
+            Action::BurstProgress(captured, total) => {
+                self.status_message = format!("Burst: {captured}/{total} frames");
+            }
+            Action::BurstFinished(captured, total, directory) => {
+                self.status_message = if captured < total {
+                    format!(
+                        "Burst saved {captured}/{total} frames (camera too slow) to {directory}"
+                    )
+                } else {
+                    format!("Burst saved {captured} frames to {directory}")
+                };
+            }
+            Action::Photo if !self.camera_active => {
+                self.status_message = "Start the camera before taking a photo".to_string();
+            }
+            Action::PhotoStarted => {
+                self.status_message = "Capturing photo…".to_string();
+            }
+            Action::PhotoFrame(stem, frame_data, width, height) => {
+                let converter = self.photo_converter(width, height);
+                Self::save_render(converter, &stem, &frame_data, width, height);
+            }
+            Action::PhotoSaved(path) => {
+                self.status_message = format!("Photo saved to {path}");
+            }
+            Action::PhotoFailed(message) => {
+                self.status_message = message;
+            }
+            Action::SaveFrameAnsi => self.save_frame_ansi(),
+            Action::SaveSnapshotPng => self.save_snapshot_png(),
+            Action::ToggleStreamToFile(ref path) => self.toggle_text_stream(path),
             Action::Resize(width, height) => {
                 // Update ASCII converter dimensions when terminal is resized
-                let ascii_width = width.saturating_sub(4) as u32;
-                let ascii_height = height.saturating_sub(6) as u32;
-                self.ascii_converter.resize(ascii_width, ascii_height);
+                self.terminal_size = (width, height);
+                self.fit_to_terminal();
+                self.report_clamping();
             }
             _ => {}
         }
+        self.report_grid_size()?;
+        self.report_luma_capture()?;
+        self.report_settings()?;
         Ok(None)
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        // Animated effects advance on every render, even without a new camera frame
+        self.refresh_display_frames();
+        // Pane titles follow settings changed while no frames arrive
+        self.sync_panes();
+
         // Create layout: main area + status bar + controls
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -257,14 +2035,17 @@ impl Component for Home {
 
 impl Home {
     fn draw_ascii_video(&self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let background = self.ascii_converter.background_color();
         let block = Block::default()
             .title("ASCII Vision")
             .borders(Borders::ALL)
-            .border_style(if self.camera_active {
-                Style::default().fg(Color::Green)
-            } else {
-                Style::default().fg(Color::Red)
-            });
+            .border_style(
+                if self.camera_active || self.test_pattern_active || self.input_file.is_some() {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default().fg(Color::Red)
+                },
+            );
 
         if let Some(ref error) = self.camera_error {
             // Display error message
@@ -278,7 +2059,7 @@ impl Home {
             let placeholder = if self.camera_active {
                 "Starting camera..."
             } else {
-                "Press SPACE to start camera\n\nControls:\n- SPACE: Toggle camera\n- C: Toggle color\n- S/A: Change character set\n- +/-: Adjust scale"
+                "Press SPACE to start camera\n\nControls:\n- SPACE: Toggle camera\n- C: Toggle color\n- S/A: Change character set\n- +/-: Adjust scale\n- P: Test pattern"
             };
 
             let text = Paragraph::new(placeholder)
@@ -286,59 +2067,229 @@ impl Home {
                 .alignment(Alignment::Center)
                 .block(block);
             frame.render_widget(text, area);
+        } else if self.channel_view {
+            // One pane per color channel
+            let thirds = Layout::horizontal([Constraint::Ratio(1, 3); 3]).split(area);
+            let titles = ["Red", "Green", "Blue"];
+            for ((pane, grid), title) in thirds.iter().zip(&self.display_channel_frames).zip(titles)
+            {
+                let block = block.clone().title(title);
+                let inner = block.inner(*pane);
+                frame.render_widget(block, *pane);
+                Self::render_grid(frame, grid, inner, background);
+            }
+        } else if self.split_view {
+            // Display both variants side by side
+            let halves =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(area);
+            let Some((left, right)) = &self.split_panes else {
+                return Ok(());
+            };
+            let panes = [
+                (halves[0], &self.display_frame, left),
+                (halves[1], &self.display_split_frame, right),
+            ];
+            for (pane, grid, converter) in panes {
+                let block = block
+                    .clone()
+                    .title(Self::split_title(converter, self.split_compare));
+                let inner = block.inner(pane);
+                frame.render_widget(block, pane);
+                Self::render_grid(frame, grid, inner, background);
+            }
         } else {
             // Display ASCII video
             let inner = block.inner(area);
             frame.render_widget(block, area);
+            Self::render_grid(frame, &self.display_frame, inner, background);
+        }
 
-            // Render ASCII frame
-            for (y, line) in self.current_frame.iter().enumerate() {
-                if y >= inner.height as usize {
-                    break;
-                }
-
-                for (x, colored_char) in line.iter().enumerate() {
-                    if x >= inner.width as usize {
-                        break;
-                    }
-
-                    let cell_area = Rect {
-                        x: inner.x + x as u16,
-                        y: inner.y + y as u16,
-                        width: 1,
-                        height: 1,
-                    };
+        Ok(())
+    }
 
-                    let char_widget =
-                        Paragraph::new(colored_char.ch.to_string()).style(colored_char.style);
-                    frame.render_widget(char_widget, cell_area);
-                }
+    /// Render an ASCII grid into the given area, clipping anything that doesn't fit
+    ///
+    /// A grid smaller than the area is centered with letterbox bars around it. Cells without
+    /// a background of their own sit on `background` when set.
+    fn render_grid(
+        frame: &mut Frame,
+        grid: &[Vec<ColoredChar>],
+        area: Rect,
+        background: Option<Color>,
+    ) {
+        let base = background.map_or_else(Style::default, |color| Style::default().bg(color));
+        frame.render_widget(Block::default().style(base), area);
+        let grid_width = grid.first().map_or(0, Vec::len) as u16;
+        let grid_height = grid.len() as u16;
+        let inner = if grid_width < area.width || grid_height < area.height {
+            frame.render_widget(
+                Block::default().style(Style::default().bg(LETTERBOX_COLOR)),
+                area,
+            );
+            let width = grid_width.min(area.width);
+            let height = grid_height.min(area.height);
+            Rect {
+                x: area.x + (area.width - width) / 2,
+                y: area.y + (area.height - height) / 2,
+                width,
+                height,
             }
-        }
+        } else {
+            area
+        };
+        // Without wrapping the paragraph clips rows and lines that don't fit
+        frame.render_widget(Paragraph::new(frame_to_text(grid)).style(base), inner);
+    }
 
-        Ok(())
+    /// Action for scrolling over a status bar field, if the field's setting can be adjusted
+    fn wheel_action(&self, field: StatusField, up: bool) -> Option<Action> {
+        Some(match (field, up) {
+            (StatusField::Scale, true) => Action::IncreaseScale,
+            (StatusField::Scale, false) => Action::DecreaseScale,
+            (StatusField::Gamma, true) => Action::IncreaseGamma,
+            (StatusField::Gamma, false) => Action::DecreaseGamma,
+            (StatusField::Charset, true) => Action::NextCharacterSet,
+            (StatusField::Charset, false) => Action::PreviousCharacterSet,
+            (StatusField::Mode, true) => Action::NextRenderMode,
+            (StatusField::Mode, false) => Action::PreviousRenderMode,
+            (StatusField::Resolution, true) => Action::IncreaseResolution,
+            (StatusField::Resolution, false) => Action::DecreaseResolution,
+            // These only cycle one way
+            (StatusField::Curve, _) => Action::NextToneCurve,
+            (StatusField::Filter, _) => Action::NextFilter,
+            (StatusField::Effect, _) => Action::NextEffect,
+            (StatusField::Preset, _) => Action::CyclePreset,
+            (StatusField::Invert, _) => Action::ToggleInvert,
+            (StatusField::Rotation, _) => Action::RotateCw,
+            (StatusField::Color, _) => Action::NextColorMode,
+            _ => return None,
+        })
     }
 
-    fn draw_status_bar(&self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let camera_status = if self.camera_active { "ON" } else { "OFF" };
-        let color_status = if self.ascii_converter.color_enabled() {
+    fn draw_status_bar(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let camera_status = if self.test_pattern_active {
+            "TEST PATTERN"
+        } else if self.input_paused || (self.camera_active && self.capture_paused) {
+            "PAUSED"
+        } else if self.input_file.is_some() {
+            "FILE"
+        } else if self.camera_active {
+            "ON"
+        } else if self.camera_disconnected {
+            "DISCONNECTED"
+        } else if self.camera_released {
+            "RELEASED"
+        } else {
+            "OFF"
+        };
+        let color_status = match self.ascii_converter.tint() {
+            _ if self.ascii_converter.color_mode() == ColorMode::Truecolor => "ON".to_string(),
+            _ if self.ascii_converter.color_enabled() => {
+                self.ascii_converter.color_mode().name().to_string()
+            }
+            Some(color) => color_name(color),
+            None => "OFF".to_string(),
+        };
+        let invert_status = if self.ascii_converter.invert() {
             "ON"
         } else {
             "OFF"
         };
 
-        let status_text = format!(
-            "Camera: {} | Character Set: {} | Color: {} | Scale: {:.1}x | {}",
-            camera_status,
-            self.ascii_converter.character_set().name(),
-            color_status,
-            self.ascii_converter.scale_factor(),
-            self.status_message
-        );
+        let (grid_width, grid_height) = self.ascii_converter.grid_dimensions();
+        let mut values = HashMap::from([
+            (StatusField::Camera, camera_status.to_string()),
+            (StatusField::Charset, charset_name(&self.ascii_converter)),
+            (StatusField::Mode, render_mode_name(&self.ascii_converter)),
+            (StatusField::Color, color_status),
+            (
+                StatusField::Scale,
+                format!("{:.1}x", self.ascii_converter.scale_factor()),
+            ),
+            (
+                StatusField::Curve,
+                self.ascii_converter.tone_curve().name().to_string(),
+            ),
+            (
+                StatusField::Gamma,
+                format!("{:.1}", self.ascii_converter.gamma()),
+            ),
+            (StatusField::Invert, invert_status.to_string()),
+            (StatusField::Effect, self.effect.name().to_string()),
+            (StatusField::Preset, self.preset.name().to_string()),
+            (
+                StatusField::Filter,
+                self.ascii_converter.filter().name().to_string(),
+            ),
+            (
+                StatusField::Rotation,
+                self.ascii_converter.rotation().name().to_string(),
+            ),
+            (StatusField::Fps, format!("{:.1}", self.fps.per_second)),
+            (
+                StatusField::CaptureFps,
+                format!("{:.0}", self.capture_fps.per_second()),
+            ),
+            (
+                StatusField::RenderFps,
+                format!("{:.0}", self.render_fps.per_second()),
+            ),
+            (StatusField::TickRate, format!("{:.0}", self.tick_rate)),
+            (StatusField::Grid, format!("{grid_width}x{grid_height}")),
+            (StatusField::Msg, self.status_message.clone()),
+        ]);
+        if let Some((width, height)) = self.source_resolution {
+            values.insert(StatusField::Resolution, format!("{width}x{height}"));
+        }
+        let (status_text, regions) = self.status_template.layout(&values);
 
+        // Surface a half-typed multi-key binding so chords are discoverable
+        let mut title = "Status".to_string();
+        if self.peak_detail.is_some() {
+            title.push_str(" | Peak detail");
+        }
+        if self.power_saver.is_some() {
+            title.push_str(" | Power saver");
+        }
+        if self.ascii_converter.edge_detect() {
+            title.push_str(" | Edge detect");
+        }
+        if self.ascii_converter.rotation() != Rotation::None {
+            title.push_str(&format!(
+                " | Rotated {}",
+                self.ascii_converter.rotation().name()
+            ));
+        }
+        if self.auto_quality.is_some() {
+            let (width, height) = self.ascii_converter.grid_dimensions();
+            title.push_str(&format!(" | Auto quality {width}x{height}"));
+            if let Some((width, height)) = self.source_resolution {
+                title.push_str(&format!(" @ {width}x{height}"));
+            }
+        }
+        if let Some(interval) = self.demo_interval {
+            title.push_str(&format!(" | Demo every {interval}s"));
+        }
+        if !self.pending_chord.is_empty() {
+            title.push_str(&format!(" | Keys: {}…", self.pending_chord));
+        }
+        let block = Block::default().borders(Borders::ALL).title(title);
+        // The text is one unwrapped line along the top of the inner area, so columns map
+        // straight onto screen cells and anything past the edge is cut off
+        let inner = block.inner(area);
+        self.status_regions = regions
+            .into_iter()
+            .filter_map(|(field, columns)| {
+                let start = columns.start.min(inner.width as usize) as u16;
+                let end = columns.end.min(inner.width as usize) as u16;
+                (start < end && inner.height > 0)
+                    .then(|| (field, Rect::new(inner.x + start, inner.y, end - start, 1)))
+            })
+            .collect();
         let status_bar = Paragraph::new(status_text)
             .style(Style::default().fg(Color::Cyan))
-            .block(Block::default().borders(Borders::ALL).title("Status"));
+            .block(block);
 
         frame.render_widget(status_bar, area);
         Ok(())
@@ -356,3 +2307,37 @@ impl Home {
         Ok(())
     }
 }
+
+/// Name of the render mode, noting the cell mode drawn in its place if any
+fn render_mode_name(converter: &AsciiConverter) -> String {
+    match converter.render_mode() {
+        mode @ (RenderMode::Ascii | RenderMode::Edges) => mode.name().to_string(),
+        _ => cell_mode_name(converter),
+    }
+}
+
+/// Name of the selected character set, noting the ASCII set drawn in its place if any
+fn charset_name(converter: &AsciiConverter) -> String {
+    let selected = converter.character_set();
+    let effective = converter.effective_character_set();
+    if converter.uses_custom_ramp() {
+        "Custom".to_string()
+    } else if converter.custom_ramp().is_some() {
+        format!("Custom (as {})", effective.name())
+    } else if selected == effective {
+        selected.name().to_string()
+    } else {
+        format!("{} (as {})", selected.name(), effective.name())
+    }
+}
+
+/// Cell mode for the status bar, noting when it draws as something else
+fn cell_mode_name(converter: &AsciiConverter) -> String {
+    let selected = converter.cell_mode();
+    let effective = converter.effective_cell_mode();
+    if selected == effective {
+        selected.name().to_string()
+    } else {
+        format!("{} (as {})", selected.name(), effective.name())
+    }
+}
@@ -0,0 +1,98 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use super::Component;
+use crate::{action::Action, logging};
+
+/// Lines moved by PageUp/PageDown
+const PAGE_LINES: usize = 10;
+
+/// Scrollable overlay showing the most recent log lines
+#[derive(Debug, Default)]
+pub struct LogViewer {
+    visible: bool,
+    /// Lines scrolled up from the newest entry, 0 follows the log
+    scroll: usize,
+}
+
+impl LogViewer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Component for LogViewer {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.visible {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_add(PAGE_LINES),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(PAGE_LINES),
+            KeyCode::Home => self.scroll = usize::MAX,
+            KeyCode::End => self.scroll = 0,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::ToggleLogViewer {
+            self.visible = !self.visible;
+            self.scroll = 0;
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+
+        // Bottom half of the screen, over the video
+        let [_, panel] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+        let lines = logging::recent_logs();
+        let rows = panel.height.saturating_sub(2) as usize;
+        let max_scroll = lines.len().saturating_sub(rows);
+        self.scroll = self.scroll.min(max_scroll);
+        let end = lines.len() - self.scroll;
+        let start = end.saturating_sub(rows);
+
+        let text: Vec<Line> = lines[start..end]
+            .iter()
+            .map(|line| {
+                let color = if line.contains("ERROR") {
+                    Color::Red
+                } else if line.contains(" WARN ") {
+                    Color::Yellow
+                } else {
+                    Color::Gray
+                };
+                Line::styled(line.as_str(), Style::default().fg(color))
+            })
+            .collect();
+        let title = if self.scroll == 0 {
+            format!("Logs ({} lines, Up/Down/PgUp/PgDn to scroll)", lines.len())
+        } else {
+            format!(
+                "Logs ({} lines, {} up from newest)",
+                lines.len(),
+                self.scroll
+            )
+        };
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(title),
+        );
+
+        frame.render_widget(Clear, panel);
+        frame.render_widget(paragraph, panel);
+        Ok(())
+    }
+}
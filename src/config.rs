@@ -1,17 +1,36 @@
 #![allow(dead_code)] // Remove this once you start using the code
 
-use std::{collections::HashMap, env, path::PathBuf};
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use ratatui::style::{Color, Modifier, Style};
-use serde::{Deserialize, de::Deserializer};
-use tracing::error;
-
-use crate::{action::Action, app::Mode};
+use serde::{Deserialize, Serialize, de::Deserializer};
+use tracing::{error, warn};
+
+use crate::{
+    action::Action,
+    app::Mode,
+    ascii::{
+        AutoContrast, CharacterSet, ColorMode, FilterKind, GridBounds, IndexRounding, Rotation,
+        ScaleMode, ToneCurve, parse_rgb,
+    },
+    blocks::CellMode,
+    camera::{CameraBackend, CameraFilter, ResolutionRequest},
+    profiles::Profiles,
+    screen::{CaptureSource, ScreenRegion},
+    status::DEFAULT_STATUS_FORMAT,
+};
+
+/// File in the config directory holding the settings saved on quit
+pub const SESSION_FILE: &str = "session.json";
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -33,6 +52,39 @@ pub struct CameraConfig {
     pub width: u32,
     #[serde(default = "default_camera_height")]
     pub height: u32,
+    #[serde(default = "default_burst_count")]
+    pub burst_count: u32,
+    #[serde(default)]
+    pub backend: CameraBackend,
+    #[serde(default)]
+    pub resolution_request: ResolutionRequest,
+    /// Re-request the capture resolution to match the ASCII grid size
+    #[serde(default)]
+    pub auto_resolution: bool,
+    /// List virtual devices like OBS Virtual Camera or v4l2loopback alongside real cameras
+    #[serde(default)]
+    pub include_virtual: bool,
+    /// Case-insensitive name substrings that mark a device as virtual
+    #[serde(default = "default_exclude_patterns")]
+    pub exclude_patterns: Vec<String>,
+    /// List a camera name only once; turn off to tell identical cameras apart
+    #[serde(default = "default_dedup_names")]
+    pub dedup_names: bool,
+    /// Ask for YUYV instead of MJPEG, so monochrome output can skip decoding frames
+    #[serde(default)]
+    pub yuyv: bool,
+    /// Image or video file to show instead of opening a camera; videos need `ffmpeg`
+    #[serde(default)]
+    pub input: Option<PathBuf>,
+    /// Grab the screen instead of a camera; needs `ffmpeg`
+    #[serde(default)]
+    pub source: CaptureSource,
+    /// Part of the screen to grab as `x,y,width,height`, instead of all of it
+    #[serde(default)]
+    pub screen_region: Option<String>,
+    /// Start the camera on launch instead of waiting for `ToggleCamera`
+    #[serde(default)]
+    pub auto_start: bool,
 }
 
 fn default_camera_index() -> u32 {
@@ -47,6 +99,489 @@ fn default_camera_width() -> u32 {
 fn default_camera_height() -> u32 {
     480
 }
+fn default_burst_count() -> u32 {
+    10
+}
+fn default_exclude_patterns() -> Vec<String> {
+    CameraFilter::default().exclude_patterns
+}
+
+fn default_dedup_names() -> bool {
+    true
+}
+
+impl CameraConfig {
+    /// The parsed `screen_region`
+    pub fn screen_region(&self) -> Result<Option<ScreenRegion>> {
+        self.screen_region
+            .as_deref()
+            .map(|region| {
+                region
+                    .parse()
+                    .map_err(|e| eyre!("Invalid camera.screen_region: {e}"))
+            })
+            .transpose()
+    }
+
+    /// Which detected devices to list
+    pub fn camera_filter(&self) -> CameraFilter {
+        CameraFilter {
+            exclude_patterns: self.exclude_patterns.clone(),
+            include_virtual: self.include_virtual,
+            dedup_names: self.dedup_names,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct InputConfig {
+    /// How long to wait for the next key of a multi-key binding
+    #[serde(default = "default_chord_timeout_ms")]
+    pub chord_timeout_ms: u64,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self {
+            chord_timeout_ms: default_chord_timeout_ms(),
+        }
+    }
+}
+
+fn default_chord_timeout_ms() -> u64 {
+    1000
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UiConfig {
+    /// Status bar layout, see `StatusTemplate` for the tokens
+    #[serde(default = "default_status_format")]
+    pub status_format: String,
+    /// Capture the mouse so the scroll wheel can adjust settings
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+    /// Action for scrolling up outside the status bar's adjustable fields
+    #[serde(default = "default_wheel_up")]
+    pub wheel_up: Option<Action>,
+    /// Action for scrolling down outside the status bar's adjustable fields
+    #[serde(default = "default_wheel_down")]
+    pub wheel_down: Option<Action>,
+    /// Save the character set, color, scale and camera on quit and start with them next
+    /// time, over what the config file says
+    #[serde(default)]
+    pub remember_settings: bool,
+    /// Convert frames on a worker thread so a slow conversion doesn't stall input and
+    /// drawing; frames that arrive while one converts replace each other
+    #[serde(default = "default_convert_in_background")]
+    pub convert_in_background: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            status_format: default_status_format(),
+            mouse: default_mouse(),
+            wheel_up: default_wheel_up(),
+            wheel_down: default_wheel_down(),
+            remember_settings: false,
+            convert_in_background: default_convert_in_background(),
+        }
+    }
+}
+
+fn default_convert_in_background() -> bool {
+    true
+}
+
+fn default_status_format() -> String {
+    DEFAULT_STATUS_FORMAT.to_string()
+}
+
+fn default_mouse() -> bool {
+    false
+}
+
+fn default_wheel_up() -> Option<Action> {
+    Some(Action::IncreaseScale)
+}
+
+fn default_wheel_down() -> Option<Action> {
+    Some(Action::DecreaseScale)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DemoConfig {
+    /// Time between demo steps
+    #[serde(default = "default_demo_interval_secs")]
+    pub interval_secs: u64,
+    /// How long a key press holds off the next step
+    #[serde(default = "default_demo_resume_after_secs")]
+    pub resume_after_secs: u64,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_demo_interval_secs(),
+            resume_after_secs: default_demo_resume_after_secs(),
+        }
+    }
+}
+
+fn default_demo_interval_secs() -> u64 {
+    6
+}
+
+fn default_demo_resume_after_secs() -> u64 {
+    5
+}
+
+/// Grid size used for exports instead of the terminal-derived display grid
+#[derive(Clone, Debug, Deserialize)]
+pub struct ExportConfig {
+    /// Export grid width in cells, the display width when unset
+    #[serde(default)]
+    pub width: Option<u32>,
+    /// Export grid height in cells, the display height when unset
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// Glyph height in pixels in PNG snapshots, glyphs being half as wide
+    #[serde(default = "default_glyph_px")]
+    pub glyph_px: u32,
+    /// PNG snapshot background as `#rrggbb`, `ascii.background` or black when unset
+    #[serde(default)]
+    pub background: Option<String>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            width: None,
+            height: None,
+            glyph_px: default_glyph_px(),
+            background: None,
+        }
+    }
+}
+
+impl ExportConfig {
+    /// The snapshot background, if set and valid; an invalid one is logged and left off
+    pub fn background(&self) -> Option<[u8; 3]> {
+        let value = self.background.as_deref()?;
+        let color = parse_rgb(value);
+        if color.is_none() {
+            warn!("Ignoring export.background {value:?}, expected #rrggbb");
+        }
+        color.map(|(r, g, b)| [r, g, b])
+    }
+}
+
+fn default_glyph_px() -> u32 {
+    24
+}
+
+/// Places converted frames go besides the terminal
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct OutputConfig {
+    /// Record the session as an asciinema cast at this path
+    #[serde(default)]
+    pub record: Option<PathBuf>,
+    /// Stream frames as ANSI text to TCP clients connecting to this address
+    #[serde(default)]
+    pub serve: Option<String>,
+    /// Append every frame as plain text to this file or named pipe, separated by form feeds
+    #[serde(default)]
+    pub text_file: Option<PathBuf>,
+}
+
+/// Power saver mode, trading smoothness and detail for battery life
+#[derive(Clone, Debug, Deserialize)]
+pub struct PowerConfig {
+    /// Start in power saver mode when running on battery
+    #[serde(default)]
+    pub auto_on_battery: bool,
+    /// Capture rate while power saver is on
+    #[serde(default = "default_power_saver_fps")]
+    pub fps: f64,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            auto_on_battery: false,
+            fps: default_power_saver_fps(),
+        }
+    }
+}
+
+fn default_power_saver_fps() -> f64 {
+    10.0
+}
+
+/// Automatic quality, lowering grid detail to keep the frame rate up
+#[derive(Clone, Debug, Deserialize)]
+pub struct QualityConfig {
+    /// Start with automatic quality on
+    #[serde(default)]
+    pub auto: bool,
+    /// Frame rate to hold, capped at the capture rate
+    #[serde(default = "default_quality_target_fps")]
+    pub target_fps: f64,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            target_fps: default_quality_target_fps(),
+        }
+    }
+}
+
+fn default_quality_target_fps() -> f64 {
+    15.0
+}
+
+/// Images fetched over HTTP with `--url`
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RemoteConfig {
+    /// Re-fetch the image this often, for URLs that keep updating like webcam snapshots
+    #[serde(default)]
+    pub refresh_secs: Option<f64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AsciiConfig {
+    /// Built-in character set to start with
+    #[serde(default)]
+    pub character_set: CharacterSet,
+    /// Start in color instead of monochrome
+    #[serde(default)]
+    pub color: bool,
+    /// Truecolor, Ansi256 or Ansi16 while color is on, for terminals without 24-bit color
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    /// Single color the picture is drawn in, `#rrggbb` or Green, Amber, White or Cyan
+    #[serde(default)]
+    pub tint: Option<String>,
+    /// Color filling the picture behind the characters as `#rrggbb`, the terminal's own
+    /// background when unset
+    #[serde(default)]
+    pub background: Option<String>,
+    /// Grid size multiplier to start with, 0.1 to 2.0
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default)]
+    pub tone_curve: ToneCurve,
+    #[serde(default)]
+    pub scale_mode: ScaleMode,
+    /// Clockwise rotation of the source, for cameras mounted sideways or upside down
+    #[serde(default)]
+    pub rotation: Rotation,
+    /// Height of a terminal cell over its width, for fonts that aren't about 1:2
+    #[serde(default = "default_cell_aspect")]
+    pub cell_aspect: f32,
+    /// Cells per source pixel across, keeping detail constant across terminal sizes
+    #[serde(default)]
+    pub detail_level: Option<f32>,
+    /// Display gamma, above 1.0 brightens midtones
+    #[serde(default = "default_gamma")]
+    pub gamma: f32,
+    /// Luma stretch around mid gray, 1.0 leaves it as is
+    #[serde(default = "default_contrast")]
+    pub contrast: f32,
+    /// Luma levels added after contrast, -128 to 128
+    #[serde(default)]
+    pub brightness_offset: i16,
+    /// Compute luma from linear light, for more faithful brightness of saturated colors
+    #[serde(default)]
+    pub linearize: bool,
+    /// Dense characters for bright areas instead of dark ones
+    #[serde(default)]
+    pub invert: bool,
+    /// Draw the Sobel edge strength instead of brightness, for an outline look
+    #[serde(default)]
+    pub edge_detect: bool,
+    /// How pixels under a cell are combined
+    #[serde(default)]
+    pub filter: FilterKind,
+    /// Width of the Gaussian filter relative to a cell
+    #[serde(default = "default_gaussian_sigma")]
+    pub gaussian_sigma: f32,
+    /// Space ramp characters by glyph coverage and dither between them
+    #[serde(default)]
+    pub perceptual_ramp: bool,
+    /// Characters to draw with instead of the built-in sets, densest first
+    #[serde(default)]
+    pub custom_ramp: Option<String>,
+    /// Reorder the custom ramp by each glyph's measured coverage
+    #[serde(default)]
+    pub calibrate_ramp: bool,
+    /// How brightness between two ramp characters picks one
+    #[serde(default)]
+    pub index_rounding: IndexRounding,
+    /// Ramp characters or block glyphs with separately colored sub-cells
+    #[serde(default)]
+    pub cell_mode: CellMode,
+    /// The terminal font has sextant glyphs (U+1FB00); otherwise sextants draw as half blocks
+    #[serde(default)]
+    pub sextants: bool,
+    #[serde(default)]
+    pub auto_contrast: AutoContrast,
+    /// CLAHE tiles per axis
+    #[serde(default = "default_clahe_tiles")]
+    pub clahe_tiles: u32,
+    /// CLAHE histogram clip limit, as a multiple of the mean bin height
+    #[serde(default = "default_clahe_clip_limit")]
+    pub clahe_clip_limit: f32,
+    #[serde(default = "default_min_width")]
+    pub min_width: u32,
+    #[serde(default = "default_min_height")]
+    pub min_height: u32,
+    #[serde(default = "default_max_width")]
+    pub max_width: u32,
+    #[serde(default = "default_max_height")]
+    pub max_height: u32,
+    /// Fraction of a phosphor trail's glow lost per frame
+    #[serde(default = "default_phosphor_decay")]
+    pub phosphor_decay: f32,
+    /// Weight of earlier frames in each cell's brightness, 0.0 is off
+    #[serde(default)]
+    pub smoothing: f32,
+    /// Film grain strength, 0.0 (off) to 1.0
+    #[serde(default)]
+    pub grain_amount: f32,
+    /// Reseed the grain every frame instead of keeping a fixed pattern
+    #[serde(default = "default_grain_animated")]
+    pub grain_animated: bool,
+    /// Only emit printable 7-bit ASCII, drawing Unicode sets with an ASCII ramp
+    #[serde(default)]
+    pub ascii_only: bool,
+}
+
+impl Default for AsciiConfig {
+    fn default() -> Self {
+        Self {
+            character_set: CharacterSet::default(),
+            color: false,
+            color_mode: ColorMode::default(),
+            tint: None,
+            background: None,
+            scale: default_scale(),
+            tone_curve: ToneCurve::default(),
+            scale_mode: ScaleMode::default(),
+            rotation: Rotation::default(),
+            cell_aspect: default_cell_aspect(),
+            detail_level: None,
+            gamma: default_gamma(),
+            contrast: default_contrast(),
+            brightness_offset: 0,
+            linearize: false,
+            invert: false,
+            edge_detect: false,
+            filter: FilterKind::default(),
+            gaussian_sigma: default_gaussian_sigma(),
+            perceptual_ramp: false,
+            custom_ramp: None,
+            calibrate_ramp: false,
+            index_rounding: IndexRounding::default(),
+            cell_mode: CellMode::default(),
+            sextants: false,
+            auto_contrast: AutoContrast::default(),
+            clahe_tiles: default_clahe_tiles(),
+            clahe_clip_limit: default_clahe_clip_limit(),
+            min_width: default_min_width(),
+            min_height: default_min_height(),
+            max_width: default_max_width(),
+            max_height: default_max_height(),
+            phosphor_decay: default_phosphor_decay(),
+            smoothing: 0.0,
+            grain_amount: 0.0,
+            grain_animated: true,
+            ascii_only: false,
+        }
+    }
+}
+
+impl AsciiConfig {
+    pub fn grid_bounds(&self) -> GridBounds {
+        GridBounds {
+            min_width: self.min_width,
+            min_height: self.min_height,
+            max_width: self.max_width,
+            max_height: self.max_height,
+        }
+    }
+
+    /// The tint, if set and valid; an invalid one is logged and left off
+    pub fn tint(&self) -> Option<(u8, u8, u8)> {
+        let value = self.tint.as_deref()?;
+        let color = parse_rgb(value);
+        if color.is_none() {
+            warn!("Ignoring ascii.tint {value:?}, expected #rrggbb or a color name");
+        }
+        color
+    }
+
+    /// The background color, if set and valid; an invalid one is logged and left off
+    pub fn background(&self) -> Option<(u8, u8, u8)> {
+        let value = self.background.as_deref()?;
+        let color = parse_rgb(value);
+        if color.is_none() {
+            warn!("Ignoring ascii.background {value:?}, expected #rrggbb");
+        }
+        color
+    }
+}
+
+fn default_min_width() -> u32 {
+    20
+}
+fn default_min_height() -> u32 {
+    8
+}
+fn default_max_width() -> u32 {
+    320
+}
+fn default_max_height() -> u32 {
+    120
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_cell_aspect() -> f32 {
+    2.0
+}
+
+fn default_gamma() -> f32 {
+    1.0
+}
+
+fn default_contrast() -> f32 {
+    1.0
+}
+
+fn default_gaussian_sigma() -> f32 {
+    0.5
+}
+
+fn default_clahe_tiles() -> u32 {
+    4
+}
+
+fn default_clahe_clip_limit() -> f32 {
+    2.0
+}
+
+fn default_phosphor_decay() -> f32 {
+    0.15
+}
+
+fn default_grain_animated() -> bool {
+    true
+}
 
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
@@ -55,9 +590,30 @@ pub struct Config {
     #[serde(default)]
     pub camera: CameraConfig,
     #[serde(default)]
+    pub ascii: AsciiConfig,
+    #[serde(default)]
+    pub input: InputConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub remote: RemoteConfig,
+    #[serde(default)]
+    pub power: PowerConfig,
+    #[serde(default)]
+    pub quality: QualityConfig,
+    #[serde(default)]
+    pub demo: DemoConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
+    /// Named setting profiles, stored separately from the config file
+    #[serde(skip)]
+    pub profiles: Profiles,
 }
 
 lazy_static! {
@@ -119,8 +675,69 @@ impl Config {
             }
         }
 
+        cfg.profiles = Profiles::load(&config_dir).unwrap_or_else(|e| {
+            error!("Failed to load profiles: {e}");
+            Profiles::default()
+        });
+        if cfg.ui.remember_settings {
+            match Session::load(&config_dir) {
+                Ok(Some(session)) => session.apply(&mut cfg),
+                Ok(None) => {}
+                Err(e) => error!("Failed to load the last session's settings: {e}"),
+            }
+        }
+
         Ok(cfg)
     }
+
+    /// Save the settings restored on the next launch to the session file next to the
+    /// config file, which itself is left untouched
+    pub fn save_session(&self) -> Result<()> {
+        let dir = &self.config.config_dir;
+        fs::create_dir_all(dir)?;
+        let session = Session::capture(self);
+        fs::write(
+            dir.join(SESSION_FILE),
+            serde_json::to_string_pretty(&session)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Settings from the last run, applied over the config file at startup
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub character_set: CharacterSet,
+    pub color: bool,
+    pub scale: f32,
+    pub camera_index: u32,
+}
+
+impl Session {
+    fn capture(config: &Config) -> Self {
+        Self {
+            character_set: config.ascii.character_set,
+            color: config.ascii.color,
+            scale: config.ascii.scale,
+            camera_index: config.camera.default_camera_index,
+        }
+    }
+
+    fn apply(&self, config: &mut Config) {
+        config.ascii.character_set = self.character_set;
+        config.ascii.color = self.color;
+        config.ascii.scale = self.scale;
+        config.camera.default_camera_index = self.camera_index;
+    }
+
+    /// Read the session file in `dir`, `None` before the first save
+    fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(SESSION_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
 }
 
 pub fn get_data_dir() -> PathBuf {
@@ -172,6 +789,22 @@ impl<'de> Deserialize<'de> for KeyBindings {
     }
 }
 
+impl KeyBindings {
+    /// Keys bound to `action` in `mode` as the help shows them, the shortest binding when
+    /// there are several
+    pub fn key_for(&self, mode: Mode, action: &Action) -> Option<String> {
+        self.get(&mode)?
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(keys, _)| {
+                keys.iter()
+                    .map(|key| format!("<{}>", key_event_to_string(key)))
+                    .collect::<String>()
+            })
+            .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+    }
+}
+
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
@@ -535,6 +1168,14 @@ mod tests {
                 .unwrap(),
             &Action::Quit
         );
+        assert_eq!(
+            c.keybindings.key_for(Mode::Home, &Action::TogglePause),
+            Some("<alt-p>".to_string())
+        );
+        assert_eq!(
+            c.keybindings.key_for(Mode::Prompt, &Action::TogglePause),
+            None
+        );
         Ok(())
     }
 
@@ -619,4 +1260,27 @@ mod tests {
             KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)
         );
     }
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ascii-vision-session-{}", std::process::id()));
+        assert_eq!(Session::load(&dir).unwrap(), None);
+
+        let mut config = Config::default();
+        config.config.config_dir = dir.clone();
+        config.ascii.character_set = CharacterSet::Blocks;
+        config.ascii.color = true;
+        config.ascii.scale = 0.6;
+        config.camera.default_camera_index = 2;
+        config.save_session().unwrap();
+
+        let mut restored = Config::default();
+        Session::load(&dir).unwrap().unwrap().apply(&mut restored);
+        assert_eq!(restored.ascii.character_set, CharacterSet::Blocks);
+        assert!(restored.ascii.color);
+        assert_eq!(restored.ascii.scale, 0.6);
+        assert_eq!(restored.camera.default_camera_index, 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
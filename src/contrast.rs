@@ -0,0 +1,147 @@
+/// Lookup table spreading a histogram over the full range via its cumulative distribution
+fn equalization_map(histogram: &[f32; 256]) -> [u8; 256] {
+    let total: f32 = histogram.iter().sum();
+    let mut map = [0u8; 256];
+    let mut cumulative = 0.0;
+    for (value, count) in histogram.iter().enumerate() {
+        cumulative += count;
+        map[value] = (cumulative * 255.0 / total.max(1.0)).round() as u8;
+    }
+    map
+}
+
+/// Global histogram equalization over a luma grid
+pub fn equalize(luma: &mut [u8]) {
+    let mut histogram = [0.0f32; 256];
+    for value in luma.iter() {
+        histogram[*value as usize] += 1.0;
+    }
+    let map = equalization_map(&histogram);
+    luma.iter_mut()
+        .for_each(|value| *value = map[*value as usize]);
+}
+
+/// Cap every bin at `clip_limit` times the mean bin height and spread the excess evenly
+///
+/// This bounds the slope of the resulting transform, so `clip_limit` is the most a
+/// tile's contrast can be stretched and flat regions aren't blown up into noise.
+fn clip_histogram(histogram: &mut [f32; 256], clip_limit: f32) {
+    let total: f32 = histogram.iter().sum();
+    let limit = clip_limit * total / 256.0;
+    let mut excess = 0.0;
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+    histogram
+        .iter_mut()
+        .for_each(|count| *count += excess / 256.0);
+}
+
+/// Contrast-limited adaptive histogram equalization over a `width` x `height` luma grid
+///
+/// The grid is split into `tiles` x `tiles` regions, each equalized with a clipped
+/// histogram, and every cell blends the transforms of its four nearest tile centers so
+/// there are no seams at tile borders.
+pub fn clahe(luma: &mut [u8], width: usize, height: usize, tiles: usize, clip_limit: f32) {
+    if width == 0 || height == 0 || luma.len() != width * height {
+        return;
+    }
+    let tiles_x = tiles.clamp(1, width);
+    let tiles_y = tiles.clamp(1, height);
+    let tile_width = width.div_ceil(tiles_x);
+    let tile_height = height.div_ceil(tiles_y);
+
+    let mut maps = Vec::with_capacity(tiles_x * tiles_y);
+    for ty in 0..tiles_y {
+        for tx in 0..tiles_x {
+            let mut histogram = [0.0f32; 256];
+            for y in ty * tile_height..((ty + 1) * tile_height).min(height) {
+                for x in tx * tile_width..((tx + 1) * tile_width).min(width) {
+                    histogram[luma[y * width + x] as usize] += 1.0;
+                }
+            }
+            clip_histogram(&mut histogram, clip_limit);
+            maps.push(equalization_map(&histogram));
+        }
+    }
+
+    // Position of a cell relative to tile centers: the lower tile and the blend towards the next
+    let neighbours = |position: usize, size: usize, count: usize| {
+        let center = (position as f32 + 0.5) / size as f32 - 0.5;
+        let lower = (center.floor().max(0.0) as usize).min(count - 1);
+        let upper = (lower + 1).min(count - 1);
+        (lower, upper, (center - lower as f32).clamp(0.0, 1.0))
+    };
+    let source = luma.to_vec();
+    for y in 0..height {
+        let (ty0, ty1, fy) = neighbours(y, tile_height, tiles_y);
+        for x in 0..width {
+            let (tx0, tx1, fx) = neighbours(x, tile_width, tiles_x);
+            let value = source[y * width + x] as usize;
+            let sample = |tx: usize, ty: usize| maps[ty * tiles_x + tx][value] as f32;
+            let top = sample(tx0, ty0) * (1.0 - fx) + sample(tx1, ty0) * fx;
+            let bottom = sample(tx0, ty1) * (1.0 - fx) + sample(tx1, ty1) * fx;
+            luma[y * width + x] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spread between the darkest and brightest value in a slice
+    fn range(values: &[u8]) -> u8 {
+        values.iter().max().unwrap() - values.iter().min().unwrap()
+    }
+
+    #[test]
+    fn test_equalize_stretches_low_contrast() {
+        let mut luma: Vec<u8> = (0..64).map(|i| 100 + (i % 8) as u8).collect();
+        equalize(&mut luma);
+        assert!(range(&luma) > 200);
+    }
+
+    #[test]
+    fn test_clahe_boosts_local_contrast_in_uneven_light() {
+        // Left half in shadow, right half brightly lit, each with faint detail
+        let (width, height) = (32, 16);
+        let mut luma: Vec<u8> = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let base = if x < width / 2 { 20 } else { 220 };
+                base + ((x + y) % 4) as u8 * 3
+            })
+            .collect();
+        clahe(&mut luma, width, height, 4, 8.0);
+
+        let shadow: Vec<u8> = (0..height)
+            .flat_map(|y| luma[y * width + 2..y * width + 6].to_vec())
+            .collect();
+        let lit: Vec<u8> = (0..height)
+            .flat_map(|y| luma[y * width + 26..y * width + 30].to_vec())
+            .collect();
+        // Detail spanning 9 levels before is at least tripled on both sides
+        assert!(range(&shadow) >= 27, "shadow range {}", range(&shadow));
+        assert!(range(&lit) >= 27, "lit range {}", range(&lit));
+    }
+
+    #[test]
+    fn test_clahe_with_tight_clip_is_gentle() {
+        let (width, height) = (16, 16);
+        let original: Vec<u8> = (0..width * height).map(|i| (i % 256) as u8).collect();
+        let mut luma = original.clone();
+        clahe(&mut luma, width, height, 1, 1.0);
+        // A clip limit of 1 flattens the histogram, which leaves an even ramp untouched
+        let max_shift = luma
+            .iter()
+            .zip(&original)
+            .map(|(a, b)| (*a as i16 - *b as i16).abs())
+            .max()
+            .unwrap();
+        assert!(max_shift <= 2, "max shift {max_shift}");
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use crate::action::Action;
+
+/// Actions the demo cycles through, one per interval
+const DEMO_STEPS: &[Action] = &[
+    Action::NextCharacterSet,
+    Action::ToggleColor,
+    Action::NextEffect,
+    Action::NextToneCurve,
+    Action::NextCharacterSet,
+    Action::CyclePreset,
+];
+
+/// Bounds for the time between demo steps
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Timer that steps through settings on its own, backing off while the user is typing
+#[derive(Debug, Clone)]
+pub struct DemoScheduler {
+    running: bool,
+    interval: Duration,
+    /// How long manual input holds off the next step
+    resume_after: Duration,
+    next_step_at: Instant,
+    step: usize,
+}
+
+impl DemoScheduler {
+    pub fn new(interval: Duration, resume_after: Duration) -> Self {
+        Self {
+            running: false,
+            interval: interval.clamp(MIN_INTERVAL, MAX_INTERVAL),
+            resume_after,
+            next_step_at: Instant::now(),
+            step: 0,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Start or stop cycling, returning whether it is now running
+    pub fn toggle(&mut self, now: Instant) -> bool {
+        self.running = !self.running;
+        self.next_step_at = now + self.interval;
+        self.running
+    }
+
+    /// Halve the interval, down to one second
+    pub fn faster(&mut self, now: Instant) {
+        self.set_interval(self.interval / 2, now);
+    }
+
+    /// Double the interval, up to a minute
+    pub fn slower(&mut self, now: Instant) {
+        self.set_interval(self.interval * 2, now);
+    }
+
+    fn set_interval(&mut self, interval: Duration, now: Instant) {
+        self.interval = interval.clamp(MIN_INTERVAL, MAX_INTERVAL);
+        self.next_step_at = now + self.interval;
+    }
+
+    /// Hold off the next step so the demo doesn't undo what the user just changed
+    pub fn pause_for_input(&mut self, now: Instant) {
+        if self.running {
+            self.next_step_at = self.next_step_at.max(now + self.resume_after);
+        }
+    }
+
+    /// The next action to emit, once the current interval has elapsed
+    pub fn tick(&mut self, now: Instant) -> Option<Action> {
+        if !self.running || now < self.next_step_at {
+            return None;
+        }
+        let action = DEMO_STEPS[self.step % DEMO_STEPS.len()].clone();
+        self.step += 1;
+        self.next_step_at = now + self.interval;
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_on_interval_and_backs_off_on_input() {
+        let start = Instant::now();
+        let mut demo = DemoScheduler::new(Duration::from_secs(4), Duration::from_secs(10));
+        assert_eq!(demo.tick(start + Duration::from_secs(5)), None);
+
+        assert!(demo.toggle(start));
+        assert_eq!(demo.tick(start + Duration::from_secs(3)), None);
+        assert_eq!(
+            demo.tick(start + Duration::from_secs(4)),
+            Some(DEMO_STEPS[0].clone())
+        );
+
+        // A key press at 5s holds the next step until 15s instead of 8s
+        demo.pause_for_input(start + Duration::from_secs(5));
+        assert_eq!(demo.tick(start + Duration::from_secs(8)), None);
+        assert_eq!(
+            demo.tick(start + Duration::from_secs(15)),
+            Some(DEMO_STEPS[1].clone())
+        );
+
+        demo.faster(start);
+        demo.faster(start);
+        demo.faster(start);
+        assert_eq!(demo.interval(), MIN_INTERVAL);
+    }
+}
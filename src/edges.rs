@@ -0,0 +1,124 @@
+use ratatui::style::{Color, Modifier};
+
+use crate::ascii::ColoredChar;
+
+/// Edge masks weaker than this leave cells untouched, so flat areas keep their color
+const EDGE_THRESHOLD: f32 = 0.1;
+
+/// Blur a `width` x `height` buffer with a separable binomial kernel
+fn blur(values: &[f32], width: usize, height: usize, kernel: &[f32]) -> Vec<f32> {
+    let radius = kernel.len() / 2;
+    let total: f32 = kernel.iter().sum();
+    let sample = |get: &dyn Fn(usize) -> f32, center: usize, len: usize| {
+        kernel
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| {
+                // Clamp to the border so edges of the grid don't read as contours
+                let index = (center + i).saturating_sub(radius).min(len - 1);
+                get(index) * weight
+            })
+            .sum::<f32>()
+            / total
+    };
+
+    let mut horizontal = vec![0.0; values.len()];
+    for y in 0..height {
+        let row = &values[y * width..(y + 1) * width];
+        for x in 0..width {
+            horizontal[y * width + x] = sample(&|i| row[i], x, width);
+        }
+    }
+    let mut result = vec![0.0; values.len()];
+    for y in 0..height {
+        for x in 0..width {
+            result[y * width + x] = sample(&|i| horizontal[i * width + x], y, height);
+        }
+    }
+    result
+}
+
+/// Difference of Gaussians over a luma grid, normalized to `[0, 1]`
+pub fn difference_of_gaussians(luma: &[f32], width: usize, height: usize) -> Vec<f32> {
+    if width == 0 || height == 0 || luma.len() != width * height {
+        return vec![0.0; luma.len()];
+    }
+    let narrow = blur(luma, width, height, &[1.0, 2.0, 1.0]);
+    let wide = blur(luma, width, height, &[1.0, 4.0, 6.0, 4.0, 1.0]);
+    let mut edges: Vec<f32> = narrow
+        .iter()
+        .zip(&wide)
+        .map(|(narrow, wide)| (narrow - wide).abs())
+        .collect();
+    let max = edges.iter().cloned().fold(0.0, f32::max);
+    if max > 0.0 {
+        edges.iter_mut().for_each(|edge| *edge /= max);
+    }
+    edges
+}
+
+/// Sobel gradient magnitude of a luma grid, scaled so a full black-to-white step is 255
+/// and flat areas are 0
+///
+/// Neighbours past the border are clamped to it, so the border doesn't read as an edge.
+pub fn sobel(luma: &[u8], width: usize, height: usize) -> Vec<u8> {
+    if width == 0 || height == 0 || luma.len() != width * height {
+        return vec![0; luma.len()];
+    }
+    let at = |x: usize, y: usize, dx: isize, dy: isize| {
+        let x = x.saturating_add_signed(dx).min(width - 1);
+        let y = y.saturating_add_signed(dy).min(height - 1);
+        luma[y * width + x] as i32
+    };
+    let mut edges = Vec::with_capacity(luma.len());
+    for y in 0..height {
+        for x in 0..width {
+            let p = |dx, dy| at(x, y, dx, dy);
+            let gx = p(1, -1) + 2 * p(1, 0) + p(1, 1) - p(-1, -1) - 2 * p(-1, 0) - p(-1, 1);
+            let gy = p(-1, 1) + 2 * p(0, 1) + p(1, 1) - p(-1, -1) - 2 * p(0, -1) - p(1, -1);
+            let magnitude = ((gx * gx + gy * gy) as f32).sqrt() / 4.0;
+            edges.push(magnitude.min(255.0) as u8);
+        }
+    }
+    edges
+}
+
+/// Darken colored cells along edges, or embolden them when there is no color
+pub fn apply_edge_overlay(grid: &mut [Vec<ColoredChar>], edges: &[f32], strength: f32) {
+    let width = grid.first().map_or(0, Vec::len);
+    for (y, line) in grid.iter_mut().enumerate() {
+        for (x, cell) in line.iter_mut().enumerate() {
+            let edge = edges.get(y * width + x).copied().unwrap_or(0.0);
+            if edge < EDGE_THRESHOLD {
+                continue;
+            }
+            match cell.style.fg {
+                Some(Color::Rgb(r, g, b)) => {
+                    let factor = 1.0 - (edge * strength).clamp(0.0, 1.0);
+                    let darken = |c: u8| (c as f32 * factor) as u8;
+                    cell.style = cell.style.fg(Color::Rgb(darken(r), darken(g), darken(b)));
+                }
+                _ => cell.style = cell.style.add_modifier(Modifier::BOLD),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sobel_finds_steps_and_ignores_flat_areas() {
+        // Black left half, white right half
+        let luma: Vec<u8> = (0..16).map(|i| if i % 4 < 2 { 0 } else { 255 }).collect();
+        assert_eq!(
+            sobel(&luma, 4, 4),
+            [0, 255, 255, 0].repeat(4),
+            "only the columns either side of the step are edges"
+        );
+        assert_eq!(sobel(&[90; 9], 3, 3), [0; 9]);
+        assert_eq!(sobel(&[7], 1, 1), [0]);
+        assert!(sobel(&[1, 2], 3, 3).iter().all(|edge| *edge == 0));
+    }
+}
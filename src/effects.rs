@@ -0,0 +1,173 @@
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::ascii::ColoredChar;
+
+/// Seconds for a full trip around the color wheel
+const HUE_CYCLE_PERIOD: f32 = 6.0;
+/// Seconds for the scanline band to sweep the whole grid
+const SCANLINE_PERIOD: f32 = 3.0;
+
+/// Time-based styling effects, re-derived from the cached frame on every render
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnimatedEffect {
+    #[default]
+    None,
+    HueCycle,
+    Scanlines,
+}
+
+impl AnimatedEffect {
+    pub fn next(&self) -> AnimatedEffect {
+        match self {
+            AnimatedEffect::None => AnimatedEffect::HueCycle,
+            AnimatedEffect::HueCycle => AnimatedEffect::Scanlines,
+            AnimatedEffect::Scanlines => AnimatedEffect::None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimatedEffect::None => "None",
+            AnimatedEffect::HueCycle => "Hue Cycle",
+            AnimatedEffect::Scanlines => "Scanlines",
+        }
+    }
+
+    /// Whether the output changes over time even when the source frame doesn't
+    pub fn is_animated(&self) -> bool {
+        *self != AnimatedEffect::None
+    }
+
+    /// Apply the effect at `t` seconds to a converted grid
+    pub fn apply(&self, grid: &[Vec<ColoredChar>], t: f32) -> Vec<Vec<ColoredChar>> {
+        match self {
+            AnimatedEffect::None => grid.to_vec(),
+            AnimatedEffect::HueCycle => {
+                let shift = (t / HUE_CYCLE_PERIOD).fract() * 360.0;
+                grid.iter()
+                    .map(|line| {
+                        line.iter()
+                            .enumerate()
+                            .map(|(x, cell)| {
+                                let fg = match cell.style.fg {
+                                    Some(Color::Rgb(r, g, b)) => rotate_hue(r, g, b, shift),
+                                    // Grayscale output gets a rainbow sweep instead
+                                    _ => hsv_to_rgb(shift + x as f32 * 4.0, 1.0, 1.0),
+                                };
+                                ColoredChar {
+                                    ch: cell.ch,
+                                    style: cell.style.fg(Color::Rgb(fg.0, fg.1, fg.2)),
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+            AnimatedEffect::Scanlines => {
+                let rows = grid.len().max(1);
+                let band = ((t / SCANLINE_PERIOD).fract() * rows as f32) as usize;
+                grid.iter()
+                    .enumerate()
+                    .map(|(y, line)| {
+                        let style = if y == band || y == band + 1 {
+                            Style::default().add_modifier(Modifier::BOLD)
+                        } else if y % 2 == 1 {
+                            Style::default().add_modifier(Modifier::DIM)
+                        } else {
+                            Style::default()
+                        };
+                        line.iter()
+                            .map(|cell| ColoredChar {
+                                ch: cell.ch,
+                                style: cell.style.patch(style),
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Rotate the hue of an RGB color by `degrees`
+fn rotate_hue(r: u8, g: u8, b: u8, degrees: f32) -> (u8, u8, u8) {
+    let (h, s, v) = rgb_to_hsv(r, g, b);
+    hsv_to_rgb(h + degrees, s, v)
+}
+
+fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (u8, u8, u8) {
+    let hue = hue.rem_euclid(360.0);
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = value - chroma;
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let to_byte = |c: f32| ((c + m) * 255.0).round() as u8;
+    (to_byte(r), to_byte(g), to_byte(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_restyle_cells_and_keep_characters() {
+        let red = ColoredChar {
+            ch: '@',
+            style: Style::default().fg(Color::Rgb(255, 0, 0)),
+        };
+        let plain = ColoredChar {
+            ch: '.',
+            style: Style::default(),
+        };
+        let grid = vec![vec![red, plain]; 4];
+        assert_eq!(AnimatedEffect::None.apply(&grid, 1.0), grid);
+        assert!(!AnimatedEffect::None.is_animated());
+
+        // A third of the way around the color wheel turns red into green
+        let cycled = AnimatedEffect::HueCycle.apply(&grid, HUE_CYCLE_PERIOD / 3.0);
+        assert_eq!(cycled[0][0].ch, '@');
+        assert_eq!(cycled[0][0].style.fg, Some(Color::Rgb(0, 255, 0)));
+        assert!(matches!(cycled[0][1].style.fg, Some(Color::Rgb(..))));
+
+        // The band starts at the top; odd rows outside it are dimmed
+        let scanned = AnimatedEffect::Scanlines.apply(&grid, 0.0);
+        assert!(scanned[0][0].style.add_modifier.contains(Modifier::BOLD));
+        assert!(scanned[1][1].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(scanned[2], grid[2]);
+        assert!(scanned[3][0].style.add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        for (r, g, b) in [(255, 0, 0), (12, 200, 90), (40, 40, 40), (0, 0, 0)] {
+            let (h, s, v) = rgb_to_hsv(r, g, b);
+            assert_eq!(hsv_to_rgb(h, s, v), (r, g, b));
+        }
+        assert_eq!(rotate_hue(255, 0, 0, 240.0), (0, 0, 255));
+    }
+}
@@ -0,0 +1,269 @@
+//! C API over the ASCII converter.
+//!
+//! # Memory ownership
+//!
+//! - Converters are created with [`avs_converter_new`] and owned by the caller until passed to
+//!   [`avs_converter_free`]. The pointer is opaque and must not be used after it is freed.
+//! - Frame buffers are borrowed for the duration of a call and never retained.
+//! - Output is written into a caller-owned buffer as UTF-8 text, rows separated by `\n`, followed
+//!   by a terminating NUL byte. Nothing returned by this API needs to be freed except converters.
+//!
+//! Functions never unwind into the caller: panics are caught and reported as [`AVS_ERR_PANIC`].
+//! On success conversion functions return the number of bytes written, excluding the NUL.
+
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    ptr, slice,
+};
+
+use crate::ascii::{AsciiConverter, CharacterSet, to_ansi_string};
+
+/// A required pointer argument was null
+pub const AVS_ERR_NULL: i32 = -1;
+/// The frame length doesn't match `width * height * 3`
+pub const AVS_ERR_INVALID_FRAME: i32 = -2;
+/// The output buffer can't hold the text and its NUL terminator
+pub const AVS_ERR_BUFFER_TOO_SMALL: i32 = -3;
+/// An argument was out of range
+pub const AVS_ERR_INVALID_ARGUMENT: i32 = -4;
+/// The conversion panicked; the converter should be freed
+pub const AVS_ERR_PANIC: i32 = -5;
+
+/// Grid size used by [`avs_convert`]
+const DEFAULT_GRID: (u32, u32) = (80, 24);
+
+/// Run `f`, turning a panic into [`AVS_ERR_PANIC`]
+fn guard(f: impl FnOnce() -> i32) -> i32 {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(AVS_ERR_PANIC)
+}
+
+/// Render a frame as text, with 24-bit ANSI colors when color is enabled
+fn render_text(converter: &mut AsciiConverter, frame: &[u8], width: u32, height: u32) -> String {
+    to_ansi_string(&converter.convert_rgb_frame_direct(frame, width, height))
+}
+
+/// Convert a frame and copy the NUL-terminated text into `out_buf`
+///
+/// # Safety
+///
+/// `frame` must point to `len` readable bytes and `out_buf` to `out_cap` writable bytes.
+unsafe fn convert_into(
+    converter: &mut AsciiConverter,
+    frame: *const u8,
+    len: usize,
+    width: u32,
+    height: u32,
+    out_buf: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    if frame.is_null() || out_buf.is_null() {
+        return AVS_ERR_NULL;
+    }
+    if width == 0 || height == 0 || (width as usize * height as usize * 3) != len {
+        return AVS_ERR_INVALID_FRAME;
+    }
+
+    // SAFETY: the caller guarantees `frame` points to `len` readable bytes
+    let frame = unsafe { slice::from_raw_parts(frame, len) };
+    let text = render_text(converter, frame, width, height);
+    if text.len() + 1 > out_cap || text.len() > i32::MAX as usize {
+        return AVS_ERR_BUFFER_TOO_SMALL;
+    }
+
+    // SAFETY: the caller guarantees `out_buf` has `out_cap` writable bytes, checked above
+    unsafe {
+        ptr::copy_nonoverlapping(text.as_ptr(), out_buf, text.len());
+        *out_buf.add(text.len()) = 0;
+    }
+    text.len() as i32
+}
+
+/// Create a converter producing a `width` x `height` grid, or null if either is zero
+#[unsafe(no_mangle)]
+pub extern "C" fn avs_converter_new(width: u32, height: u32) -> *mut AsciiConverter {
+    if width == 0 || height == 0 {
+        return ptr::null_mut();
+    }
+    catch_unwind(|| Box::into_raw(Box::new(AsciiConverter::new_dense(width, height))))
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Free a converter created by [`avs_converter_new`]; null is ignored
+///
+/// # Safety
+///
+/// `converter` must be null or a pointer from [`avs_converter_new`] that wasn't freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn avs_converter_free(converter: *mut AsciiConverter) {
+    if !converter.is_null() {
+        // SAFETY: the pointer came from `Box::into_raw` and is freed only once
+        drop(unsafe { Box::from_raw(converter) });
+    }
+}
+
+/// Select the character set: 0 Dense, 1 Simple, 2 Blocks, 3 Minimal
+///
+/// # Safety
+///
+/// `converter` must be null or a live pointer from [`avs_converter_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn avs_converter_set_character_set(
+    converter: *mut AsciiConverter,
+    character_set: i32,
+) -> i32 {
+    // SAFETY: the caller guarantees the pointer is null or live
+    let Some(converter) = (unsafe { converter.as_mut() }) else {
+        return AVS_ERR_NULL;
+    };
+    let character_set = match character_set {
+        0 => CharacterSet::Dense,
+        1 => CharacterSet::Simple,
+        2 => CharacterSet::Blocks,
+        3 => CharacterSet::Minimal,
+        _ => return AVS_ERR_INVALID_ARGUMENT,
+    };
+    converter.set_character_set(character_set);
+    0
+}
+
+/// Set the scale factor, clamped to `[0.1, 2.0]`
+///
+/// # Safety
+///
+/// `converter` must be null or a live pointer from [`avs_converter_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn avs_converter_set_scale(
+    converter: *mut AsciiConverter,
+    scale: f32,
+) -> i32 {
+    // SAFETY: the caller guarantees the pointer is null or live
+    let Some(converter) = (unsafe { converter.as_mut() }) else {
+        return AVS_ERR_NULL;
+    };
+    if !scale.is_finite() {
+        return AVS_ERR_INVALID_ARGUMENT;
+    }
+    converter.set_scale_factor(scale);
+    0
+}
+
+/// Enable (non-zero) or disable (zero) 24-bit ANSI color escapes in the output
+///
+/// # Safety
+///
+/// `converter` must be null or a live pointer from [`avs_converter_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn avs_converter_set_color(
+    converter: *mut AsciiConverter,
+    enabled: i32,
+) -> i32 {
+    // SAFETY: the caller guarantees the pointer is null or live
+    let Some(converter) = (unsafe { converter.as_mut() }) else {
+        return AVS_ERR_NULL;
+    };
+    converter.set_color_enabled(enabled != 0);
+    0
+}
+
+/// Convert an RGB frame with a converter's settings
+///
+/// # Safety
+///
+/// `converter` must be null or a live pointer from [`avs_converter_new`], `frame` must point to
+/// `len` readable bytes and `out_buf` to `out_cap` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn avs_converter_convert(
+    converter: *mut AsciiConverter,
+    frame: *const u8,
+    len: usize,
+    width: u32,
+    height: u32,
+    out_buf: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    // SAFETY: the caller guarantees the pointer is null or live
+    let Some(converter) = (unsafe { converter.as_mut() }) else {
+        return AVS_ERR_NULL;
+    };
+    // SAFETY: forwarded from the caller's guarantees
+    guard(|| unsafe { convert_into(converter, frame, len, width, height, out_buf, out_cap) })
+}
+
+/// Convert an RGB frame to an 80x24 grid with the Dense set and no color
+///
+/// # Safety
+///
+/// `frame` must point to `len` readable bytes and `out_buf` to `out_cap` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn avs_convert(
+    frame: *const u8,
+    len: usize,
+    width: u32,
+    height: u32,
+    out_buf: *mut u8,
+    out_cap: usize,
+) -> i32 {
+    guard(|| {
+        let mut converter = AsciiConverter::new_dense(DEFAULT_GRID.0, DEFAULT_GRID.1);
+        // SAFETY: forwarded from the caller's guarantees
+        unsafe { convert_into(&mut converter, frame, len, width, height, out_buf, out_cap) }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let converter = avs_converter_new(4, 2);
+        assert!(!converter.is_null());
+        let frame = [255u8; 8 * 4 * 3];
+        let mut out = vec![0u8; 64];
+
+        unsafe {
+            assert_eq!(avs_converter_set_character_set(converter, 1), 0);
+            let written = avs_converter_convert(
+                converter,
+                frame.as_ptr(),
+                frame.len(),
+                8,
+                4,
+                out.as_mut_ptr(),
+                out.len(),
+            );
+            assert_eq!(written, 9);
+            let text = CStr::from_bytes_until_nul(&out).unwrap().to_str().unwrap();
+            assert_eq!(text, "@@@@\n@@@@");
+
+            // Too small for the text plus its NUL terminator
+            let written = avs_converter_convert(
+                converter,
+                frame.as_ptr(),
+                frame.len(),
+                8,
+                4,
+                out.as_mut_ptr(),
+                9,
+            );
+            assert_eq!(written, AVS_ERR_BUFFER_TOO_SMALL);
+
+            assert_eq!(
+                avs_converter_set_character_set(converter, 7),
+                AVS_ERR_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                avs_converter_convert(converter, frame.as_ptr(), 5, 8, 4, out.as_mut_ptr(), 64),
+                AVS_ERR_INVALID_FRAME
+            );
+            avs_converter_free(converter);
+
+            assert_eq!(
+                avs_convert(ptr::null(), 0, 1, 1, out.as_mut_ptr(), out.len()),
+                AVS_ERR_NULL
+            );
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use crate::ascii::ColoredChar;
+
+/// A converted grid shared without copying; a new frame replaces the whole `Arc`, so anyone
+/// holding the old one still sees a complete frame
+pub type SharedGrid = Arc<Vec<Vec<ColoredChar>>>;
+
+/// The latest source frame and its converted grid
+#[derive(Debug, Clone, Default)]
+pub struct FrameSnapshot {
+    /// Raw RGB data, empty when no source is running or the frame was converted straight
+    /// from camera luma
+    pub rgb: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub grid: SharedGrid,
+    /// Incremented on every publish so readers can tell new frames apart
+    pub sequence: u64,
+}
+
+/// Write side of the shared frame, owned by exactly one component
+#[derive(Debug)]
+pub struct FrameWriter(Arc<RwLock<FrameSnapshot>>);
+
+/// Read-only handle to the shared frame, handed to every component
+#[derive(Debug, Clone)]
+pub struct FrameReader(Arc<RwLock<FrameSnapshot>>);
+
+/// Create the single writer and a reader that can be cloned freely
+pub fn shared_frame() -> (FrameWriter, FrameReader) {
+    let frame = Arc::new(RwLock::new(FrameSnapshot::default()));
+    (FrameWriter(frame.clone()), FrameReader(frame))
+}
+
+impl FrameWriter {
+    /// Replace the shared frame, reusing its buffer and sharing the grid
+    pub fn publish(&self, rgb: &[u8], width: u32, height: u32, grid: &SharedGrid) {
+        let mut frame = self.0.write().unwrap_or_else(|e| e.into_inner());
+        frame.rgb.clear();
+        frame.rgb.extend_from_slice(rgb);
+        frame.width = width;
+        frame.height = height;
+        frame.grid = grid.clone();
+        frame.sequence += 1;
+    }
+
+    /// Drop the frame when the source stops, keeping the sequence counting up
+    pub fn clear(&self) {
+        let mut frame = self.0.write().unwrap_or_else(|e| e.into_inner());
+        frame.rgb.clear();
+        frame.width = 0;
+        frame.height = 0;
+        frame.grid = SharedGrid::default();
+        frame.sequence += 1;
+    }
+}
+
+impl FrameReader {
+    /// Lock the frame for reading; keep the guard short-lived so the writer isn't blocked
+    pub fn read(&self) -> RwLockReadGuard<'_, FrameSnapshot> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.read().sequence
+    }
+}
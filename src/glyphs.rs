@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use lazy_static::lazy_static;
+use tracing::debug;
+
+use crate::ascii::glyph_coverage;
+
+/// Size of a glyph cell in the bundled bitmap font
+pub const GLYPH_WIDTH: usize = 12;
+pub const GLYPH_HEIGHT: usize = 24;
+
+/// DejaVu Sans Mono rasterized into 12x24 one-bit cells (4x4 supersampled, half-covered
+/// pixels set), the cell spanning the advance width and ascender to descender.
+///
+/// Each glyph is 24 rows of 3 hex digits, the top bit of 12 being the leftmost pixel. Covers
+/// printable ASCII and the block elements used by the built-in ramps.
+///
+/// The font's license is in `LICENSES/DejaVu.txt`.
+#[rustfmt::skip]
+const GLYPH_BITMAPS: &[(char, &str)] = &[
+    (' ', "000000000000000000000000000000000000000000000000000000000000000000000000"),
+    ('!', "000000000000060060060060060060060060060060000000060060060000000000000000"),
+    ('"', "000000000000198198198198198198000000000000000000000000000000000000000000"),
+    ('#', "00000000000004404404c0cc3ff7ff088198198ffcffe330330320220000000000000000"),
+    ('$', "0000000000000200201fc3ac3203203203e00f803c0260260263ac1f8020020020000000"),
+    ('%', "000000000000000780440c60c606c238e0301c071e02206306303601c000000000000000"),
+    ('&', "0000000000001f01f83003001001801c03c2663433c3a41e60e71e3fe040000000000000"),
+    ('\'', "000000000000060060060060060060000000000000000000000000000000000000000000"),
+    ('(', "0000000000100300300600600600c00c00c00c00c00c00c0060060060030030010000000"),
+    (')', "0000000000800c00c00600600600300300300300300300300600600600c00c0080000000"),
+    ('*', "00000000000006006036c1f80601f836c060060000000000000000000000000000000000"),
+    ('+', "0000000000000000000000600600600600607fe7fe060060060060000000000000000000"),
+    (',', "0000000000000000000000000000000000000000000000000600600600600c00c0000000"),
+    ('-', "0000000000000000000000000000000000000001f8000000000000000000000000000000"),
+    ('.', "000000000000000000000000000000000000000000000000060060060000000000000000"),
+    ('/', "00000000000000c00c0080180180300300600600c00c0180180100300300600000000000"),
+    ('0', "0000000000000f01f830c30c70e60666666666660670e30c30c39c1f8000000000000000"),
+    ('1', "0000000000000f03f00300300300300300300300300300300301fc1fe000000000000000"),
+    ('2', "0000000000003f07f800c00c00c00c01c0180300600c01803003fc7fc000000000000000"),
+    ('3', "0000000000003f03f800c00c00c00c0f80f801c00c00e00e00c71c7f8040000000000000"),
+    ('4', "0000000000000180380780580d81981983182186187fe7fe018018018000000000000000"),
+    ('5', "0000000000003f83f83003003003e03f801c00c00c00e00c00c73c7f80c0000000000000"),
+    ('6', "0000000000000fc1fc3803002006707fc70c70e70670630630c39c1f8020000000000000"),
+    ('7', "0000000000007fe7fc00c00c0180180100300300600600600c00c01c0000000000000000"),
+    ('8', "0000000000001f83fc30c30c30c30c1f81f830c30c60660670e39c1f8060000000000000"),
+    ('9', "0000000000001f03f830c60c60c60e60e30e3fe1f600400c00c3383f0040000000000000"),
+    (':', "000000000000000000000000060060060060000000000000060060060000000000000000"),
+    (';', "0000000000000000000000000600600600600000000000000600600600600c00c0000000"),
+    ('<', "00000000000000000000000000601e0f83c07007801f003c00e000000000000000000000"),
+    ('=', "0000000000000000000000000000007fe7fe0000007fe7fe000000000000000000000000"),
+    ('>', "0000000000000000000000006007801f003c00e01e0f83c0700000000000000000000000"),
+    ('?', "0000000000001f83fc20c00c00c018030060060060060000040060060000000000000000"),
+    ('@', "0000000000000000781fc30660261b47fcc7cc3cc3cc3cc347f43a6003001800fc000000"),
+    ('A', "0000000000000600f00f00f019819819819830c3fc3fc70e606606606000000000000000"),
+    ('B', "0000000000007f07fc70c70e70e70c7fc7f870c7067067067067fc7f8000000000000000"),
+    ('C', "0000000000000fc1fe3803003007007007007007003003003801c60fc010000000000000"),
+    ('D', "0000000000007e07f861c60c60c60e60660660660660e60c61c7f87f0000000000000000"),
+    ('E', "0000000000003fe3fc3003003003003fc3fc3003003003003003fc3fe000000000000000"),
+    ('F', "0000000000003fe3fe3003003003003fc3fc300300300300300300300000000000000000"),
+    ('G', "0000000000000fc1fc30030060060060061c61e60660630630618e0fc020000000000000"),
+    ('H', "0000000000006066066066066066067fe7fe606606606606606606606000000000000000"),
+    ('I', "0000000000003fc3fc0600600600600600600600600600600603fc3fc000000000000000"),
+    ('J', "0000000000000f80f80180180180180180180180180180184186387f0080000000000000"),
+    ('K', "00000000000060660c61c6386706e07c07e077063061861c60c60e607000000000000000"),
+    ('L', "0000000000003003003003003003003003003003003003003003fe3fe000000000000000"),
+    ('M', "00000000000070e70e70e79e6966966f66e6666666606606606606606000000000000000"),
+    ('N', "0000000000007067067867866c66c664666662663663661e61e61e60e000000000000000"),
+    ('O', "0000000000000f03fc30c30c60660660660660660660630c30c39c1f8000000000000000"),
+    ('P', "0000000000003f83fc30e30630630630e3fc3f8300300300300300300000000000000000"),
+    ('Q', "0000000000000f03fc30c30c60660660660660660660630c30c39c1f803801c008000000"),
+    ('R', "0000000000007f07f860c60c60c60c60c7f87f061860c60c606606603000000000000000"),
+    ('S', "0000000000001f83fc3006006003003c01f803c00c00600600e71c3f8040000000000000"),
+    ('T', "000000000000fff7fe060060060060060060060060060060060060060000000000000000"),
+    ('U', "00000000000070e70e70e70e70e70e70e70e70e70e70e70e30c39c1f8000000000000000"),
+    ('V', "00000000000060660660660630c30c30c1981981981980f00f00f0060000000000000000"),
+    ('W', "000000000000c03c03c03c036626666f66f66f669669639c39c30c30c000000000000000"),
+    ('X', "00000000000060630630c1981980f00700600f00d819838c30c606606000000000000000"),
+    ('Y', "00000000000060660630c30c1981980f00f0060060060060060060060000000000000000"),
+    ('Z', "0000000000003fe3fe00e00c0180180300600600c01c01803003fe7ff000000000000000"),
+    ('[', "0000000000700f80c00c00c00c00c00c00c00c00c00c00c00c00c00c00c00f0078000000"),
+    ('\\', "0000000000006003003003001801800c00c006006003003001001801800c00c000000000"),
+    (']', "0000000000e01f00300300300300300300300300300300300300300300300f01e0000000"),
+    ('^', "0000000000000600f019830c30c606000000000000000000000000000000000000000000"),
+    ('_', "000000000000000000000000000000000000000000000000000000000000000000000fff"),
+    ('`', "0000000001800c0060020000000000000000000000000000000000000000000000000000"),
+    ('a', "0000000000000000000000403f831c00c00c1fc3fc20c60c60c31c3fc000000000000000"),
+    ('b', "0000000002003003003003203f839c30c30630630630630630c39c3f8020000000000000"),
+    ('c', "0000000000000000000000100fc1c43803003003003003003801c40fc010000000000000"),
+    ('d', "00000000000400c00c00c04c1fc39c30c60c60c60c60c60c30c39c1fc040000000000000"),
+    ('e', "0000000000000000000000201f838c3046067fe7fe6006003003861fc020000000000000"),
+    ('f', "00000000001c03c0600600603fc060060060060060060060060060060000000000000000"),
+    ('g', "0000000000000000000000401fc39c30c60c60c60c60c60c30c39c1fc00c00c01c3f80e0"),
+    ('h', "0000000002003003003003003f839c30c30c30c30c30c30c30c30c30c000000000000000"),
+    ('i', "0000000000200600600000003e00600600600600600600600600603fe000000000000000"),
+    ('j', "0000000000200300300000001f00300300300300300300300300300300300300603e0380"),
+    ('k', "00000000010030030030030030c3183303603e03f033031830c30e306000000000000000"),
+    ('l', "0000000003c03c00c00c00c00c00c00c00c00c00c00c00c006007c03c000000000000000"),
+    ('m', "0000000000000000000000007dc666666666666666666666666666666000000000000000"),
+    ('n', "0000000000000000000000003f839c30c30c30c30c30c30c30c30c30c000000000000000"),
+    ('o', "0000000000000000000000001f839c30c60660660660660630c39c1f8000000000000000"),
+    ('p', "0000000000000000000000203f839c30c30630630630630630c39c3f8320300300300200"),
+    ('q', "0000000000000000000000001fc39c30c60c60c60c60c60c30c39c1fc04c00c00c00c004"),
+    ('r', "0000000000000000000000001be1f21c01c0180180180180180180180000000000000000"),
+    ('s', "0000000000000000000000201f83883003003c01f801c00c00c31c3f8040000000000000"),
+    ('t', "0000000000000000c00c00c07fc0c00c00c00c00c00c00c00c006007c000000000000000"),
+    ('u', "00000000000000000000000030c30c30c30c30c30c30c30c30c39c1fc040000000000000"),
+    ('v', "00000000000000000000000060620430c30c1081981980900f00f0060000000000000000"),
+    ('w', "000000000000000000000000c03c034026666666666f629439c39c30c000000000000000"),
+    ('x', "00000000000000000000000030c30c1980f00f00600f019819830c606000000000000000"),
+    ('y', "00000000000000000000000060630630c30c1881980980d00f00700600600600c0380200"),
+    ('z', "0000000000000000000000003fc00c0180180300600c01c01803003fc000000000000000"),
+    ('{', "00000000000803c0600600600600600600603c03c006006006006006006006003c008000"),
+    ('|', "000000000000060060060060060060060060060060060060060060060060060060060060"),
+    ('}', "0000000001003c006006006006006006006003c03c0600600600600600600603c0100000"),
+    ('~', "0000000000000000000000000000000001807fe43e000000000000000000000000000000"),
+    ('░', "c30c30000186186000820c30820104186104820c30820104186104000c30c30000186186"),
+    ('▒', "ccccccfff333333cccccc333333333cccccc333333fffcccccc333333ccccccccc333333"),
+    ('▓', "7df7dffffefbefbfff7df7dffffefbefbffffff7df7dffffefbefbfff7df7dffffefbefb"),
+    ('█', "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+    ('▏', "800800800800800800800800800800800800800800800800800800800800800800800800"),
+    ('▎', "e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00e00"),
+    ('▍', "f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80f80"),
+    ('▌', "fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0fc0"),
+    ('▋', "ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0ff0"),
+    ('▊', "ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8ff8"),
+    ('▉', "ffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffeffe"),
+    ('▀', "ffffffffffffffffffffffffffffffffffff000000000000000000000000000000000000"),
+    ('▄', "000000000000000000000000000000000000ffffffffffffffffffffffffffffffffffff"),
+    ('▐', "03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f03f"),
+];
+
+lazy_static! {
+    static ref BITMAPS: HashMap<char, [u16; GLYPH_HEIGHT]> = GLYPH_BITMAPS
+        .iter()
+        .map(|(ch, hex)| {
+            let mut rows = [0u16; GLYPH_HEIGHT];
+            for (row, digits) in rows.iter_mut().zip(hex.as_bytes().chunks(3)) {
+                let digits = std::str::from_utf8(digits).unwrap_or_default();
+                *row = u16::from_str_radix(digits, 16).unwrap_or_default();
+            }
+            (*ch, rows)
+        })
+        .collect();
+    /// Calibrations already measured, keyed by the ramp as given
+    static ref CALIBRATIONS: Mutex<HashMap<Vec<char>, Calibration>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Ramp glyphs densest first, each with its coverage
+pub type Calibration = Arc<[(char, f32)]>;
+
+/// A glyph's pixels in the bundled font, `None` when the font doesn't have it
+pub fn rasterize(ch: char) -> Option<[u16; GLYPH_HEIGHT]> {
+    BITMAPS.get(&ch).copied()
+}
+
+/// Fraction of the cell a glyph fills in the bundled font
+pub fn measured_coverage(ch: char) -> Option<f32> {
+    let inked: u32 = rasterize(ch)?.iter().map(|row| row.count_ones()).sum();
+    Some(inked as f32 / (GLYPH_WIDTH * GLYPH_HEIGHT) as f32)
+}
+
+/// Order a ramp from densest to lightest by measured coverage, paired with that coverage
+///
+/// Glyphs missing from the bundled font keep the estimate from [`glyph_coverage`]. Results
+/// are cached per ramp, so recalibrating is free.
+pub fn calibrate(ramp: &[char]) -> Calibration {
+    let mut cache = CALIBRATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(calibrated) = cache.get(ramp) {
+        return calibrated.clone();
+    }
+    let mut calibrated: Vec<(char, f32)> = ramp
+        .iter()
+        .map(|ch| {
+            (
+                *ch,
+                measured_coverage(*ch).unwrap_or_else(|| glyph_coverage(*ch)),
+            )
+        })
+        .collect();
+    // Stable, so glyphs with equal coverage keep the order they were given in
+    calibrated.sort_by(|a, b| b.1.total_cmp(&a.1));
+    debug!("Calibrated ramp {:?}", calibrated);
+    let calibrated: Calibration = calibrated.into();
+    cache.insert(ramp.to_vec(), calibrated.clone());
+    calibrated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibrate_reorders_shuffled_ramp() {
+        let shuffled: Vec<char> = ".@:# +*".chars().collect();
+        let calibrated = calibrate(&shuffled);
+        let order: String = calibrated.iter().map(|(ch, _)| *ch).collect();
+        assert_eq!(order, "@#+*:. ");
+        assert!(calibrated.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        // Block elements measure close to their geometric coverage
+        assert_eq!(measured_coverage('█'), Some(1.0));
+        assert_eq!(measured_coverage('▌'), Some(0.5));
+    }
+}
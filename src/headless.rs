@@ -0,0 +1,319 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use color_eyre::{Result, eyre::eyre};
+use image::DynamicImage;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::{
+    action::Action,
+    ascii::{AsciiConverter, ColoredChar, to_ansi_string},
+    camera::CameraCapture,
+    cli::Cli,
+    config::Config,
+    remote,
+};
+
+/// Grid size when neither `export.width`/`export.height` nor the command line set one
+const DEFAULT_GRID: (u32, u32) = (80, 24);
+
+/// One converted frame, written as a single line of JSON
+#[derive(Debug, Serialize)]
+pub struct JsonFrame<'a> {
+    pub w: u32,
+    pub h: u32,
+    pub cells: &'a [Vec<ColoredChar>],
+}
+
+impl<'a> JsonFrame<'a> {
+    pub fn new(cells: &'a [Vec<ColoredChar>]) -> Self {
+        Self {
+            w: cells.first().map_or(0, Vec::len) as u32,
+            h: cells.len() as u32,
+            cells,
+        }
+    }
+}
+
+/// Converter for headless output: config settings at the export grid size, always in color
+/// in the configured color mode
+fn headless_converter(config: &Config) -> AsciiConverter {
+    let width = config.export.width.unwrap_or(DEFAULT_GRID.0);
+    let height = config.export.height.unwrap_or(DEFAULT_GRID.1);
+    let mut converter = AsciiConverter::new_dense(width, height);
+    converter.set_character_set(config.ascii.character_set);
+    converter.set_tint(config.ascii.tint());
+    converter.set_tone_curve(config.ascii.tone_curve);
+    converter.set_scale_mode(config.ascii.scale_mode);
+    converter.set_rotation(config.ascii.rotation);
+    converter.set_cell_aspect(config.ascii.cell_aspect);
+    converter.set_detail_level(config.ascii.detail_level);
+    converter.set_gamma(config.ascii.gamma);
+    converter.set_contrast(config.ascii.contrast);
+    converter.set_brightness_offset(config.ascii.brightness_offset);
+    converter.set_linearize(config.ascii.linearize);
+    converter.set_invert(config.ascii.invert);
+    converter.set_edge_detect(config.ascii.edge_detect);
+    converter.set_smoothing(config.ascii.smoothing);
+    converter.set_grain_amount(config.ascii.grain_amount);
+    converter.set_grain_animated(config.ascii.grain_animated);
+    converter.set_ascii_only(config.ascii.ascii_only);
+    converter.set_custom_ramp(config.ascii.custom_ramp.as_deref());
+    if config.ascii.calibrate_ramp {
+        converter.calibrate_ramp();
+    }
+    converter.set_index_rounding(config.ascii.index_rounding);
+    converter.set_cell_mode(config.ascii.cell_mode);
+    converter.set_sextants_supported(config.ascii.sextants);
+    converter.set_filter(config.ascii.filter);
+    converter.set_gaussian_sigma(config.ascii.gaussian_sigma);
+    converter.set_perceptual_ramp(config.ascii.perceptual_ramp);
+    converter.set_auto_contrast(config.ascii.auto_contrast);
+    converter.set_clahe_tiles(config.ascii.clahe_tiles);
+    converter.set_clahe_clip_limit(config.ascii.clahe_clip_limit);
+    converter.set_color_mode(config.ascii.color_mode);
+    converter.set_color_enabled(true);
+    converter
+}
+
+/// Write each converted camera frame as newline-delimited JSON until Ctrl-C or the
+/// reader goes away
+pub async fn run_json(args: &Cli) -> Result<()> {
+    let mut config = Config::new()?;
+    args.apply_overrides(&mut config);
+    let mut out: Box<dyn Write> = match &args.json_output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let converter = headless_converter(&config);
+    info!(
+        "Streaming JSON frames to {}",
+        args.json_output
+            .as_deref()
+            .map_or("stdout".into(), Path::to_string_lossy)
+    );
+    convert_camera_frames(&config, converter, |grid| {
+        serde_json::to_writer(&mut out, &JsonFrame::new(grid)).map_err(io::Error::from)?;
+        out.write_all(b"\n")?;
+        out.flush()
+    })
+    .await
+}
+
+/// Print each converted camera frame to stdout, clearing the screen and homing the cursor
+/// before each one, until Ctrl-C or the reader goes away
+///
+/// Character set, color and scale come from the config and command line like in the TUI,
+/// and the grid fills the terminal unless the export size is set.
+pub async fn run_headless(args: &Cli) -> Result<()> {
+    let mut config = Config::new()?;
+    args.apply_overrides(&mut config);
+    let mut converter = headless_converter(&config);
+    converter.set_color_enabled(config.ascii.color);
+    converter.set_scale_factor(config.ascii.scale);
+    // Keep a line for the prompt, like a one-off `--url` print
+    if let (None, None, Ok((width, height))) = (
+        config.export.width,
+        config.export.height,
+        crossterm::terminal::size(),
+    ) {
+        converter.resize(width as u32, height.saturating_sub(1).max(1) as u32);
+    }
+    info!("Printing frames to stdout");
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let result = convert_camera_frames(&config, converter, |grid| {
+        write!(out, "\x1b[2J\x1b[H{}", to_ansi_string(grid))?;
+        out.flush()
+    })
+    .await;
+    // Leave the prompt below the last frame
+    let _ = writeln!(out);
+    result
+}
+
+/// Open the camera and hand every converted frame to `write`, at most at the camera frame
+/// rate, until Ctrl-C or the output is closed
+async fn convert_camera_frames(
+    config: &Config,
+    mut converter: AsciiConverter,
+    mut write: impl FnMut(&[Vec<ColoredChar>]) -> io::Result<()>,
+) -> Result<()> {
+    let (frame_tx, mut frame_rx) = mpsc::unbounded_channel();
+    let mut camera = CameraCapture::new(config.camera.backend);
+    camera.set_target_fps(config.camera.fps);
+    camera.initialize(
+        config.camera.default_camera_index,
+        config.camera.width,
+        config.camera.height,
+        config.camera.fps.round() as u32,
+        config.camera.resolution_request,
+        frame_tx,
+    )?;
+    camera.start()?;
+
+    // Never faster than the camera delivers frames
+    let mut interval =
+        tokio::time::interval(Duration::from_secs_f64(1.0 / config.camera.fps.max(1.0)));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let result = loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break Ok(()),
+            _ = interval.tick() => {}
+        }
+        if let Err(e) = camera.capture_frame() {
+            if camera.is_disconnected() {
+                break Err(eyre!("Camera disconnected"));
+            }
+            warn!("Failed to capture frame: {}", e);
+            continue;
+        }
+        // Only the newest frame matters if several queued up
+        let mut latest = None;
+        while let Ok(action) = frame_rx.try_recv() {
+            if let Action::CameraFrame(data, width, height, _) = action {
+                latest = Some((data, width, height));
+            }
+        }
+        let Some((data, width, height)) = latest else {
+            continue;
+        };
+        let mut grid = converter.convert_rgb_frame_direct(&data, width, height);
+        converter.quantize_colors(&mut grid);
+        match write(&grid) {
+            Ok(()) => {}
+            // The consumer closed the pipe, which is a normal way to stop
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                debug!("Output closed");
+                break Ok(());
+            }
+            Err(e) => break Err(e.into()),
+        }
+    };
+    camera.stop();
+    result
+}
+
+/// An image converted in the configured color mode
+fn image_grid(converter: &AsciiConverter, image: &DynamicImage) -> Vec<Vec<ColoredChar>> {
+    let mut grid = converter.convert_image_colored(image);
+    converter.quantize_colors(&mut grid);
+    grid
+}
+
+/// Print the image at `url` as colored ASCII art, re-fetching and redrawing it in place
+/// every `remote.refresh_secs` if set
+pub async fn run_url(args: &Cli, url: &str) -> Result<()> {
+    let mut config = Config::new()?;
+    args.apply_overrides(&mut config);
+    let mut converter = headless_converter(&config);
+    // Fill the terminal unless the export size was set, keeping a line for the prompt
+    if let (None, None, Ok((width, height))) = (
+        config.export.width,
+        config.export.height,
+        crossterm::terminal::size(),
+    ) {
+        converter.resize(width as u32, height.saturating_sub(1).max(1) as u32);
+    }
+
+    let Some(refresh) = config
+        .remote
+        .refresh_secs
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+    else {
+        let image = remote::fetch_image(url).await?;
+        println!("{}", to_ansi_string(&image_grid(&converter, &image)));
+        return Ok(());
+    };
+
+    info!("Fetching {} every {}s", url, refresh);
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(refresh));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut first = true;
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            _ = interval.tick() => {}
+        }
+        // After the first image, a failed fetch keeps the last one up and tries again next time
+        let image = match remote::fetch_image(url).await {
+            Ok(image) => image,
+            Err(e) if first => return Err(e),
+            Err(e) => {
+                warn!("Failed to refresh {}: {}", url, e);
+                continue;
+            }
+        };
+        let text = to_ansi_string(&image_grid(&converter, &image));
+        let clear = if first { "\x1b[2J" } else { "" };
+        first = false;
+        let mut out = io::stdout().lock();
+        write!(out, "{clear}\x1b[H{text}")?;
+        out.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+    use ratatui::style::{Color, Style};
+
+    use super::*;
+    use crate::ascii::ColorMode;
+
+    #[test]
+    fn test_json_frame_format() {
+        let cells = vec![vec![
+            ColoredChar {
+                ch: '@',
+                style: Style::default().fg(Color::Rgb(10, 20, 30)),
+            },
+            ColoredChar {
+                ch: '.',
+                style: Style::default(),
+            },
+            ColoredChar::letterbox(),
+        ]];
+        let json = serde_json::to_string(&JsonFrame::new(&cells)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"w":3,"h":1,"cells":[[{"ch":"@","fg":[10,20,30]},{"ch":"."},{"ch":" ","bg":[0,0,0]}]]}"#
+        );
+    }
+
+    #[test]
+    fn test_image_output_follows_the_color_mode() {
+        let mut config = Config::default();
+        config.ascii.color_mode = ColorMode::Ansi16;
+        let converter = headless_converter(&config);
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 16, Rgb([200, 30, 30])));
+        let grid = image_grid(&converter, &image);
+        let colors: Vec<Color> = grid
+            .iter()
+            .flatten()
+            .filter_map(|cell| cell.style.fg)
+            .collect();
+        assert!(!colors.is_empty());
+        assert!(colors.iter().all(|&color| color == Color::Red));
+
+        // Printed and serialized, the quantized colors survive
+        assert!(to_ansi_string(&grid).starts_with("\x1b[31m"));
+        let json = serde_json::to_string(&JsonFrame::new(&grid)).unwrap();
+        assert!(json.contains(r#""fg":[205,0,0]"#));
+
+        config.ascii.color_mode = ColorMode::Ansi256;
+        let grid = image_grid(&headless_converter(&config), &image);
+        assert_eq!(grid[0][0].style.fg, Some(Color::Indexed(160)));
+        assert!(to_ansi_string(&grid).starts_with("\x1b[38;5;160m"));
+        let json = serde_json::to_string(&JsonFrame::new(&grid)).unwrap();
+        assert!(json.contains(r#""fg":[215,0,0]"#));
+    }
+}
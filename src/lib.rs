@@ -0,0 +1,44 @@
+//! Real-time camera to ASCII art conversion.
+//!
+//! The `ascii-vision` binary is a thin wrapper around [`app::App`]. The conversion core is also
+//! usable on its own, from Rust through [`ascii::AsciiConverter`] or from other languages through
+//! the C API in [`ffi`].
+
+pub mod action;
+pub mod app;
+pub mod ascii;
+pub mod blocks;
+pub mod camera;
+pub mod chord;
+pub mod cli;
+pub mod components;
+pub mod config;
+pub mod contrast;
+pub mod demo;
+pub mod edges;
+pub mod effects;
+pub mod errors;
+pub mod ffi;
+pub mod frame;
+pub mod glyphs;
+pub mod headless;
+pub mod logging;
+pub mod power;
+pub mod preset;
+pub mod profiles;
+pub mod quality;
+pub mod remote;
+pub mod render_image;
+pub mod screen;
+pub mod sink;
+pub mod status;
+pub mod stream;
+pub mod terminal;
+pub mod test_pattern;
+pub mod threads;
+pub mod tui;
+pub mod value_entry;
+pub mod video;
+pub mod worker;
+pub mod yuyv;
+pub mod zoom;
@@ -1,35 +1,115 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use color_eyre::Result;
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+};
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tracing_subscriber::{EnvFilter, Layer, fmt, layer::Context, prelude::*};
 
 use crate::config;
 
 lazy_static::lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOG_LEVEL", config::PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    /// Recent log lines for the in-app log viewer
+    pub static ref LOG_BUFFER: Mutex<VecDeque<String>> =
+        Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY));
 }
 
-pub fn init() -> Result<()> {
-    let directory = config::get_data_dir();
-    std::fs::create_dir_all(directory.clone())?;
-    let log_path = directory.join(LOG_FILE.clone());
-    let log_file = std::fs::File::create(log_path)?;
+/// Number of log lines kept for the in-app log viewer
+pub const LOG_BUFFER_CAPACITY: usize = 500;
+
+/// Copy of the buffered log lines, oldest first
+pub fn recent_logs() -> Vec<String> {
+    LOG_BUFFER
+        .lock()
+        .map(|buffer| buffer.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Collects an event's message and fields into a single line
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// Tracing layer that keeps the most recent events in `LOG_BUFFER`
+struct RingBufferLayer;
+
+impl<S: Subscriber> Layer<S> for RingBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+            % 86_400;
+        let line = format!(
+            "{:02}:{:02}:{:02} {:>5} {}: {}{}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            event.metadata().level(),
+            event.metadata().target(),
+            visitor.message,
+            visitor.fields
+        );
+
+        if let Ok(mut buffer) = LOG_BUFFER.lock() {
+            if buffer.len() == LOG_BUFFER_CAPACITY {
+                buffer.pop_front();
+            }
+            buffer.push_back(line);
+        }
+    }
+}
+
+fn env_filter() -> Result<EnvFilter> {
     let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
     // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
     // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
     // errors, then this will return an error.
-    let env_filter = env_filter
+    Ok(env_filter
         .try_from_env()
-        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?;
+        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?)
+}
+
+pub fn init() -> Result<()> {
+    let directory = config::get_data_dir();
+    std::fs::create_dir_all(directory.clone())?;
+    let log_path = directory.join(LOG_FILE.clone());
+    let log_file = std::fs::File::create(log_path)?;
     let file_subscriber = fmt::layer()
         .with_file(true)
         .with_line_number(true)
         .with_writer(log_file)
         .with_target(false)
         .with_ansi(false)
-        .with_filter(env_filter);
+        .with_filter(env_filter()?);
+    let buffer_subscriber = RingBufferLayer.with_filter(env_filter()?);
     tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(buffer_subscriber)
         .with(ErrorLayer::default())
         .try_init()?;
     Ok(())
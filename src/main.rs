@@ -1,27 +1,23 @@
+use ascii_vision::{app::App, cli::Cli};
 use clap::Parser;
-use cli::Cli;
 use color_eyre::Result;
 
-use crate::app::App;
-
-mod action;
-mod app;
-mod ascii;
-mod camera;
-mod cli;
-mod components;
-mod config;
-mod errors;
-mod logging;
-mod tui;
-
 #[tokio::main]
 async fn main() -> Result<()> {
-    crate::errors::init()?;
-    crate::logging::init()?;
+    ascii_vision::errors::init()?;
+    ascii_vision::logging::init()?;
 
     let args = Cli::parse();
-    let mut app = App::new(args.tick_rate, args.frame_rate)?;
+    if let Some(url) = &args.url {
+        return ascii_vision::headless::run_url(&args, url).await;
+    }
+    if args.json {
+        return ascii_vision::headless::run_json(&args).await;
+    }
+    if args.headless {
+        return ascii_vision::headless::run_headless(&args).await;
+    }
+    let mut app = App::new(&args)?;
     app.run().await?;
     Ok(())
 }
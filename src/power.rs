@@ -0,0 +1,69 @@
+use std::{fs, path::Path};
+
+/// Whether the machine is running on battery, `None` when that can't be told
+///
+/// Best effort: reads the power supplies on Linux and asks `pmset` on macOS.
+pub fn on_battery() -> Option<bool> {
+    platform_on_battery()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_on_battery() -> Option<bool> {
+    on_battery_in(Path::new("/sys/class/power_supply"))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_on_battery() -> Option<bool> {
+    None
+}
+
+/// Look through a `/sys/class/power_supply`-style directory for a discharging battery
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn on_battery_in(dir: &Path) -> Option<bool> {
+    let read = |supply: &Path, file: &str| {
+        fs::read_to_string(supply.join(file))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut found = false;
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let supply = entry.path();
+        found = true;
+        if read(&supply, "type") == "Battery" && read(&supply, "status") == "Discharging" {
+            return Some(true);
+        }
+    }
+    found.then_some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discharging_battery_means_on_battery() {
+        let dir = std::env::temp_dir().join(format!("ascii-vision-power-{}", std::process::id()));
+        let battery = dir.join("BAT0");
+        fs::create_dir_all(&battery).unwrap();
+        fs::create_dir_all(dir.join("AC")).unwrap();
+        fs::write(dir.join("AC/type"), "Mains\n").unwrap();
+        fs::write(battery.join("type"), "Battery\n").unwrap();
+
+        fs::write(battery.join("status"), "Charging\n").unwrap();
+        assert_eq!(on_battery_in(&dir), Some(false));
+        fs::write(battery.join("status"), "Discharging\n").unwrap();
+        assert_eq!(on_battery_in(&dir), Some(true));
+        assert_eq!(on_battery_in(&dir.join("missing")), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
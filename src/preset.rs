@@ -0,0 +1,73 @@
+use crate::ascii::{AsciiConverter, CharacterSet, ScaleMode};
+
+/// Named looks that set several converter controls at once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// No preset, grading is neutral
+    #[default]
+    None,
+    /// Letterboxed, warm and slightly punchy, drawn with blocks
+    Cinematic,
+    /// Green phosphor monitor
+    RetroGreen,
+    /// High-contrast monochrome print
+    Newspaper,
+    /// Saturated full-color output with strong contrast
+    Vivid,
+}
+
+impl Preset {
+    pub fn next(&self) -> Preset {
+        match self {
+            Preset::None => Preset::Cinematic,
+            Preset::Cinematic => Preset::RetroGreen,
+            Preset::RetroGreen => Preset::Newspaper,
+            Preset::Newspaper => Preset::Vivid,
+            Preset::Vivid => Preset::None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::None => "None",
+            Preset::Cinematic => "Cinematic",
+            Preset::RetroGreen => "Retro-Green",
+            Preset::Newspaper => "Newspaper",
+            Preset::Vivid => "Vivid",
+        }
+    }
+
+    /// Set the converter fields this preset controls
+    ///
+    /// `None` only resets the grading so it doesn't undo the user's own character set,
+    /// color and scale choices.
+    pub fn apply(&self, converter: &mut AsciiConverter) {
+        let (contrast, temperature, tint) = match self {
+            Preset::None => (1.0, 0.0, None),
+            Preset::Cinematic => {
+                converter.set_scale_mode(ScaleMode::Fit);
+                converter.set_character_set(CharacterSet::Blocks);
+                converter.set_color_enabled(true);
+                (1.2, 0.5, None)
+            }
+            Preset::RetroGreen => {
+                converter.set_character_set(CharacterSet::Dense);
+                converter.set_color_enabled(true);
+                (1.1, 0.0, Some((51, 255, 102)))
+            }
+            Preset::Newspaper => {
+                converter.set_character_set(CharacterSet::Simple);
+                converter.set_color_enabled(false);
+                (1.6, 0.0, None)
+            }
+            Preset::Vivid => {
+                converter.set_character_set(CharacterSet::Minimal);
+                converter.set_color_enabled(true);
+                (1.4, 0.0, None)
+            }
+        };
+        converter.set_contrast(contrast);
+        converter.set_temperature(temperature);
+        converter.set_tint(tint);
+    }
+}
@@ -0,0 +1,103 @@
+use std::{collections::BTreeMap, fs, ops::Bound, path::Path};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::ascii::{AsciiConverter, CharacterSet, ScaleMode, ToneCurve};
+
+/// File in the config directory holding the saved profiles
+pub const PROFILES_FILE: &str = "profiles.json";
+
+/// Converter settings saved under a name
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub character_set: CharacterSet,
+    pub color_enabled: bool,
+    pub scale_factor: f32,
+    #[serde(default)]
+    pub scale_mode: ScaleMode,
+    #[serde(default)]
+    pub tone_curve: ToneCurve,
+    #[serde(default = "default_contrast")]
+    pub contrast: f32,
+    #[serde(default)]
+    pub brightness_offset: i16,
+    #[serde(default)]
+    pub temperature: f32,
+    #[serde(default)]
+    pub tint: Option<(u8, u8, u8)>,
+}
+
+fn default_contrast() -> f32 {
+    1.0
+}
+
+impl Profile {
+    /// Snapshot the current converter settings
+    pub fn capture(converter: &AsciiConverter) -> Self {
+        Self {
+            character_set: converter.character_set(),
+            color_enabled: converter.color_enabled(),
+            scale_factor: converter.scale_factor(),
+            scale_mode: converter.scale_mode(),
+            tone_curve: converter.tone_curve(),
+            contrast: converter.contrast(),
+            brightness_offset: converter.brightness_offset(),
+            temperature: converter.temperature(),
+            tint: converter.tint(),
+        }
+    }
+
+    pub fn apply(&self, converter: &mut AsciiConverter) {
+        converter.set_character_set(self.character_set);
+        converter.set_color_enabled(self.color_enabled);
+        converter.set_scale_factor(self.scale_factor);
+        converter.set_scale_mode(self.scale_mode);
+        converter.set_tone_curve(self.tone_curve);
+        converter.set_contrast(self.contrast);
+        converter.set_brightness_offset(self.brightness_offset);
+        converter.set_temperature(self.temperature);
+        converter.set_tint(self.tint);
+    }
+}
+
+/// Named profiles, kept sorted by name so cycling is stable
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profiles(pub BTreeMap<String, Profile>);
+
+impl Profiles {
+    /// Load profiles from `dir`, treating a missing file as no profiles
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(PROFILES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(PROFILES_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    pub fn insert(&mut self, name: String, profile: Profile) {
+        self.0.insert(name, profile);
+    }
+
+    /// The profile after `current` in name order, wrapping around
+    pub fn next_after(&self, current: Option<&str>) -> Option<(&String, &Profile)> {
+        current
+            .and_then(|name| {
+                self.0
+                    .range::<str, _>((Bound::Excluded(name), Bound::Unbounded))
+                    .next()
+            })
+            .or_else(|| self.0.iter().next())
+    }
+}
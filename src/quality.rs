@@ -0,0 +1,227 @@
+use std::time::{Duration, Instant};
+
+/// Grid scale multiplier for each quality level, full detail first
+pub const QUALITY_SCALES: &[f32] = &[1.0, 0.8, 0.65, 0.5, 0.4];
+
+/// How long frame rates are averaged over before deciding anything
+const WINDOW: Duration = Duration::from_secs(1);
+/// Time after a change before the frame rate counts again, covering the capture resolution
+/// following the new grid size and the camera reopening
+const SETTLE: Duration = Duration::from_secs(4);
+/// Below this fraction of the target the feed is struggling
+const LOW_RATIO: f64 = 0.85;
+/// At or above this fraction of the target, with nothing dropped, there's headroom
+const HIGH_RATIO: f64 = 0.97;
+/// Struggling windows in a row before lowering quality
+const LOWER_AFTER: u32 = 2;
+/// Windows with headroom in a row before raising quality, doubled whenever a raise has to
+/// be taken back so the controller doesn't flap between two levels
+const RAISE_AFTER: u32 = 3;
+const MAX_RAISE_AFTER: u32 = 64;
+/// A raise taken back within this long counts as failed
+const FAILED_RAISE: Duration = Duration::from_secs(15);
+/// A raise that holds this long resets the backoff
+const STABLE_RAISE: Duration = Duration::from_secs(60);
+
+/// Feedback loop trading grid detail for frame rate: lowers the quality level while frames
+/// come in slower than the target or get dropped, and raises it again when there's headroom
+#[derive(Debug, Clone)]
+pub struct QualityController {
+    running: bool,
+    level: usize,
+    window_start: Instant,
+    /// Shared frame sequence at the start of the window
+    window_sequence: u64,
+    /// Camera frames dropped in the window because newer ones arrived first
+    dropped: usize,
+    low_windows: u32,
+    high_windows: u32,
+    hold_until: Instant,
+    raise_after: u32,
+    last_raise: Option<Instant>,
+}
+
+impl Default for QualityController {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            running: false,
+            level: 0,
+            window_start: now,
+            window_sequence: 0,
+            dropped: 0,
+            low_windows: 0,
+            high_windows: 0,
+            hold_until: now,
+            raise_after: RAISE_AFTER,
+            last_raise: None,
+        }
+    }
+}
+
+impl QualityController {
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Current index into [`QUALITY_SCALES`]
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Start or stop adapting, returning whether it is now running; it always starts from
+    /// full detail
+    pub fn toggle(&mut self, now: Instant, sequence: u64) -> bool {
+        let running = !self.running;
+        *self = Self {
+            running,
+            window_start: now,
+            window_sequence: sequence,
+            hold_until: now,
+            ..Self::default()
+        };
+        running
+    }
+
+    /// Count camera frames that were replaced by newer ones before being shown
+    pub fn record_dropped(&mut self, frames: usize) {
+        self.dropped += frames;
+    }
+
+    /// Measure the frame rate once a window has passed, returning the new level when it
+    /// changes
+    ///
+    /// `sequence` is the shared frame's sequence number, which counts converted frames.
+    pub fn tick(&mut self, now: Instant, sequence: u64, target_fps: f64) -> Option<usize> {
+        if !self.running {
+            return None;
+        }
+        let elapsed = now.duration_since(self.window_start);
+        if elapsed < WINDOW {
+            return None;
+        }
+        let frames = sequence.saturating_sub(self.window_sequence);
+        let dropped = std::mem::take(&mut self.dropped);
+        self.window_start = now;
+        self.window_sequence = sequence;
+        if let Some(raised) = self.last_raise
+            && now.duration_since(raised) >= STABLE_RAISE
+        {
+            self.raise_after = RAISE_AFTER;
+            self.last_raise = None;
+        }
+        // Nothing to measure while the source is stopped or the last change settles
+        if frames == 0 || now < self.hold_until {
+            self.low_windows = 0;
+            self.high_windows = 0;
+            return None;
+        }
+
+        let fps = frames as f64 / elapsed.as_secs_f64();
+        let struggling = fps < target_fps * LOW_RATIO || dropped * 5 > frames as usize;
+        let headroom = fps >= target_fps * HIGH_RATIO && dropped == 0;
+        (self.low_windows, self.high_windows) = match (struggling, headroom) {
+            (true, _) => (self.low_windows + 1, 0),
+            (_, true) => (0, self.high_windows + 1),
+            _ => (0, 0),
+        };
+
+        if self.low_windows >= LOWER_AFTER && self.level + 1 < QUALITY_SCALES.len() {
+            if self
+                .last_raise
+                .is_some_and(|raised| now.duration_since(raised) < FAILED_RAISE)
+            {
+                self.raise_after = (self.raise_after * 2).min(MAX_RAISE_AFTER);
+            }
+            self.last_raise = None;
+            return Some(self.change_level(self.level + 1, now));
+        }
+        if self.high_windows >= self.raise_after && self.level > 0 {
+            self.last_raise = Some(now);
+            return Some(self.change_level(self.level - 1, now));
+        }
+        None
+    }
+
+    fn change_level(&mut self, level: usize, now: Instant) -> usize {
+        self.level = level;
+        self.low_windows = 0;
+        self.high_windows = 0;
+        self.hold_until = now + SETTLE;
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `windows` one-second windows at `fps`, returning the level changes
+    fn run(
+        quality: &mut QualityController,
+        clock: &mut Instant,
+        sequence: &mut u64,
+        windows: u32,
+        fps: u64,
+    ) -> Vec<usize> {
+        let mut changes = Vec::new();
+        for _ in 0..windows {
+            *clock += WINDOW;
+            *sequence += fps;
+            changes.extend(quality.tick(*clock, *sequence, 20.0));
+        }
+        changes
+    }
+
+    #[test]
+    fn test_lowers_when_slow_and_waits_for_the_change_to_settle() {
+        let mut clock = Instant::now();
+        let mut sequence = 0;
+        let mut quality = QualityController::default();
+        assert!(quality.toggle(clock, sequence));
+
+        // One slow window isn't enough, two are
+        assert!(run(&mut quality, &mut clock, &mut sequence, 1, 10).is_empty());
+        assert_eq!(run(&mut quality, &mut clock, &mut sequence, 1, 10), [1]);
+        // Still slow, but nothing changes until the settle time has passed
+        assert!(run(&mut quality, &mut clock, &mut sequence, 3, 10).is_empty());
+        assert_eq!(run(&mut quality, &mut clock, &mut sequence, 3, 10), [2]);
+
+        // Dropped frames count as struggling even at the target rate
+        clock += SETTLE;
+        for _ in 0..2 {
+            quality.record_dropped(10);
+            assert!(run(&mut quality, &mut clock, &mut sequence, 1, 20).len() <= 1);
+        }
+        assert_eq!(quality.level(), 3);
+
+        // No frames, e.g. a stopped camera, is not slow
+        clock += SETTLE;
+        assert!(run(&mut quality, &mut clock, &mut sequence, 10, 0).is_empty());
+    }
+
+    #[test]
+    fn test_failed_raises_back_off() {
+        let mut clock = Instant::now();
+        let mut sequence = 0;
+        let mut quality = QualityController::default();
+        quality.toggle(clock, sequence);
+        run(&mut quality, &mut clock, &mut sequence, 2, 10);
+        assert_eq!(quality.level(), 1);
+
+        // Headroom raises after the settle time plus three good windows
+        let changes = run(&mut quality, &mut clock, &mut sequence, 7, 20);
+        assert_eq!(changes, [0]);
+        // The raise was too much, so the next one needs twice the good windows
+        run(&mut quality, &mut clock, &mut sequence, 6, 10);
+        assert_eq!(quality.level(), 1);
+        assert!(run(&mut quality, &mut clock, &mut sequence, 6, 20).is_empty());
+        assert_eq!(run(&mut quality, &mut clock, &mut sequence, 1, 20), [0]);
+
+        // Stopping and starting again begins from full detail with no backoff
+        assert!(!quality.toggle(clock, sequence));
+        assert!(run(&mut quality, &mut clock, &mut sequence, 3, 10).is_empty());
+        assert!(quality.toggle(clock, sequence));
+        assert_eq!(quality.level(), 0);
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use color_eyre::{Result, eyre::eyre};
+use image::DynamicImage;
+use reqwest::{Client, header, redirect::Policy};
+use tracing::debug;
+
+/// Redirects followed before giving up
+const MAX_REDIRECTS: usize = 5;
+/// Time allowed for connecting and downloading one image
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Largest response accepted, so a misconfigured URL can't exhaust memory
+const MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+
+/// HTTP(S) client for fetching images, following a few redirects
+fn client() -> Result<Client> {
+    Client::builder()
+        .user_agent(concat!("ascii-vision/", env!("CARGO_PKG_VERSION")))
+        .redirect(Policy::limited(MAX_REDIRECTS))
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| eyre!("Couldn't set up the HTTP client: {e}"))
+}
+
+/// Whether a `Content-Type` value can be an image; a missing one is given the benefit of
+/// the doubt and left to the decoder
+fn is_image_type(content_type: Option<&str>) -> bool {
+    content_type.is_none_or(|value| value.trim().to_ascii_lowercase().starts_with("image/"))
+}
+
+/// Download an image over HTTP or HTTPS, following redirects
+pub async fn fetch_image(url: &str) -> Result<DynamicImage> {
+    let response = client()?
+        .get(url)
+        .header(header::ACCEPT, "image/*")
+        .send()
+        .await
+        .map_err(|e| eyre!("Fetching {url} failed: {e}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(eyre!("Fetching {url} failed with HTTP status {status}"));
+    }
+    if response.url().as_str() != url {
+        debug!("{} redirected to {}", url, response.url());
+    }
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if !is_image_type(content_type.as_deref()) {
+        return Err(eyre!(
+            "{url} is not an image (content type {})",
+            content_type.unwrap_or_default()
+        ));
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_RESPONSE_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| eyre!("Downloading {url} failed: {e}"))?;
+    // Chunked responses give no length up front
+    if body.len() > MAX_RESPONSE_BYTES {
+        return Err(too_large());
+    }
+    image::load_from_memory(&body).map_err(|e| eyre!("Couldn't decode the image from {url}: {e}"))
+}
+
+fn too_large() -> color_eyre::Report {
+    eyre!(
+        "Response is larger than {} MiB",
+        MAX_RESPONSE_BYTES / 1024 / 1024
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_content_types() {
+        assert!(is_image_type(Some("image/png")));
+        assert!(is_image_type(Some(" Image/JPEG; charset=binary")));
+        assert!(is_image_type(None));
+        assert!(!is_image_type(Some("text/html")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_urls_without_a_scheme() {
+        assert!(fetch_image("cam.example/a.jpg").await.is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Rasterizing ASCII art into an image with the bundled bitmap font, so a frame can be saved
+//! as a PNG that looks the same everywhere instead of depending on the viewer's terminal.
+
+use image::{Rgb, RgbImage};
+
+use crate::{
+    ascii::{ColoredChar, color_rgb, glyph_coverage},
+    blocks::{self, CellMode},
+    glyphs::{self, GLYPH_HEIGHT, GLYPH_WIDTH},
+};
+
+/// Color of characters without a foreground color, as a terminal's default text
+const DEFAULT_FOREGROUND: [u8; 3] = [255, 255, 255];
+
+/// Smallest glyph height drawn; below this glyphs are just specks
+pub const MIN_GLYPH_PX: u32 = 4;
+
+/// Pixel size of a cell for glyphs `glyph_px` tall, keeping the font's 1:2 proportions
+pub fn cell_size(glyph_px: u32) -> (u32, u32) {
+    let height = glyph_px.max(MIN_GLYPH_PX);
+    let width = (height as usize * GLYPH_WIDTH / GLYPH_HEIGHT) as u32;
+    (width, height)
+}
+
+/// How a character's cell is inked
+enum Glyph {
+    /// From the bundled font
+    Bitmap([u16; GLYPH_HEIGHT]),
+    /// Sextant blocks or braille dots, drawn from their sub-cell pattern
+    SubCells(CellMode, u8),
+    /// Anything else, as a flat cell this much inked
+    Shade(f32),
+}
+
+impl Glyph {
+    fn of(ch: char) -> Self {
+        if let Some(bitmap) = glyphs::rasterize(ch) {
+            Glyph::Bitmap(bitmap)
+        } else if let Some((mode, pattern)) = blocks::glyph_pattern(ch) {
+            Glyph::SubCells(mode, pattern)
+        } else {
+            Glyph::Shade(glyph_coverage(ch))
+        }
+    }
+
+    /// Whether pixel `x`, `y` of a `width` x `height` cell is inked, `None` when shaded
+    fn inked(&self, x: u32, y: u32, width: u32, height: u32) -> Option<bool> {
+        match self {
+            Glyph::Bitmap(rows) => {
+                let bits = rows[(y * GLYPH_HEIGHT as u32 / height) as usize];
+                let bit = x * GLYPH_WIDTH as u32 / width;
+                Some(bits & 1 << (GLYPH_WIDTH as u32 - 1 - bit) != 0)
+            }
+            Glyph::SubCells(mode, pattern) => {
+                let (columns, rows) = mode.sub_cells();
+                let (across, down) = (x * columns, y * rows);
+                let bit = down / height * columns + across / width;
+                let lit = pattern >> bit & 1 == 1;
+                // Braille dots fill the middle half of their sub-cell, sextants all of it
+                let in_dot = |offset: u32, size: u32| (size / 4..size * 3 / 4).contains(&offset);
+                Some(
+                    lit && (*mode != CellMode::Braille
+                        || in_dot(across % width, width) && in_dot(down % height, height)),
+                )
+            }
+            Glyph::Shade(_) => None,
+        }
+    }
+}
+
+/// Draw a frame on black, each glyph `glyph_px` tall in its foreground color
+pub fn rasterize(frame: &[Vec<ColoredChar>], glyph_px: u32) -> RgbImage {
+    rasterize_on(frame, glyph_px, [0, 0, 0])
+}
+
+/// Draw a frame on `background`; cells with their own background, like letterbox bars,
+/// keep it
+///
+/// Glyphs are scaled from the bundled 12x24 font by nearest neighbor, so sizes that are
+/// multiples of 24 stay sharpest. Sextants and braille are drawn from their sub-cells, and
+/// other characters the font lacks as a flat cell shaded by their estimated coverage.
+pub fn rasterize_on(frame: &[Vec<ColoredChar>], glyph_px: u32, background: [u8; 3]) -> RgbImage {
+    let (cell_width, cell_height) = cell_size(glyph_px);
+    let columns = frame.iter().map(Vec::len).max().unwrap_or(0) as u32;
+    let mut image = RgbImage::from_pixel(
+        columns * cell_width,
+        frame.len() as u32 * cell_height,
+        Rgb(background),
+    );
+    for (row, line) in frame.iter().enumerate() {
+        for (column, cell) in line.iter().enumerate() {
+            let fg = cell
+                .style
+                .fg
+                .and_then(color_rgb)
+                .unwrap_or(DEFAULT_FOREGROUND);
+            let bg = cell.style.bg.and_then(color_rgb).unwrap_or(background);
+            let glyph = Glyph::of(cell.ch);
+            let shade = match glyph {
+                Glyph::Shade(coverage) => blend(bg, fg, coverage),
+                _ => bg,
+            };
+            let (left, top) = (column as u32 * cell_width, row as u32 * cell_height);
+            for y in 0..cell_height {
+                for x in 0..cell_width {
+                    let color = match glyph.inked(x, y, cell_width, cell_height) {
+                        Some(true) => fg,
+                        Some(false) => bg,
+                        None => shade,
+                    };
+                    image.put_pixel(left + x, top + y, Rgb(color));
+                }
+            }
+        }
+    }
+    image
+}
+
+/// `from` moved towards `to` by `amount`, 0 to 1
+fn blend(from: [u8; 3], to: [u8; 3], amount: f32) -> [u8; 3] {
+    let amount = amount.clamp(0.0, 1.0);
+    std::array::from_fn(|i| {
+        (from[i] as f32 + (to[i] as f32 - from[i] as f32) * amount).round() as u8
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{Color, Style};
+
+    use super::*;
+
+    #[test]
+    fn test_rasterized_frame_is_one_cell_per_character() {
+        let cell = |ch, style| ColoredChar { ch, style };
+        let frame = vec![
+            vec![
+                cell('█', Style::default().fg(Color::Rgb(255, 0, 0))),
+                cell(' ', Style::default()),
+                cell('@', Style::default()),
+            ],
+            vec![
+                cell('\u{2800}', Style::default().bg(Color::Rgb(0, 0, 255))),
+                cell('⣿', Style::default()),
+                cell('\u{1FB00}', Style::default()),
+            ],
+        ];
+        let image = rasterize(&frame, 24);
+        let (cell_width, cell_height) = cell_size(24);
+        assert_eq!((cell_width, cell_height), (12, 24));
+        assert_eq!(image.dimensions(), (3 * cell_width, 2 * cell_height));
+        // The full block fills its cell in its color, the space stays background
+        assert_eq!(image.get_pixel(6, 12), &Rgb([255, 0, 0]));
+        assert_eq!(image.get_pixel(18, 12), &Rgb([0, 0, 0]));
+        // An empty braille cell has no ink but keeps its own background
+        assert_eq!(image.get_pixel(6, 36), &Rgb([0, 0, 255]));
+        // Braille dots sit inside their sub-cells, sextants fill them
+        assert_eq!(image.get_pixel(12 + 3, 24 + 3), &Rgb([255, 255, 255]));
+        assert_eq!(image.get_pixel(12, 24), &Rgb([0, 0, 0]));
+        assert_eq!(image.get_pixel(24, 24), &Rgb([255, 255, 255]));
+        assert_eq!(image.get_pixel(24 + 6, 24 + 8), &Rgb([0, 0, 0]));
+
+        let small = rasterize_on(&frame, 10, [9, 9, 9]);
+        assert_eq!(small.dimensions(), (3 * 5, 2 * 10));
+        assert_eq!(small.get_pixel(7, 5), &Rgb([9, 9, 9]));
+        assert_eq!(rasterize(&frame, 0).dimensions(), (3 * 2, 2 * 4));
+        assert_eq!(rasterize(&[], 24).dimensions(), (0, 0));
+    }
+}
@@ -0,0 +1,155 @@
+//! The desktop as a frame source: `ffmpeg` grabs the primary display, optionally cropped to
+//! a region, and [`VideoPlayer`] sends the frames on like a video's.
+//!
+//! Grabbing uses x11grab on Linux (so it needs an X11 or XWayland display), gdigrab on
+//! Windows and avfoundation on macOS, which asks for screen recording permission.
+
+use std::{env, fmt, process::Command, str::FromStr};
+
+use clap::ValueEnum;
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    video::{self, VideoInfo, VideoPlayer},
+};
+
+/// Where frames come from when no input file is set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum CaptureSource {
+    #[default]
+    Camera,
+    /// The primary display
+    Screen,
+}
+
+/// Part of the screen to grab, in pixels from the top left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRegion {
+    /// Whether the region lies within a `width` x `height` screen
+    pub fn fits(&self, width: u32, height: u32) -> bool {
+        self.x.saturating_add(self.width) <= width && self.y.saturating_add(self.height) <= height
+    }
+}
+
+/// Parses `x,y,width,height`, e.g. `0,0,1280,720`
+impl FromStr for ScreenRegion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|value| value.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("expected x,y,width,height in pixels, got {s:?}"))?;
+        let [x, y, width, height] = values[..] else {
+            return Err(format!("expected x,y,width,height in pixels, got {s:?}"));
+        };
+        if width == 0 || height == 0 {
+            return Err(format!("region {s:?} has no area"));
+        }
+        Ok(Self {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+}
+
+impl fmt::Display for ScreenRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.x, self.y, self.width, self.height)
+    }
+}
+
+/// `ffmpeg` input options grabbing the primary display at `fps` on this platform
+fn grab_input(fps: f64) -> Vec<String> {
+    let fps = format!("{}", fps.max(1.0));
+    let (format, device) = if cfg!(target_os = "macos") {
+        ("avfoundation", "Capture screen 0".to_string())
+    } else if cfg!(windows) {
+        ("gdigrab", "desktop".to_string())
+    } else {
+        (
+            "x11grab",
+            env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()),
+        )
+    };
+    ["-f", format, "-framerate", &fps, "-i", &device]
+        .map(String::from)
+        .to_vec()
+}
+
+/// Start grabbing the screen, or just `region` of it, sending frames at `fps`
+pub fn open_screen(
+    region: Option<ScreenRegion>,
+    fps: f64,
+    tx: UnboundedSender<Action>,
+) -> Result<VideoPlayer> {
+    let input = grab_input(fps);
+    let screen = video::probe_input(&input, "the screen")?;
+    let mut command = Command::new("ffmpeg");
+    command.args(["-v", "error", "-nostdin"]).args(&input);
+    let (width, height) = match region {
+        Some(region) if !region.fits(screen.width, screen.height) => {
+            return Err(eyre!(
+                "Region {region} doesn't fit the {}x{} screen",
+                screen.width,
+                screen.height
+            ));
+        }
+        Some(region) => {
+            command.args([
+                "-vf",
+                &format!(
+                    "crop={}:{}:{}:{}",
+                    region.width, region.height, region.x, region.y
+                ),
+            ]);
+            (region.width, region.height)
+        }
+        None => (screen.width, screen.height),
+    };
+    let info = VideoInfo {
+        width,
+        height,
+        fps: Some(fps.max(1.0)),
+    };
+    VideoPlayer::spawn(command, info, "the screen", tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_region_parsing() {
+        let region: ScreenRegion = " 10, 20,1280,720".parse().unwrap();
+        assert_eq!(
+            region,
+            ScreenRegion {
+                x: 10,
+                y: 20,
+                width: 1280,
+                height: 720
+            }
+        );
+        assert_eq!(region.to_string(), "10,20,1280,720");
+        assert!(region.fits(1290, 740));
+        assert!(!region.fits(1280, 740));
+
+        assert!("10,20,1280".parse::<ScreenRegion>().is_err());
+        assert!("0,0,0,720".parse::<ScreenRegion>().is_err());
+        assert!("0,0,-5,720".parse::<ScreenRegion>().is_err());
+    }
+}
@@ -0,0 +1,541 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, ErrorKind, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::Result;
+use serde_json::json;
+use tracing::{debug, error, info, warn};
+
+use crate::{ascii::to_ansi_string, frame::SharedGrid};
+
+/// Frames queued per sink before new ones are dropped, so a slow sink only loses frames
+const SINK_QUEUE: usize = 2;
+/// How long a network client may block a write before it is disconnected
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Clear the screen once, then redraw from the top-left for every frame
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+
+/// Follows every frame in a text stream, so readers can split it into pages
+const FORM_FEED: char = '\x0c';
+/// How often a text stream pushes out what it has buffered
+const TEXT_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A converted frame as handed to the sinks
+#[derive(Debug, Clone)]
+pub struct SinkFrame {
+    pub grid: SharedGrid,
+    /// Frames dispatched before this one in the session
+    pub sequence: u64,
+    /// When the source captured the frame
+    pub captured: Instant,
+}
+
+impl SinkFrame {
+    /// The frame as terminal output, ANSI colored with `\r\n` line endings
+    fn terminal_text(&self) -> String {
+        to_ansi_string(&self.grid).replace('\n', "\r\n")
+    }
+}
+
+/// A destination for converted frames besides the terminal, like a recording or a stream
+///
+/// Each sink runs on its own thread, so a slow one never holds up the display or the others.
+pub trait FrameSink: Send {
+    fn name(&self) -> String;
+
+    fn write_frame(&mut self, frame: &SinkFrame) -> Result<()>;
+
+    /// Flush anything buffered once no more frames will come
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct SinkHandle {
+    name: String,
+    tx: SyncSender<SinkFrame>,
+    thread: JoinHandle<()>,
+    dropped: u64,
+}
+
+/// Fans every converted frame out to all active sinks
+#[derive(Default)]
+pub struct SinkSet {
+    sinks: Vec<SinkHandle>,
+    sequence: u64,
+}
+
+impl SinkSet {
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Start feeding a sink from its own thread
+    pub fn add(&mut self, sink: Box<dyn FrameSink>) {
+        let name = sink.name();
+        let (tx, rx) = sync_channel(SINK_QUEUE);
+        let thread = thread::spawn(move || run_sink(sink, rx));
+        info!("Output sink started: {}", name);
+        self.sinks.push(SinkHandle {
+            name,
+            tx,
+            thread,
+            dropped: 0,
+        });
+    }
+
+    /// Stop the sink called `name` once it has written what's queued, returning false when
+    /// there is no such sink, e.g. because it failed
+    pub fn remove(&mut self, name: &str) -> bool {
+        let Some(index) = self.sinks.iter().position(|sink| sink.name == name) else {
+            return false;
+        };
+        close_sink(self.sinks.remove(index));
+        true
+    }
+
+    /// Queue a frame for every sink; full queues drop it and failed sinks are removed
+    pub fn dispatch(&mut self, grid: &SharedGrid, captured: Instant) {
+        if self.sinks.is_empty() {
+            return;
+        }
+        let frame = SinkFrame {
+            grid: grid.clone(),
+            sequence: self.sequence,
+            captured,
+        };
+        self.sequence += 1;
+        self.sinks
+            .retain_mut(|sink| match sink.tx.try_send(frame.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    sink.dropped += 1;
+                    debug!("Sink {} is behind, dropped a frame", sink.name);
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    warn!("Output sink {} stopped", sink.name);
+                    false
+                }
+            });
+    }
+}
+
+impl Drop for SinkSet {
+    fn drop(&mut self) {
+        for sink in self.sinks.drain(..) {
+            close_sink(sink);
+        }
+    }
+}
+
+fn close_sink(sink: SinkHandle) {
+    // Closing the channel ends the thread once it has written what's queued
+    drop(sink.tx);
+    if sink.thread.join().is_err() {
+        error!("Output sink {} panicked", sink.name);
+    } else if sink.dropped > 0 {
+        info!(
+            "Output sink {} skipped {} frames it couldn't keep up with",
+            sink.name, sink.dropped
+        );
+    }
+}
+
+fn run_sink(mut sink: Box<dyn FrameSink>, rx: Receiver<SinkFrame>) {
+    for frame in rx {
+        if let Err(e) = sink.write_frame(&frame) {
+            error!("Output sink {} failed: {}", sink.name(), e);
+            return;
+        }
+    }
+    if let Err(e) = sink.finish() {
+        error!("Output sink {} failed to finish: {}", sink.name(), e);
+    }
+}
+
+/// Records frames as an asciinema v2 cast, replayable with `asciinema play`
+///
+/// Event times come from the capture timestamps, so a stuttering camera replays at its
+/// real pace. Time covered by dropped frames stays in the gap before the next one.
+pub struct CastRecorder<W = BufWriter<File>> {
+    path: String,
+    out: W,
+    /// Capture time of the first frame, which the event times count from
+    start: Option<Instant>,
+}
+
+impl CastRecorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self::new(
+            path.display().to_string(),
+            BufWriter::new(File::create(path)?),
+        ))
+    }
+}
+
+impl<W: Write + Send> CastRecorder<W> {
+    fn new(path: String, out: W) -> Self {
+        Self {
+            path,
+            out,
+            start: None,
+        }
+    }
+}
+
+impl<W: Write + Send> FrameSink for CastRecorder<W> {
+    fn name(&self) -> String {
+        format!("recording to {}", self.path)
+    }
+
+    fn write_frame(&mut self, frame: &SinkFrame) -> Result<()> {
+        let mut data = String::new();
+        let first = self.start.is_none();
+        let start = *self.start.get_or_insert(frame.captured);
+        if first {
+            // The header takes the size of the first frame
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            let header = json!({
+                "version": 2,
+                "width": frame.grid.first().map_or(0, Vec::len),
+                "height": frame.grid.len(),
+                "timestamp": timestamp,
+            });
+            writeln!(self.out, "{header}")?;
+            data.push_str(CLEAR_SCREEN);
+        }
+        data.push_str(CURSOR_HOME);
+        data.push_str(&frame.terminal_text());
+        let time = frame.captured.duration_since(start).as_secs_f64();
+        writeln!(self.out, "{}", json!([time, "o", data]))?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Appends frames as plain text to a file or named pipe, each followed by a form feed
+///
+/// Writes are buffered and flushed a few times a second rather than per frame.
+pub struct TextFileSink<W = BufWriter<File>> {
+    path: String,
+    out: W,
+    last_flush: Instant,
+}
+
+impl TextFileSink {
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self::new(
+            path.display().to_string(),
+            BufWriter::new(open_append(path)?),
+        ))
+    }
+}
+
+impl<W: Write + Send> TextFileSink<W> {
+    fn new(path: String, out: W) -> Self {
+        Self {
+            path,
+            out,
+            last_flush: Instant::now(),
+        }
+    }
+}
+
+impl<W: Write + Send> FrameSink for TextFileSink<W> {
+    fn name(&self) -> String {
+        format!("text frames to {}", self.path)
+    }
+
+    fn write_frame(&mut self, frame: &SinkFrame) -> Result<()> {
+        let mut text = String::new();
+        for line in frame.grid.iter() {
+            text.extend(line.iter().map(|cell| cell.ch));
+            text.push('\n');
+        }
+        text.push(FORM_FEED);
+        self.out.write_all(text.as_bytes())?;
+        if self.last_flush.elapsed() >= TEXT_FLUSH_INTERVAL {
+            self.out.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Open a file for appending, creating it if needed
+///
+/// Opening a named pipe for writing waits for a reader, which would freeze the display, so
+/// on Unix a pipe nobody reads from is an error instead.
+fn open_append(path: &Path) -> io::Result<File> {
+    let mut options = File::options();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    {
+        use std::os::{
+            fd::AsRawFd,
+            unix::fs::{FileTypeExt, OpenOptionsExt},
+        };
+        if std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_fifo()) {
+            let file = options
+                .custom_flags(libc::O_NONBLOCK)
+                .open(path)
+                .map_err(|e| match e.raw_os_error() {
+                    Some(libc::ENXIO) => io::Error::other("nothing is reading from the pipe"),
+                    _ => e,
+                })?;
+            // Writes happen on the sink's own thread, where blocking on a slow reader is fine
+            // SAFETY: the descriptor stays open for the duration of both calls
+            unsafe {
+                let fd = file.as_raw_fd();
+                let flags = libc::fcntl(fd, libc::F_GETFL);
+                if flags == -1 || libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            return Ok(file);
+        }
+    }
+    options.open(path)
+}
+
+/// Streams frames as ANSI text to every client connected over TCP, e.g. `nc host port`
+pub struct TcpBroadcaster {
+    address: String,
+    listener: TcpListener,
+    clients: Vec<TcpStream>,
+}
+
+impl TcpBroadcaster {
+    pub fn bind(address: &str) -> Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            address: listener.local_addr()?.to_string(),
+            listener,
+            clients: Vec::new(),
+        })
+    }
+
+    /// Take every connection waiting to be accepted
+    fn accept_clients(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((mut stream, peer)) => {
+                    info!("Stream client connected: {}", peer);
+                    stream.set_nonblocking(false)?;
+                    stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+                    if stream.write_all(CLEAR_SCREEN.as_bytes()).is_ok() {
+                        self.clients.push(stream);
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl FrameSink for TcpBroadcaster {
+    fn name(&self) -> String {
+        format!("stream on {}", self.address)
+    }
+
+    fn write_frame(&mut self, frame: &SinkFrame) -> Result<()> {
+        if let Err(e) = self.accept_clients() {
+            warn!("Failed to accept stream client: {}", e);
+        }
+        if self.clients.is_empty() {
+            return Ok(());
+        }
+        let data = format!("{CURSOR_HOME}{}", frame.terminal_text());
+        // A client that errors or can't keep up is dropped, the rest carry on
+        self.clients.retain_mut(|client| {
+            let sent = client.write_all(data.as_bytes());
+            if let Err(e) = &sent {
+                debug!("Dropping stream client: {}", e);
+            }
+            sent.is_ok()
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        sync::{Arc, mpsc},
+    };
+
+    use super::*;
+    use crate::ascii::ColoredChar;
+
+    /// Reports each sequence it writes; a held sink then blocks until released
+    struct TestSink {
+        written: mpsc::Sender<u64>,
+        held: Option<mpsc::Receiver<()>>,
+    }
+
+    impl TestSink {
+        fn new(held: Option<mpsc::Receiver<()>>) -> (Box<Self>, mpsc::Receiver<u64>) {
+            let (written, rx) = mpsc::channel();
+            (Box::new(Self { written, held }), rx)
+        }
+    }
+
+    impl FrameSink for TestSink {
+        fn name(&self) -> String {
+            "test".to_string()
+        }
+
+        fn write_frame(&mut self, frame: &SinkFrame) -> Result<()> {
+            let _ = self.written.send(frame.sequence);
+            if let Some(held) = &self.held {
+                // Returns at once when released, so later frames aren't held
+                let _ = held.recv();
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_slow_sink_drops_frames_without_stalling_others() {
+        let (fast, fast_written) = TestSink::new(None);
+        let (release, held) = mpsc::channel();
+        let (slow, slow_written) = TestSink::new(Some(held));
+        let mut sinks = SinkSet::default();
+        sinks.add(fast);
+        sinks.add(slow);
+
+        // The slow sink takes the first frame and is stuck on it until released
+        let grid = Arc::new(vec![vec![ColoredChar::letterbox()]]);
+        sinks.dispatch(&grid, Instant::now());
+        assert_eq!(slow_written.recv().unwrap(), 0);
+        assert_eq!(fast_written.recv().unwrap(), 0);
+        for sequence in 1..20 {
+            sinks.dispatch(&grid, Instant::now());
+            assert_eq!(fast_written.recv().unwrap(), sequence);
+        }
+        drop(release);
+        drop(sinks);
+
+        // Only the frames that fit in its queue meanwhile reach the slow sink
+        let slow: Vec<u64> = slow_written.try_iter().collect();
+        assert_eq!(slow, (1..=SINK_QUEUE as u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cast_times_follow_capture_timestamps() {
+        let mut recorder = CastRecorder::new("test.cast".to_string(), Vec::new());
+        let start = Instant::now();
+        let grid = Arc::new(vec![vec![ColoredChar::letterbox(); 3]; 2]);
+        // Uneven intervals, with the frame at sequence 2 dropped before the recorder
+        for (sequence, millis) in [(0, 0), (1, 100), (3, 350)] {
+            let frame = SinkFrame {
+                grid: grid.clone(),
+                sequence,
+                captured: start + Duration::from_millis(millis),
+            };
+            recorder.write_frame(&frame).unwrap();
+        }
+
+        let output = String::from_utf8(recorder.out).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(
+            (lines[0]["width"].as_u64(), lines[0]["height"].as_u64()),
+            (Some(3), Some(2))
+        );
+        let times: Vec<f64> = lines[1..]
+            .iter()
+            .map(|event| event[0].as_f64().unwrap())
+            .collect();
+        assert_eq!(times, [0.0, 0.1, 0.35]);
+    }
+
+    #[test]
+    fn test_text_file_sink_separates_frames_with_form_feeds() {
+        let mut sink = TextFileSink::new("frames.txt".to_string(), Vec::new());
+        let cell = |ch| ColoredChar {
+            ch,
+            style: Default::default(),
+        };
+        for ch in ['@', '#'] {
+            let frame = SinkFrame {
+                grid: Arc::new(vec![vec![cell(ch), cell(' ')], vec![cell('.'), cell(ch)]]),
+                sequence: 0,
+                captured: Instant::now(),
+            };
+            sink.write_frame(&frame).unwrap();
+        }
+        sink.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(sink.out).unwrap(),
+            "@ \n.@\n\x0c# \n.#\n\x0c"
+        );
+    }
+
+    #[test]
+    fn test_sink_set_removes_a_sink_by_name() {
+        let (sink, written) = TestSink::new(None);
+        let mut sinks = SinkSet::default();
+        sinks.add(sink);
+        let grid = Arc::new(vec![vec![ColoredChar::letterbox()]]);
+        sinks.dispatch(&grid, Instant::now());
+        assert!(!sinks.remove("missing"));
+        assert!(sinks.remove("test"));
+        assert!(sinks.is_empty());
+        // Frames queued before removal are still written
+        assert_eq!(written.try_iter().collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn test_tcp_broadcaster_streams_to_clients() {
+        let mut broadcaster = TcpBroadcaster::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(&broadcaster.address).unwrap();
+        let frame = SinkFrame {
+            grid: Arc::new(vec![vec![ColoredChar {
+                ch: '@',
+                style: Default::default(),
+            }]]),
+            sequence: 0,
+            captured: Instant::now(),
+        };
+        // The connection may take a moment to be ready for accepting
+        for _ in 0..50 {
+            broadcaster.write_frame(&frame).unwrap();
+            if !broadcaster.clients.is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        drop(broadcaster);
+
+        let mut received = String::new();
+        client.read_to_string(&mut received).unwrap();
+        assert_eq!(received, format!("{CLEAR_SCREEN}{CURSOR_HOME}@"));
+    }
+}
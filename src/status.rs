@@ -0,0 +1,213 @@
+use std::{collections::HashMap, ops::Range};
+
+use tracing::warn;
+use unicode_width::UnicodeWidthStr;
+
+/// Layout matching the status bar before it became configurable, plus the frame rates
+pub const DEFAULT_STATUS_FORMAT: &str = "Camera: {camera} | Character Set: {charset} | Mode: {mode} | Color: {color} | Scale: {scale} | Curve: {curve} | Invert: {invert} | Effect: {effect} | Preset: {preset} | Capture: {capture_fps} / Render: {render_fps} | {msg}";
+
+/// A value the status bar can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusField {
+    Camera,
+    Charset,
+    /// ASCII, block glyphs, braille or edges
+    Mode,
+    Color,
+    Scale,
+    Curve,
+    Gamma,
+    Invert,
+    Effect,
+    Preset,
+    Filter,
+    Rotation,
+    Fps,
+    /// Frames per second the camera delivers
+    CaptureFps,
+    /// Frames per second drawn to the terminal
+    RenderFps,
+    /// App ticks per second
+    TickRate,
+    Resolution,
+    Grid,
+    Msg,
+}
+
+impl StatusField {
+    fn from_token(token: &str) -> Option<StatusField> {
+        Some(match token {
+            "camera" => StatusField::Camera,
+            "charset" => StatusField::Charset,
+            "mode" => StatusField::Mode,
+            "color" => StatusField::Color,
+            "scale" => StatusField::Scale,
+            "curve" => StatusField::Curve,
+            "gamma" => StatusField::Gamma,
+            "invert" => StatusField::Invert,
+            "effect" => StatusField::Effect,
+            "preset" => StatusField::Preset,
+            "filter" => StatusField::Filter,
+            "rotation" => StatusField::Rotation,
+            "fps" => StatusField::Fps,
+            "capture_fps" => StatusField::CaptureFps,
+            "render_fps" => StatusField::RenderFps,
+            "tick_rate" => StatusField::TickRate,
+            "resolution" => StatusField::Resolution,
+            "grid" => StatusField::Grid,
+            "msg" => StatusField::Msg,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(StatusField),
+}
+
+/// Status bar layout parsed from a format string like `"{camera} | {fps} fps | {msg}"`
+///
+/// Tokens are field names in braces, `{{` and `}}` are literal braces. Unknown tokens are
+/// dropped with a warning so a typo doesn't break the status bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTemplate {
+    segments: Vec<Segment>,
+}
+
+impl Default for StatusTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_STATUS_FORMAT)
+    }
+}
+
+impl StatusTemplate {
+    pub fn parse(format: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut token = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        token.push(c);
+                    }
+                    if !closed {
+                        // Not a token after all, keep the text as typed
+                        literal.push('{');
+                        literal.push_str(&token);
+                        continue;
+                    }
+                    match StatusField::from_token(token.trim()) {
+                        Some(field) => {
+                            if !literal.is_empty() {
+                                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                            }
+                            segments.push(Segment::Field(field));
+                        }
+                        None => warn!("Unknown status bar token {{{}}}, ignoring it", token),
+                    }
+                }
+                _ => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Self { segments }
+    }
+
+    /// Fill in the fields; ones without a value render as nothing
+    pub fn render(&self, values: &HashMap<StatusField, String>) -> String {
+        self.layout(values).0
+    }
+
+    /// Fill in the fields like [`render`](Self::render), also returning the columns each
+    /// field's value covers so clicks and scrolls can be routed to it
+    ///
+    /// Columns are terminal cells, so wide glyphs count twice and combining marks not at all.
+    pub fn layout(
+        &self,
+        values: &HashMap<StatusField, String>,
+    ) -> (String, Vec<(StatusField, Range<usize>)>) {
+        let mut text = String::new();
+        let mut regions = Vec::new();
+        let mut column = 0;
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => {
+                    text.push_str(literal);
+                    column += literal.width();
+                }
+                Segment::Field(field) => {
+                    if let Some(value) = values.get(field) {
+                        text.push_str(value);
+                        let start = column;
+                        column += value.width();
+                        regions.push((*field, start..column));
+                    }
+                }
+            }
+        }
+        (text, regions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_renders_known_fields_and_drops_unknown() {
+        let template = StatusTemplate::parse("{camera} | {nope}{fps} fps {{raw}} | {msg}");
+        let values = HashMap::from([
+            (StatusField::Camera, "ON".to_string()),
+            (StatusField::Fps, "20.0".to_string()),
+            (StatusField::Msg, "Ready".to_string()),
+        ]);
+        assert_eq!(template.render(&values), "ON | 20.0 fps {raw} | Ready");
+
+        // Unterminated tokens stay literal and fields without a value render empty
+        let template = StatusTemplate::parse("{grid} {camera");
+        assert_eq!(template.render(&values), " {camera");
+    }
+
+    #[test]
+    fn test_layout_reports_field_columns() {
+        let template = StatusTemplate::parse("Scale: {scale} | {grid}{gamma} γ | {camera} | {msg}");
+        let values = HashMap::from([
+            (StatusField::Scale, "1.0x".to_string()),
+            (StatusField::Gamma, "2.20".to_string()),
+            (StatusField::Camera, "カメラ e\u{301}".to_string()),
+            (StatusField::Msg, "Ready".to_string()),
+        ]);
+        let (text, regions) = template.layout(&values);
+        assert_eq!(text, "Scale: 1.0x | 2.20 γ | カメラ e\u{301} | Ready");
+        // Columns are display cells: the katakana take two each and the combining accent
+        // none, and fields without a value have no region
+        assert_eq!(
+            regions,
+            vec![
+                (StatusField::Scale, 7..11),
+                (StatusField::Gamma, 14..18),
+                (StatusField::Camera, 23..31),
+                (StatusField::Msg, 34..39),
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use futures::Stream;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, info};
+
+use crate::{
+    action::Action,
+    ascii::{AsciiConverter, ColoredChar},
+    camera::CameraCapture,
+    config::CameraConfig,
+    threads::{JOIN_TIMEOUT, join_within},
+};
+
+/// Converted frames buffered for a slow reader before new ones are dropped
+const STREAM_QUEUE: usize = 2;
+
+/// One converted camera frame
+#[derive(Debug, Clone)]
+pub struct AsciiFrame {
+    pub grid: Vec<Vec<ColoredChar>>,
+    /// Size of the camera frame it was converted from
+    pub source_width: u32,
+    pub source_height: u32,
+    /// When the camera captured the frame
+    pub captured: Instant,
+    /// Frames converted before this one; gaps mean the reader fell behind and missed some
+    pub sequence: u64,
+}
+
+/// Converted camera frames as an async [`Stream`], for programs that want the ASCII output
+/// without the TUI
+///
+/// ```no_run
+/// # async fn run() -> color_eyre::Result<()> {
+/// use ascii_vision::{ascii::AsciiConverter, config::CameraConfig, stream::FrameStream};
+/// use futures::StreamExt;
+///
+/// let camera = CameraConfig { fps: 30.0, width: 640, height: 480, ..Default::default() };
+/// let mut frames = FrameStream::open(&camera, AsciiConverter::new_dense(80, 24));
+/// while let Some(frame) = frames.next().await {
+///     let frame = frame?;
+///     println!("{} rows", frame.grid.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The camera and the converter are owned by a capture thread, so neither has to be `Send`
+/// across awaits and conversion never blocks the runtime. The camera opens when the stream is
+/// created and closes when it is dropped. Failing to open ends the stream after a single
+/// `Err` item; a failed capture yields an `Err` and the stream carries on, until so many fail
+/// in a row that the camera counts as unplugged, which ends it with a final `Err`. A reader
+/// that falls behind misses frames instead of building up a backlog.
+pub struct FrameStream {
+    rx: mpsc::Receiver<Result<AsciiFrame>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FrameStream {
+    /// Open the camera from `camera` and start converting its frames with `converter`
+    pub fn open(camera: &CameraConfig, converter: AsciiConverter) -> Self {
+        let (tx, rx) = mpsc::channel(STREAM_QUEUE);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let camera = camera.clone();
+            let stop = stop.clone();
+            thread::spawn(move || run_capture(camera, converter, tx, stop))
+        };
+        Self {
+            rx,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Stop capturing and close the camera, waiting for the capture thread to finish
+    ///
+    /// A thread still stuck on a camera read after [`JOIN_TIMEOUT`] is left to close the
+    /// camera on its own once the read returns.
+    pub fn close(mut self) {
+        self.shut_down();
+    }
+
+    fn shut_down(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.rx.close();
+        if let Some(thread) = self.thread.take() {
+            join_within(thread, JOIN_TIMEOUT, "Frame stream capture");
+        }
+    }
+}
+
+impl Stream for FrameStream {
+    type Item = Result<AsciiFrame>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for FrameStream {
+    fn drop(&mut self) {
+        self.shut_down();
+    }
+}
+
+fn run_capture(
+    config: CameraConfig,
+    mut converter: AsciiConverter,
+    tx: mpsc::Sender<Result<AsciiFrame>>,
+    stop: Arc<AtomicBool>,
+) {
+    let (frame_tx, mut frame_rx) = mpsc::unbounded_channel();
+    let mut camera = CameraCapture::new(config.backend);
+    camera.set_yuyv(config.yuyv);
+    camera.set_target_fps(config.fps);
+    let opened = camera
+        .initialize(
+            config.default_camera_index,
+            config.width,
+            config.height,
+            config.fps.round() as u32,
+            config.resolution_request,
+            frame_tx,
+        )
+        .and_then(|()| camera.start());
+    if let Err(e) = opened {
+        let _ = tx.blocking_send(Err(e));
+        return;
+    }
+    info!("Frame stream started");
+
+    let interval = Duration::from_secs_f64(1.0 / config.fps.max(1.0));
+    let mut sequence = 0;
+    while !stop.load(Ordering::Relaxed) {
+        let started = Instant::now();
+        let mut latest = None;
+        let result = camera.capture_frame();
+        while let Ok(action) = frame_rx.try_recv() {
+            if let Action::CameraFrame(data, width, height, captured) = action {
+                latest = Some((data, width, height, captured));
+            }
+        }
+        let item = match (result, latest) {
+            (Err(e), _) => Some(Err(e)),
+            (Ok(()), Some((data, width, height, captured))) => {
+                let frame = AsciiFrame {
+                    grid: converter.convert_rgb_frame_direct(&data, width, height),
+                    source_width: width,
+                    source_height: height,
+                    captured,
+                    sequence,
+                };
+                sequence += 1;
+                Some(Ok(frame))
+            }
+            (Ok(()), None) => None,
+        };
+        if let Some(item) = item {
+            match tx.try_send(item) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => debug!("Frame stream reader is behind"),
+                Err(TrySendError::Closed(_)) => break,
+            }
+        }
+        if camera.is_disconnected() {
+            let _ = tx.blocking_send(Err(eyre!("Camera disconnected")));
+            break;
+        }
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+    camera.stop();
+    info!("Frame stream stopped");
+}
@@ -0,0 +1,84 @@
+use std::env;
+
+/// Color depth the terminal advertises through its environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorSupport {
+    /// Inspect `COLORTERM` and `TERM` of the current process
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Classify a terminal from its `COLORTERM` and `TERM` values
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or_default().to_lowercase();
+        let term = term.unwrap_or_default().to_lowercase();
+
+        if matches!(colorterm.as_str(), "truecolor" | "24bit")
+            || term.contains("truecolor")
+            || term.contains("24bit")
+            || term.contains("direct")
+        {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorSupport::TrueColor => "truecolor",
+            ColorSupport::Ansi256 => "256 colors",
+            ColorSupport::Ansi16 => "16 colors",
+        }
+    }
+}
+
+/// Whether the terminal advertises 24-bit color, which colored ASCII output relies on
+///
+/// tmux and SSH sessions commonly drop `COLORTERM`, so this can be false even when the
+/// outer terminal supports truecolor.
+#[allow(dead_code)]
+pub fn terminal_supports_truecolor() -> bool {
+    ColorSupport::detect() == ColorSupport::TrueColor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_support_from_env() {
+        assert_eq!(
+            ColorSupport::from_env(Some("truecolor"), Some("xterm-256color")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(Some("24bit"), None),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm-direct")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("screen-256color")),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm")),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(ColorSupport::from_env(None, None), ColorSupport::Ansi16);
+    }
+}
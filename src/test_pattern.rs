@@ -0,0 +1,119 @@
+use std::time::Instant;
+
+/// Color bars, from brightest to darkest luma
+const BARS: [[u8; 3]; 7] = [
+    [255, 255, 255], // White
+    [255, 255, 0],   // Yellow
+    [0, 255, 255],   // Cyan
+    [0, 255, 0],     // Green
+    [255, 0, 255],   // Magenta
+    [255, 0, 0],     // Red
+    [0, 0, 255],     // Blue
+];
+
+/// Procedurally generated, animated RGB source for running without a camera
+///
+/// The top two thirds show color bars, the bottom third a scrolling grayscale
+/// gradient, and a white square bounces across the whole frame.
+pub struct TestPattern {
+    width: u32,
+    height: u32,
+    started: Instant,
+}
+
+impl TestPattern {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Render the pattern for the current time as raw RGB data
+    pub fn frame(&self) -> Vec<u8> {
+        self.render(self.started.elapsed().as_secs_f32())
+    }
+
+    /// Render the pattern at `t` seconds as raw RGB data
+    pub fn render(&self, t: f32) -> Vec<u8> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut data = vec![0u8; width * height * 3];
+        let bars_height = height * 2 / 3;
+
+        // Bouncing square, a tenth of the frame height, moving along both axes
+        let size = (height / 10).max(1);
+        let bounce = |range: usize, speed: f32| {
+            let range = range.saturating_sub(size).max(1) as f32;
+            let phase = (t * speed) % (2.0 * range);
+            (if phase > range {
+                2.0 * range - phase
+            } else {
+                phase
+            }) as usize
+        };
+        let square_x = bounce(width, width as f32 * 0.4);
+        let square_y = bounce(height, height as f32 * 0.3);
+
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = if (square_x..square_x + size).contains(&x)
+                    && (square_y..square_y + size).contains(&y)
+                {
+                    [255, 255, 255]
+                } else if y < bars_height {
+                    BARS[x * BARS.len() / width]
+                } else {
+                    // Gradient scrolls one frame width every four seconds
+                    let offset = (t * width as f32 / 4.0) as usize;
+                    let level = ((x + offset) % width * 255 / width.max(1)) as u8;
+                    [level, level, level]
+                };
+                let index = (y * width + x) * 3;
+                data[index..index + 3].copy_from_slice(&pixel);
+            }
+        }
+
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_draws_bars_gradient_and_a_moving_square() {
+        let pattern = TestPattern::new(70, 30);
+        let frame = pattern.render(0.0);
+        assert_eq!(frame.len(), 70 * 30 * 3);
+        let pixel = |frame: &[u8], x: usize, y: usize| {
+            let index = (y * 70 + x) * 3;
+            [frame[index], frame[index + 1], frame[index + 2]]
+        };
+
+        // Bars across the top two thirds, brightest on the left
+        assert_eq!(pixel(&frame, 15, 10), BARS[1]);
+        assert_eq!(pixel(&frame, 69, 19), BARS[6]);
+        // A gray gradient below, getting lighter to the right
+        let (left, right) = (pixel(&frame, 10, 25), pixel(&frame, 60, 25));
+        assert!(left[0] == left[1] && left[1] == left[2]);
+        assert!(left[0] < right[0]);
+        // The white square starts in the top left corner and moves across the bars
+        assert_eq!(pixel(&frame, 0, 0), [255, 255, 255]);
+        assert_eq!(pixel(&frame, 29, 10), BARS[2]);
+        assert_eq!(pixel(&pattern.render(1.0), 29, 10), [255, 255, 255]);
+
+        let empty = TestPattern::new(0, 0);
+        assert_eq!((empty.width(), empty.height()), (1, 1));
+        assert_eq!(empty.render(0.0).len(), 3);
+    }
+}
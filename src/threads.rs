@@ -0,0 +1,53 @@
+//! Stopping helper threads without letting one stuck on a camera read or a pipe hang the
+//! owner's drop.
+
+use std::{
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tracing::{error, warn};
+
+/// How long an owner waits for a thread it signalled to stop
+pub const JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often a pending join checks on the thread
+const JOIN_POLL: Duration = Duration::from_millis(10);
+
+/// Wait up to `timeout` for a thread that was told to stop, then leave it running detached
+///
+/// Returns whether it finished. A thread left behind ends on its own once whatever blocks it
+/// returns, or with the process.
+pub fn join_within(thread: JoinHandle<()>, timeout: Duration, name: &str) -> bool {
+    let deadline = Instant::now() + timeout;
+    while !thread.is_finished() {
+        if Instant::now() >= deadline {
+            warn!("{name} thread didn't stop within {timeout:?}, leaving it behind");
+            return false;
+        }
+        thread::sleep(JOIN_POLL);
+    }
+    if thread.join().is_err() {
+        error!("{name} thread panicked");
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_join_within_leaves_a_stuck_thread_behind() {
+        let (tx, rx) = mpsc::channel::<()>();
+        let stuck = thread::spawn(move || {
+            let _ = rx.recv();
+        });
+        assert!(!join_within(stuck, Duration::from_millis(20), "Stuck"));
+        drop(tx);
+
+        assert!(join_within(thread::spawn(|| {}), JOIN_TIMEOUT, "Quick"));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::action::Action;
+
+/// Limits for typed-in values, matching what the setters accept
+const SCALE_RANGE: (f32, f32) = (0.1, 2.0);
+const GAMMA_RANGE: (f32, f32) = (0.1, 5.0);
+const FPS_RANGE: (u32, u32) = (1, 120);
+/// Smallest and largest capture width or height
+const RESOLUTION_RANGE: (u32, u32) = (16, 7680);
+
+/// A setting that can be typed in exactly instead of stepped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueField {
+    Scale,
+    Gamma,
+    Fps,
+    Resolution,
+}
+
+impl ValueField {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueField::Scale => "Scale",
+            ValueField::Gamma => "Gamma",
+            ValueField::Fps => "Target FPS",
+            ValueField::Resolution => "Resolution",
+        }
+    }
+
+    /// Accepted input, shown in the prompt
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ValueField::Scale => "0.1-2.0",
+            ValueField::Gamma => "0.1-5.0",
+            ValueField::Fps => "1-120",
+            ValueField::Resolution => "WIDTHxHEIGHT",
+        }
+    }
+
+    /// Whether `c` can be part of a value for this field; anything else is swallowed
+    pub fn accepts(&self, c: char) -> bool {
+        c.is_ascii_digit()
+            || match self {
+                ValueField::Scale | ValueField::Gamma => c == '.',
+                ValueField::Fps => false,
+                ValueField::Resolution => c == 'x',
+            }
+    }
+
+    /// Parse typed text into the action setting it, plus a note when the value was clamped
+    pub fn parse(&self, text: &str) -> Result<(Action, Option<String>), String> {
+        let text = text.trim();
+        match self {
+            ValueField::Scale | ValueField::Gamma => {
+                let value: f32 = text
+                    .parse()
+                    .ok()
+                    .filter(|v: &f32| v.is_finite())
+                    .ok_or_else(|| format!("'{text}' is not a number"))?;
+                let (min, max) = match self {
+                    ValueField::Scale => SCALE_RANGE,
+                    _ => GAMMA_RANGE,
+                };
+                let clamped = value.clamp(min, max);
+                let action = match self {
+                    ValueField::Scale => Action::SetScale(clamped),
+                    _ => Action::SetGamma(clamped),
+                };
+                Ok((action, clamp_note(text, value != clamped, self.hint())))
+            }
+            ValueField::Fps => {
+                let value = parse_whole(text)?;
+                let clamped = value.clamp(FPS_RANGE.0, FPS_RANGE.1);
+                Ok((
+                    Action::SetTargetFps(clamped),
+                    clamp_note(text, value != clamped, self.hint()),
+                ))
+            }
+            ValueField::Resolution => {
+                let (width, height) = text
+                    .split_once('x')
+                    .ok_or_else(|| format!("'{text}' is not WIDTHxHEIGHT"))?;
+                let (width, height) = (parse_whole(width)?, parse_whole(height)?);
+                let (min, max) = RESOLUTION_RANGE;
+                let clamped = (width.clamp(min, max), height.clamp(min, max));
+                Ok((
+                    Action::SetResolution(clamped.0, clamped.1),
+                    clamp_note(text, (width, height) != clamped, &format!("{min}-{max}")),
+                ))
+            }
+        }
+    }
+}
+
+fn parse_whole(text: &str) -> Result<u32, String> {
+    text.parse()
+        .map_err(|_| format!("'{text}' is not a whole number"))
+}
+
+fn clamp_note(text: &str, clamped: bool, range: &str) -> Option<String> {
+    clamped.then(|| format!("{text} is outside {range}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clamps_and_rejects() {
+        assert_eq!(
+            ValueField::Scale.parse("1.5"),
+            Ok((Action::SetScale(1.5), None))
+        );
+        let (action, note) = ValueField::Gamma.parse("9").unwrap();
+        assert_eq!(action, Action::SetGamma(5.0));
+        assert!(note.is_some());
+        assert_eq!(
+            ValueField::Resolution.parse("1280x720"),
+            Ok((Action::SetResolution(1280, 720), None))
+        );
+        assert_eq!(
+            ValueField::Fps.parse("500").unwrap().0,
+            Action::SetTargetFps(120)
+        );
+
+        assert!(ValueField::Scale.parse("").is_err());
+        assert!(ValueField::Scale.parse("1.2.3").is_err());
+        assert!(ValueField::Resolution.parse("1280").is_err());
+        assert!(!ValueField::Fps.accepts('.'));
+    }
+}
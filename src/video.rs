@@ -0,0 +1,278 @@
+//! Video files as a frame source: an `ffmpeg` child process decodes to raw RGB on a pipe,
+//! and a reader thread paces the frames out at the file's own frame rate.
+//!
+//! Nothing is linked in for decoding, so playing videos needs `ffmpeg` and `ffprobe` on the
+//! `PATH`; the rest of the app works without them.
+
+use std::{
+    ffi::OsStr,
+    io::Read,
+    path::Path,
+    process::{Child, ChildStdout, Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info, warn};
+
+use crate::{
+    action::Action,
+    threads::{JOIN_TIMEOUT, join_within},
+};
+
+/// How often a paused reader checks whether to carry on
+const PAUSE_POLL: Duration = Duration::from_millis(50);
+
+/// Stream facts read by `ffprobe`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Native frame rate, if the file states a usable one
+    pub fps: Option<f64>,
+}
+
+/// Parse `ffprobe -of default=noprint_wrappers=1` output for the first video stream
+pub fn parse_probe(output: &str) -> Option<VideoInfo> {
+    let mut width = None;
+    let mut height = None;
+    let mut fps = None;
+    for line in output.lines() {
+        match line.trim().split_once('=') {
+            Some(("width", value)) => width = value.parse().ok(),
+            Some(("height", value)) => height = value.parse().ok(),
+            Some(("r_frame_rate", value)) => fps = parse_frame_rate(value),
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => {
+            Some(VideoInfo { width, height, fps })
+        }
+        _ => None,
+    }
+}
+
+/// Frame rate from ffprobe's `num/den` form, e.g. `30000/1001`; `0/0` means unknown
+fn parse_frame_rate(value: &str) -> Option<f64> {
+    let (num, den) = value.split_once('/')?;
+    let fps = num.trim().parse::<f64>().ok()? / den.trim().parse::<f64>().ok()?;
+    (fps.is_finite() && fps > 0.0).then_some(fps)
+}
+
+/// Decodes a video file in a loop, or another `ffmpeg` input such as the screen, sending
+/// every frame as [`Action::CameraFrame`]
+///
+/// The `ffmpeg` process and the reader thread stop when the player is dropped. While paused
+/// the reader stops pulling frames, so `ffmpeg` blocks on the full pipe instead of decoding
+/// ahead.
+pub struct VideoPlayer {
+    child: Child,
+    paused: Arc<AtomicBool>,
+    /// Another source has taken over for now; kept apart from the user's pause
+    held: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl VideoPlayer {
+    /// Probe `path`, start decoding it and send frames at its native rate, or `fallback_fps`
+    /// when the file doesn't state one
+    pub fn open(path: &Path, fallback_fps: f64, tx: UnboundedSender<Action>) -> Result<Self> {
+        let mut info = probe(path)?;
+        if info.fps.is_none() {
+            warn!(
+                "{} has no usable frame rate, playing at {} fps",
+                path.display(),
+                fallback_fps
+            );
+            info.fps = Some(fallback_fps.max(1.0));
+        }
+        let mut command = Command::new("ffmpeg");
+        command
+            .args([
+                "-v",
+                "error",
+                "-nostdin",
+                "-stream_loop",
+                "-1",
+                "-noautorotate",
+                "-i",
+            ])
+            .arg(path)
+            .args(["-map", "0:v:0"]);
+        Self::spawn(command, info, &path.display().to_string(), tx)
+    }
+
+    /// Run an `ffmpeg` command whose input is set up already, reading its output as raw RGB
+    /// frames of `info`'s size and sending them at `info.fps`
+    pub(crate) fn spawn(
+        mut command: Command,
+        info: VideoInfo,
+        name: &str,
+        tx: UnboundedSender<Action>,
+    ) -> Result<Self> {
+        let mut child = command
+            .args(["-f", "rawvideo", "-pix_fmt", "rgb24", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            // Anything ffmpeg prints would land on top of the TUI
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| eyre!("Failed to start ffmpeg for {name}: {e}"))?;
+        let Some(stdout) = child.stdout.take() else {
+            let _ = child.kill();
+            return Err(eyre!("ffmpeg gave no output pipe"));
+        };
+        info!(
+            "Playing {} ({}x{} at {:.2} fps)",
+            name,
+            info.width,
+            info.height,
+            info.fps.unwrap_or_default()
+        );
+
+        let paused = Arc::new(AtomicBool::new(false));
+        let held = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (paused, held, stop) = (paused.clone(), held.clone(), stop.clone());
+            thread::spawn(move || run_reader(stdout, info, tx, paused, held, stop))
+        };
+        Ok(Self {
+            child,
+            paused,
+            held,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Pause or resume, returning whether it is now paused
+    pub fn toggle_pause(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    /// Stop sending frames while another source is shown, leaving the pause state alone
+    pub fn set_held(&self, held: bool) {
+        self.held.store(held, Ordering::Relaxed);
+    }
+}
+
+impl Drop for VideoPlayer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Killing ffmpeg closes the pipe, which ends a reader blocked on it
+        if let Err(e) = self.child.kill() {
+            debug!("ffmpeg already exited: {e}");
+        }
+        if let Some(thread) = self.thread.take() {
+            join_within(thread, JOIN_TIMEOUT, "Video reader");
+        }
+        let _ = self.child.wait();
+    }
+}
+
+fn probe(path: &Path) -> Result<VideoInfo> {
+    probe_input([path.as_os_str()], &path.display().to_string())
+}
+
+/// Ask `ffprobe` about the first video stream of an input given as `ffprobe` arguments,
+/// e.g. a file path or a capture device with its `-f` format
+pub(crate) fn probe_input<I, S>(input: I, name: &str) -> Result<VideoInfo>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=width,height,r_frame_rate"])
+        .args(["-of", "default=noprint_wrappers=1"])
+        .args(input)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| eyre!("Failed to run ffprobe, is ffmpeg installed? {e}"))?;
+    if !output.status.success() {
+        let reason = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("ffprobe could not read {}: {}", name, reason.trim()));
+    }
+    parse_probe(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| eyre!("No video stream in {}", name))
+}
+
+fn run_reader(
+    mut stdout: ChildStdout,
+    info: VideoInfo,
+    tx: UnboundedSender<Action>,
+    paused: Arc<AtomicBool>,
+    held: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+) {
+    let interval = Duration::from_secs_f64(1.0 / info.fps.unwrap_or(30.0));
+    let mut frame = vec![0u8; info.width as usize * info.height as usize * 3];
+    let mut next_due = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        if paused.load(Ordering::Relaxed) || held.load(Ordering::Relaxed) {
+            thread::sleep(PAUSE_POLL);
+            // Carry on at the normal pace rather than catching up
+            next_due = Instant::now();
+            continue;
+        }
+        if let Err(e) = stdout.read_exact(&mut frame) {
+            debug!("Video ended: {e}");
+            break;
+        }
+        if tx
+            .send(Action::CameraFrame(
+                frame.clone(),
+                info.width,
+                info.height,
+                Instant::now(),
+            ))
+            .is_err()
+        {
+            break;
+        }
+        next_due += interval;
+        let now = Instant::now();
+        if next_due > now {
+            thread::sleep(next_due - now);
+        } else if now - next_due > interval * 4 {
+            // Fell well behind, e.g. after a slow decode; don't rush to make up for it
+            next_due = now;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probe() {
+        let info = parse_probe("width=1280\nheight=720\nr_frame_rate=30000/1001\n").unwrap();
+        assert_eq!((info.width, info.height), (1280, 720));
+        assert!((info.fps.unwrap() - 29.97).abs() < 0.01);
+
+        // Order doesn't matter, an unknown rate is left for the caller
+        let info = parse_probe("r_frame_rate=0/0\nheight=480\nwidth=640").unwrap();
+        assert_eq!(
+            info,
+            VideoInfo {
+                width: 640,
+                height: 480,
+                fps: None
+            }
+        );
+
+        // Audio-only files have no video stream to report
+        assert_eq!(parse_probe(""), None);
+        assert_eq!(parse_probe("width=0\nheight=480"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Frame conversion on a worker thread, so converting a large frame doesn't hold up the event
+//! loop. The worker hands each grid back as [`Action::AsciiFrameReady`].
+
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, error};
+
+use crate::{action::Action, ascii::AsciiConverter, frame::SharedGrid, yuyv};
+
+/// Byte layout of a source frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameLayout {
+    Rgb,
+    /// Undecoded camera YUYV, sent when only luma is needed
+    Yuyv,
+}
+
+/// A frame to convert, with the settings to convert it with
+#[derive(Debug)]
+pub struct ConversionJob {
+    /// A copy of the live settings; the worker keeps its own phosphor trails and smoothing
+    /// history across jobs
+    pub converter: AsciiConverter,
+    pub layout: FrameLayout,
+    pub frame: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// When the source captured the frame
+    pub captured: Instant,
+}
+
+/// A converted frame coming back from the worker
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertedFrame {
+    pub grid: SharedGrid,
+    /// The RGB frame it came from, empty when it was converted straight from luma
+    pub rgb: Arc<Vec<u8>>,
+    pub width: u32,
+    pub height: u32,
+    pub captured: Instant,
+}
+
+#[derive(Default)]
+struct Mailbox {
+    /// The next frame to convert; a newer one replaces it, so stale frames are dropped
+    job: Option<ConversionJob>,
+    closed: bool,
+}
+
+/// Converts frames on its own thread, one at a time, always the latest one submitted
+pub struct ConversionWorker {
+    mailbox: Arc<(Mutex<Mailbox>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConversionWorker {
+    /// Start the worker thread, sending converted frames to `tx`
+    pub fn spawn(tx: UnboundedSender<Action>) -> Self {
+        let mailbox = Arc::new((Mutex::new(Mailbox::default()), Condvar::new()));
+        let thread = {
+            let mailbox = mailbox.clone();
+            thread::Builder::new()
+                .name("ascii-conversion".to_string())
+                .spawn(move || run(&mailbox, &tx))
+                .map_err(|e| error!("Failed to start the conversion thread: {}", e))
+                .ok()
+        };
+        Self { mailbox, thread }
+    }
+
+    /// Whether the thread is running; without it nothing submitted is converted
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Queue a frame, replacing one still waiting; returns whether one was dropped
+    pub fn submit(&self, job: ConversionJob) -> bool {
+        let (mailbox, ready) = &*self.mailbox;
+        let mut mailbox = mailbox.lock().unwrap_or_else(|e| e.into_inner());
+        let dropped = mailbox.job.replace(job).is_some();
+        ready.notify_one();
+        dropped
+    }
+}
+
+impl Drop for ConversionWorker {
+    fn drop(&mut self) {
+        let (mailbox, ready) = &*self.mailbox;
+        mailbox.lock().unwrap_or_else(|e| e.into_inner()).closed = true;
+        ready.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run(mailbox: &(Mutex<Mailbox>, Condvar), tx: &UnboundedSender<Action>) {
+    let (mailbox, ready) = mailbox;
+    let mut previous: Option<AsciiConverter> = None;
+    loop {
+        let job = {
+            let mut mailbox = mailbox.lock().unwrap_or_else(|e| e.into_inner());
+            loop {
+                if mailbox.closed {
+                    return;
+                }
+                if let Some(job) = mailbox.job.take() {
+                    break job;
+                }
+                mailbox = ready.wait(mailbox).unwrap_or_else(|e| e.into_inner());
+            }
+        };
+        let mut converter = job.converter;
+        if let Some(mut previous) = previous.take() {
+            converter.take_frame_state(&mut previous);
+        }
+        let (grid, rgb) = match job.layout {
+            FrameLayout::Yuyv if converter.accepts_luma() => (
+                converter.convert_yuyv_frame(&job.frame, job.width, job.height),
+                Vec::new(),
+            ),
+            FrameLayout::Yuyv => {
+                let rgb = yuyv::to_rgb(&job.frame);
+                let grid = converter.convert_rgb_frame_direct(&rgb, job.width, job.height);
+                (grid, rgb)
+            }
+            FrameLayout::Rgb => {
+                let grid = converter.convert_rgb_frame_direct(&job.frame, job.width, job.height);
+                (grid, job.frame)
+            }
+        };
+        previous = Some(converter);
+        let frame = ConvertedFrame {
+            grid: Arc::new(grid),
+            rgb: Arc::new(rgb),
+            width: job.width,
+            height: job.height,
+            captured: job.captured,
+        };
+        if tx.send(Action::AsciiFrameReady(frame)).is_err() {
+            debug!("Nothing receives converted frames, stopping the conversion thread");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn job(value: u8) -> ConversionJob {
+        ConversionJob {
+            converter: AsciiConverter::new_dense(4, 2),
+            layout: FrameLayout::Rgb,
+            frame: vec![value; 4 * 2 * 3],
+            width: 4,
+            height: 2,
+            captured: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_worker_converts_the_latest_frame() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let worker = ConversionWorker::spawn(tx);
+        assert!(worker.is_running());
+        for value in [0, 64, 128, 255] {
+            worker.submit(job(value));
+        }
+        // Frames waiting behind a newer one may be dropped, but the newest always arrives
+        let mut received = 0;
+        loop {
+            let Some(Action::AsciiFrameReady(frame)) = rx.blocking_recv() else {
+                panic!("expected a converted frame");
+            };
+            received += 1;
+            assert_eq!((frame.width, frame.height, frame.grid.len()), (4, 2, 2));
+            if frame.rgb.first() == Some(&255) {
+                break;
+            }
+        }
+        assert!(received <= 4);
+
+        // Dropping the worker stops its thread and closes the channel
+        drop(worker);
+        assert_eq!(rx.blocking_recv(), None);
+    }
+}
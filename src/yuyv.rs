@@ -0,0 +1,65 @@
+//! YUYV (YUV 4:2:2) frames as cameras deliver them: each pair of pixels is four bytes,
+//! `Y0 U Y1 V`, sharing one chroma sample.
+//!
+//! The luma bytes alone are enough for monochrome output, which skips the RGB decode.
+
+/// Bytes a YUYV frame of this size takes
+pub fn frame_len(width: u32, height: u32) -> usize {
+    width as usize * height as usize * 2
+}
+
+/// Display brightness of a luma byte, expanding the BT.601 studio range (16-235) to 0-255
+///
+/// This is exactly what decoding a gray pixel with [`to_rgb`] gives, so both paths pick the
+/// same characters.
+pub fn luma(y: u8) -> u8 {
+    ((298 * (y as i32 - 16) + 128) >> 8).clamp(0, 255) as u8
+}
+
+/// Luma byte of the pixel at `index`
+pub fn luma_at(frame: &[u8], index: usize) -> Option<u8> {
+    frame.get(index * 2).copied()
+}
+
+/// Decode to packed RGB with the BT.601 studio-range integer formulas nokhwa uses
+pub fn to_rgb(frame: &[u8]) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(frame.len() / 2 * 3);
+    for pair in frame.chunks_exact(4) {
+        let [y0, u, y1, v] = [pair[0], pair[1], pair[2], pair[3]];
+        let (d, e) = (u as i32 - 128, v as i32 - 128);
+        for y in [y0, y1] {
+            let c = 298 * (y as i32 - 16);
+            let channel = |value: i32| ((value + 128) >> 8).clamp(0, 255) as u8;
+            rgb.extend_from_slice(&[
+                channel(c + 409 * e),
+                channel(c - 100 * d - 208 * e),
+                channel(c + 516 * d),
+            ]);
+        }
+    }
+    rgb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gray_decodes_to_its_luma() {
+        for y in [0, 16, 17, 100, 128, 235, 255] {
+            assert_eq!(to_rgb(&[y, 128, y, 128]), vec![luma(y); 6]);
+        }
+        assert_eq!((luma(16), luma(235)), (0, 255));
+    }
+
+    #[test]
+    fn test_decode_colors_and_layout() {
+        // Studio-range red followed by a black pixel sharing its chroma
+        let rgb = to_rgb(&[81, 90, 16, 240]);
+        assert_eq!(&rgb[..3], &[255, 0, 0]);
+        assert_eq!(rgb.len(), 6);
+        assert_eq!(luma_at(&[81, 90, 16, 240], 1), Some(16));
+        assert_eq!(luma_at(&[81, 90, 16, 240], 2), None);
+        assert_eq!(frame_len(640, 480), 640 * 480 * 2);
+    }
+}
@@ -0,0 +1,157 @@
+//! Digital zoom: a region of the picture that the converter spreads over the whole grid
+//!
+//! The region is kept in fractions of the rotated picture, so it survives resolution and
+//! rotation changes, and only becomes pixels when a frame is sampled.
+
+/// Largest digital zoom, where the region is an eighth of the frame across
+pub const MAX_ZOOM: f32 = 8.0;
+
+/// Zoom factor per zoom step
+pub const ZOOM_STEP: f32 = 1.25;
+
+/// Fraction of the region's size moved per pan step
+pub const PAN_STEP: f32 = 0.1;
+
+/// Region of interest in the picture, as fractions of its width and height from the top left
+///
+/// Only this part of a frame is converted, stretched over the whole grid, for a digital zoom
+/// that leaves the camera alone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Roi {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Roi {
+    /// The whole picture
+    pub const FULL: Roi = Roi {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    /// Magnification over the whole picture
+    pub fn zoom(&self) -> f32 {
+        1.0 / self.width.max(self.height)
+    }
+
+    /// A step closer in around the center, up to [`MAX_ZOOM`]
+    pub fn zoom_in(&self) -> Roi {
+        self.zoomed((self.zoom() * ZOOM_STEP).min(MAX_ZOOM))
+    }
+
+    /// A step further out around the center, `None` once the whole picture shows
+    pub fn zoom_out(&self) -> Option<Roi> {
+        let zoom = self.zoom() / ZOOM_STEP;
+        (zoom > 1.001).then(|| self.zoomed(zoom))
+    }
+
+    /// This region's center at `zoom`, kept inside the picture
+    fn zoomed(&self, zoom: f32) -> Roi {
+        let size = 1.0 / zoom;
+        let (center_x, center_y) = (self.x + self.width / 2.0, self.y + self.height / 2.0);
+        Roi {
+            x: center_x - size / 2.0,
+            y: center_y - size / 2.0,
+            width: size,
+            height: size,
+        }
+        .clamped()
+    }
+
+    /// Moved by `dx`, `dy` steps of [`PAN_STEP`], stopping at the picture's edges
+    pub fn pan(&self, dx: f32, dy: f32) -> Roi {
+        Roi {
+            x: self.x + dx * PAN_STEP * self.width,
+            y: self.y + dy * PAN_STEP * self.height,
+            ..*self
+        }
+        .clamped()
+    }
+
+    /// Shrunk to fit the picture and moved back inside it
+    pub fn clamped(&self) -> Roi {
+        let width = self.width.clamp(1.0 / MAX_ZOOM, 1.0);
+        let height = self.height.clamp(1.0 / MAX_ZOOM, 1.0);
+        Roi {
+            x: self.x.clamp(0.0, 1.0 - width),
+            y: self.y.clamp(0.0, 1.0 - height),
+            width,
+            height,
+        }
+    }
+
+    /// Pixel rectangle `(x, y, width, height)` of the region in a `width` x `height` frame,
+    /// at least one pixel across
+    pub fn pixels(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let span = |start: f32, size: f32, total: u32| {
+            let first = ((start * total as f32).round() as u32).min(total.saturating_sub(1));
+            let count = ((size * total as f32).round() as u32).clamp(1, total - first);
+            (first, count)
+        };
+        let (x, crop_width) = span(self.x, self.width, width);
+        let (y, crop_height) = span(self.y, self.height, height);
+        (x, y, crop_width, crop_height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roi_zooms_around_center_and_pans_within_the_frame() {
+        let roi = Roi::FULL.zoom_in().zoom_in();
+        assert!((roi.zoom() - 1.5625).abs() < 1e-4);
+        assert!((roi.x + roi.width / 2.0 - 0.5).abs() < 1e-4);
+        assert_eq!(roi.zoom_out().and_then(|roi| roi.zoom_out()), None);
+
+        let mut max = Roi::FULL;
+        for _ in 0..20 {
+            max = max.zoom_in();
+        }
+        assert_eq!(max.zoom(), MAX_ZOOM);
+
+        // Panning stops at the edges
+        let mut left = roi;
+        for _ in 0..50 {
+            left = left.pan(-1.0, 1.0);
+        }
+        assert_eq!((left.x, left.y), (0.0, 1.0 - left.height));
+        assert_eq!(left.width, roi.width);
+
+        // Zooming out near an edge keeps the region inside the frame
+        let near_edge = Roi {
+            x: 0.9,
+            y: 0.0,
+            width: 0.1,
+            height: 0.1,
+        };
+        let out = near_edge.zoom_out().unwrap();
+        assert!(out.x + out.width <= 1.0);
+    }
+
+    #[test]
+    fn test_roi_pixels_stay_inside_the_frame() {
+        let right_half = Roi {
+            x: 0.5,
+            y: 0.0,
+            width: 0.5,
+            height: 1.0,
+        };
+        assert_eq!(right_half.pixels(4, 2), (2, 0, 2, 2));
+        assert_eq!(Roi::FULL.pixels(1, 1), (0, 0, 1, 1));
+
+        // A region too small to cover a pixel still gets one
+        let sliver = Roi {
+            x: 0.99,
+            y: 0.99,
+            width: 0.01,
+            height: 0.01,
+        };
+        assert_eq!(sliver.pixels(10, 10), (9, 9, 1, 1));
+    }
+}
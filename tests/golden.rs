@@ -0,0 +1,295 @@
+//! Golden outputs for the conversion paths, so changes to the hot loops show up as exact
+//! grid differences
+//!
+//! Every converter here uses the Dense set (`@#S%?*+;:,. `), Stretch, rounding to the
+//! nearest character and no adjustments.
+//! When a change to the output is intended, update the expected grids by hand.
+
+use ascii_vision::{
+    ascii::{AsciiConverter, ColoredChar, FilterKind, IndexRounding, to_ansi_string},
+    blocks::CellMode,
+};
+use image::{DynamicImage, ImageBuffer, Rgb};
+use pretty_assertions::assert_eq;
+
+/// Raw RGB frame with each pixel from `pixel(x, y)`
+fn frame(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 3]) -> Vec<u8> {
+    let mut data = Vec::with_capacity((width * height * 3) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            data.extend_from_slice(&pixel(x, y));
+        }
+    }
+    data
+}
+
+fn image(width: u32, height: u32, data: &[u8]) -> DynamicImage {
+    DynamicImage::ImageRgb8(
+        ImageBuffer::<Rgb<u8>, _>::from_raw(width, height, data.to_vec()).unwrap(),
+    )
+}
+
+fn solid(width: u32, height: u32, color: [u8; 3]) -> Vec<u8> {
+    frame(width, height, |_, _| color)
+}
+
+/// Left to right from black to white, one step per pixel
+fn gradient(width: u32, height: u32) -> Vec<u8> {
+    frame(width, height, |x, _| [(x * 255 / (width - 1)) as u8; 3])
+}
+
+/// White and black squares of `size` pixels, white in the top-left corner
+fn checkerboard(width: u32, height: u32, size: u32) -> Vec<u8> {
+    frame(width, height, |x, y| {
+        if (x / size + y / size).is_multiple_of(2) {
+            [255; 3]
+        } else {
+            [0; 3]
+        }
+    })
+}
+
+/// Characters of a colored grid, one string per row
+fn chars(grid: &[Vec<ColoredChar>]) -> Vec<String> {
+    grid.iter()
+        .map(|line| line.iter().map(|cell| cell.ch).collect())
+        .collect()
+}
+
+/// Grids from every monochrome path: `convert_image`, `convert_rgb_frame`,
+/// `convert_image_colored` and `convert_rgb_frame_direct`
+fn all_paths(
+    converter: &mut AsciiConverter,
+    data: &[u8],
+    width: u32,
+    height: u32,
+) -> [Vec<String>; 4] {
+    let source = image(width, height, data);
+    [
+        converter.convert_image(&source),
+        converter.convert_rgb_frame(data, width, height),
+        chars(&converter.convert_image_colored(&source)),
+        chars(&converter.convert_rgb_frame_direct(data, width, height)),
+    ]
+}
+
+fn golden(rows: &[&str]) -> Vec<String> {
+    rows.iter().map(|row| row.to_string()).collect()
+}
+
+#[test]
+fn test_solid_colors() {
+    let mut converter = AsciiConverter::new_dense(8, 4);
+    for (color, row) in [
+        ([0, 0, 0], "        "),
+        ([255, 255, 255], "@@@@@@@@"),
+        ([128, 128, 128], "********"),
+    ] {
+        let expected = golden(&[row; 4]);
+        for grid in all_paths(&mut converter, &solid(16, 8, color), 16, 8) {
+            assert_eq!(grid, expected, "solid {color:?}");
+        }
+    }
+}
+
+#[test]
+fn test_saturated_red_luma_differs_between_paths() {
+    // The monochrome image paths take luma from `image` (Rec. 709 weights), the others
+    // from `pixel_luma` (Rec. 601), so pure red lands on neighboring characters
+    let mut converter = AsciiConverter::new_dense(4, 2);
+    let [image, rgb, image_colored, direct] =
+        all_paths(&mut converter, &solid(16, 8, [255, 0, 0]), 16, 8);
+    assert_eq!(image, golden(&[",,,,"; 2]));
+    assert_eq!(rgb, golden(&[",,,,"; 2]));
+    assert_eq!(image_colored, golden(&["::::"; 2]));
+    assert_eq!(direct, golden(&["::::"; 2]));
+}
+
+#[test]
+fn test_gradient_walks_the_ramp() {
+    let mut converter = AsciiConverter::new_dense(12, 2);
+    for grid in all_paths(&mut converter, &gradient(12, 2), 12, 2) {
+        assert_eq!(grid, golden(&[" .,:;+*?%S#@"; 2]));
+    }
+}
+
+#[test]
+fn test_checkerboard() {
+    // Direct conversion samples one pixel per cell, which lands on alternating squares;
+    // the image paths resize with a triangle filter and blur them together
+    let mut converter = AsciiConverter::new_dense(4, 4);
+    let [image, rgb, image_colored, direct] =
+        all_paths(&mut converter, &checkerboard(8, 8, 2), 8, 8);
+    let filtered = golden(&["%;?:", ";?;?", "?;?;", ":?;%"]);
+    assert_eq!(image, filtered);
+    assert_eq!(rgb, filtered);
+    assert_eq!(image_colored, filtered);
+    assert_eq!(direct, golden(&["@ @ ", " @ @", "@ @ ", " @ @"]));
+}
+
+#[test]
+fn test_truncated_index_rounding() {
+    // The mapping from before rounding was configurable, one character denser in between
+    let mut converter = AsciiConverter::new_dense(4, 4);
+    converter.set_index_rounding(IndexRounding::Truncate);
+    let [image, rgb, image_colored, _] = all_paths(&mut converter, &checkerboard(8, 8, 2), 8, 8);
+    let filtered = golden(&["S;%:", ";?+%", "%+?;", ":%;S"]);
+    assert_eq!(image, filtered);
+    assert_eq!(rgb, filtered);
+    assert_eq!(image_colored, filtered);
+}
+
+#[test]
+fn test_colored_gradient() {
+    let mut converter = AsciiConverter::new_dense(4, 1);
+    converter.set_color_enabled(true);
+    let data = gradient(4, 1);
+    let expected = concat!(
+        "\x1b[38;2;0;0;0m ",
+        "\x1b[38;2;85;85;85m;",
+        "\x1b[38;2;170;170;170m?",
+        "\x1b[38;2;255;255;255m@",
+        "\x1b[0m",
+    );
+    assert_eq!(
+        to_ansi_string(&converter.convert_rgb_frame_direct(&data, 4, 1)),
+        expected
+    );
+    assert_eq!(
+        to_ansi_string(&converter.convert_rgb_frame_colored(&data, 4, 1)),
+        expected
+    );
+}
+
+#[test]
+fn test_colored_checkerboard_keeps_blended_colors() {
+    let mut converter = AsciiConverter::new_dense(2, 1);
+    converter.set_color_enabled(true);
+    let data = checkerboard(4, 2, 2);
+    assert_eq!(
+        to_ansi_string(&converter.convert_rgb_frame_direct(&data, 4, 2)),
+        "\x1b[38;2;255;255;255m@\x1b[38;2;0;0;0m \x1b[0m"
+    );
+    assert_eq!(
+        to_ansi_string(&converter.convert_image_colored(&image(4, 2, &data))),
+        "\x1b[38;2;219;219;219mS\x1b[38;2;36;36;36m,\x1b[0m"
+    );
+}
+
+#[test]
+fn test_one_by_one_grid() {
+    let mut converter = AsciiConverter::new_dense(1, 1);
+    converter.set_color_enabled(true);
+    converter.set_filter(FilterKind::Nearest);
+    let data = gradient(16, 8);
+    // Nearest direct conversion reads the top-left pixel, the image paths average the whole
+    // frame
+    assert_eq!(
+        to_ansi_string(&converter.convert_rgb_frame_direct(&data, 16, 8)),
+        "\x1b[38;2;0;0;0m \x1b[0m"
+    );
+    assert_eq!(
+        to_ansi_string(&converter.convert_rgb_frame_colored(&data, 16, 8)),
+        "\x1b[38;2;127;127;127m+\x1b[0m"
+    );
+    assert_eq!(converter.convert_rgb_frame(&data, 16, 8), golden(&["+"]));
+}
+
+#[test]
+fn test_short_ramps_are_ignored() {
+    // A ramp needs at least two characters, so the character set stays in use
+    for ramp in ["", "#"] {
+        let mut converter = AsciiConverter::new_dense(12, 1);
+        converter.set_custom_ramp(Some(ramp));
+        assert!(converter.custom_ramp().is_none());
+        let grid = converter.convert_rgb_frame_direct(&gradient(12, 1), 12, 1);
+        assert_eq!(chars(&grid), golden(&[" .,:;+*?%S#@"]));
+    }
+}
+
+#[test]
+fn test_empty_frame_gives_blank_grid() {
+    let mut converter = AsciiConverter::new_dense(4, 2);
+    let grid = converter.convert_rgb_frame_direct(&[], 0, 0);
+    assert_eq!(chars(&grid), golden(&["    "; 2]));
+}
+
+#[test]
+fn test_mismatched_buffer_length() {
+    let mut converter = AsciiConverter::new_dense(4, 2);
+    let short = [0u8; 5];
+    assert_eq!(
+        converter.convert_rgb_frame(&short, 2, 2),
+        golden(&["Invalid frame data"])
+    );
+    assert_eq!(
+        chars(&converter.convert_rgb_frame_colored(&short, 2, 2)),
+        golden(&["E"])
+    );
+    assert_eq!(
+        chars(&converter.convert_rgb_frame_direct(&short, 2, 2)),
+        golden(&["E"])
+    );
+    // One byte too many is rejected as well
+    let long = [0u8; 13];
+    assert_eq!(
+        chars(&converter.convert_rgb_frame_direct(&long, 2, 2)),
+        golden(&["E"])
+    );
+}
+
+#[test]
+fn test_half_blocks_with_backgrounds() {
+    // Top row white, bottom row black, then a flat gray cell whose background is unset
+    let mut converter = AsciiConverter::new_dense(2, 1);
+    converter.set_color_enabled(true);
+    converter.set_cell_mode(CellMode::HalfBlock);
+    let data = frame(2, 2, |x, y| match (x, y) {
+        (0, 0) => [255; 3],
+        (0, _) => [0; 3],
+        _ => [128; 3],
+    });
+    assert_eq!(
+        to_ansi_string(&converter.convert_rgb_frame_direct(&data, 2, 2)),
+        concat!(
+            "\x1b[38;2;255;255;255m\x1b[48;2;0;0;0m▀",
+            "\x1b[38;2;128;128;128m\x1b[49m█",
+            "\x1b[0m",
+        )
+    );
+}
+
+#[test]
+fn test_gamma_brightens_every_path_alike() {
+    let mut converter = AsciiConverter::new_dense(12, 2);
+    for _ in 0..10 {
+        converter.increase_gamma();
+    }
+    assert_eq!(converter.gamma(), 2.0);
+    for grid in all_paths(&mut converter, &gradient(12, 2), 12, 2) {
+        assert_eq!(grid, golden(&[" :+*??%SS##@"; 2]));
+    }
+}
+
+#[test]
+fn test_invert_matches_a_negative_on_every_path() {
+    let frame = gradient(12, 2);
+    let negative: Vec<u8> = frame.iter().map(|channel| 255 - channel).collect();
+    let mut converter = AsciiConverter::new_dense(12, 2);
+    let expected = all_paths(&mut converter, &negative, 12, 2);
+    converter.toggle_invert();
+    assert!(converter.invert());
+    assert_eq!(all_paths(&mut converter, &frame, 12, 2), expected);
+    assert_eq!(expected[0], golden(&["@#S%?*+;:,. "; 2]));
+}
+
+#[test]
+fn test_edge_detect_outlines_steps_on_every_path() {
+    // Black left half, white right half
+    let frame = frame(12, 2, |x, _| [if x < 6 { 0 } else { 255 }; 3]);
+    let mut converter = AsciiConverter::new_dense(12, 2);
+    converter.toggle_edge_detect();
+    for grid in all_paths(&mut converter, &frame, 12, 2) {
+        assert_eq!(grid, golden(&["     @@     "; 2]));
+    }
+}